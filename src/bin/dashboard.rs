@@ -1,55 +1,83 @@
 #![cfg_attr(windows, windows_subsystem = "windows")]
 
 use std::{
+    borrow::Cow,
     collections::HashSet,
+    path::{Path, PathBuf},
     sync::{
         Arc, Mutex,
         atomic::{AtomicBool, AtomicIsize, Ordering},
         mpsc::{self, Receiver, TryRecvError},
     },
     thread::{self, JoinHandle},
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime},
 };
 
 use anyhow::{Result, anyhow};
-use chrono::{Duration as ChronoDuration, Local, NaiveDate};
+use chrono::{DateTime, Duration as ChronoDuration, Local, NaiveDate, Utc};
 use eframe::egui::epaint::Shadow;
 use eframe::egui::{
-    self, Color32, Frame, Grid, Margin, RichText, Rounding, ScrollArea, Stroke, Vec2b,
-    ViewportCommand, style::Visuals,
+    self, Color32, Frame, Grid, Key, KeyboardShortcut, Margin, Modifiers, RichText, Rounding,
+    ScrollArea, Stroke, Vec2b, ViewportCommand, style::Visuals,
 };
 use egui_plot::{Bar, BarChart, Legend, Plot, PlotBounds, PlotPoint};
 use rfd::FileDialog;
-use star_citizen_playtime::leaderboard::{LeaderboardClient, LeaderboardEntry};
-use star_citizen_playtime::monitor::{Monitor, MonitorSnapshot};
-use star_citizen_playtime::settings::{AppSettings, SettingsStore};
+use star_citizen_playtime::charts;
+use star_citizen_playtime::leaderboard::{
+    DEFAULT_PING_TIMEOUT, LeaderboardClient, LeaderboardEntry, LeaderboardPeriod, LeaderboardRank,
+    MAX_USERNAME_CHARS, MonthlyTotal, RateLimited, pseudonymize_username,
+};
+use star_citizen_playtime::local_api::LocalApi;
+use star_citizen_playtime::monitor::{
+    DEFAULT_WINDOW_TITLE, MatchMode, Monitor, MonitorSnapshot, ProcessMatcher,
+};
+use star_citizen_playtime::notifications;
 #[cfg(windows)]
+use star_citizen_playtime::settings::MAX_STARTUP_DELAY_SECONDS;
+use star_citizen_playtime::settings::{AppSettings, MAX_UTC_OFFSET_MINUTES, SettingsStore, Theme};
+#[cfg(any(windows, target_os = "macos", target_os = "linux"))]
 use star_citizen_playtime::startup;
+#[cfg(windows)]
+use star_citizen_playtime::startup::StartupMethod;
 use star_citizen_playtime::storage::{
-    Analytics, Session, SessionStore, active_session_minutes, compute_analytics, format_duration,
+    Analytics, PatchRelease, Session, SessionStore, active_minutes_on_day, active_session_minutes,
+    compute_analytics_at, current_streak, date_at, format_duration, format_duration_precise,
+    longest_streak, minutes_on_day, minutes_per_patch, parse_local_datetime, parse_tags,
+    sessions_are_adjacent,
 };
+use star_citizen_playtime::watch::{DEFAULT_DEBOUNCE, DataWatcher};
+use uuid::Uuid;
 
+#[cfg(any(windows, target_os = "macos", target_os = "linux"))]
+use std::env;
 #[cfg(windows)]
-use std::{env, process::Command};
+use std::process::Command;
 
 #[cfg(windows)]
-use sysinfo::{Signal, System, get_current_pid};
+use star_citizen_playtime::monitor::process_refresh_kind;
+#[cfg(windows)]
+use star_citizen_playtime::storage::ActiveSession;
+#[cfg(windows)]
+use star_citizen_playtime::storage::{LOG_ROTATE_BYTES, LOG_ROTATE_KEEP, rotate_log};
+#[cfg(windows)]
+use sysinfo::{RefreshKind, Signal, System, get_current_pid};
 
 #[cfg(windows)]
 use raw_window_handle::{HasWindowHandle, RawWindowHandle};
 
 #[cfg(windows)]
 use windows_sys::Win32::{
-    Foundation::HWND,
+    Foundation::{ERROR_ALREADY_EXISTS, GetLastError, HANDLE, HWND},
+    System::Threading::CreateMutexW,
     UI::WindowsAndMessaging::{
-        PostMessageW, SW_RESTORE, SW_SHOW, SetForegroundWindow, ShowWindow, WM_CLOSE,
+        FindWindowW, PostMessageW, SW_RESTORE, SW_SHOW, SetForegroundWindow, ShowWindow, WM_CLOSE,
     },
 };
 
 #[cfg(windows)]
 use tray_icon::{
     ClickType, Icon, TrayIcon, TrayIconBuilder, TrayIconEvent,
-    menu::{Menu, MenuEvent, MenuId, MenuItemBuilder, PredefinedMenuItem},
+    menu::{Menu, MenuEvent, MenuId, MenuItem, MenuItemBuilder, PredefinedMenuItem},
 };
 
 #[cfg(windows)]
@@ -62,9 +90,234 @@ enum DashboardTab {
     Insights,
 }
 
+/// Column the recent-sessions grid is ordered by.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum RecentSort {
+    Start,
+    Duration,
+}
+
+/// Row counts offered for the recent-sessions grid; `None` shows every session.
+const RECENT_ROW_CHOICES: [Option<usize>; 3] = [Some(12), Some(25), None];
+
+struct NoteEditor {
+    id: Uuid,
+    started: String,
+    text: String,
+    tags: String,
+}
+
+/// Input for backfilling a session that was not tracked.
+#[derive(Default)]
+struct SessionForm {
+    start: String,
+    end: String,
+    note: String,
+    allow_overlap: bool,
+}
+
+/// Filter for the recent-sessions grid. Dates are inclusive and compared against the day a
+/// session started.
+#[derive(Default)]
+struct SessionFilter {
+    text: String,
+    from: String,
+    to: String,
+}
+
+impl SessionFilter {
+    fn is_active(&self) -> bool {
+        !self.text.trim().is_empty() || !self.from.trim().is_empty() || !self.to.trim().is_empty()
+    }
+
+    /// Parses a date field; `Ok(None)` when it is blank.
+    fn parse_day(value: &str) -> Result<Option<NaiveDate>, String> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Ok(None);
+        }
+        NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map(Some)
+            .map_err(|_| format!("\"{value}\" is not a YYYY-MM-DD date"))
+    }
+
+    /// The sessions that pass the filter, or an error for an unreadable date.
+    fn apply<'a>(&self, sessions: &'a [Session]) -> Result<Vec<&'a Session>, String> {
+        let from = Self::parse_day(&self.from)?;
+        let to = Self::parse_day(&self.to)?;
+        let needle = self.text.trim().to_lowercase();
+        Ok(sessions
+            .iter()
+            .filter(|session| {
+                let day = session.start.date_naive();
+                from.is_none_or(|from| day >= from)
+                    && to.is_none_or(|to| day <= to)
+                    && (needle.is_empty()
+                        || session.note.to_lowercase().contains(&needle)
+                        || session
+                            .tags
+                            .iter()
+                            .any(|tag| tag.to_lowercase().contains(&needle)))
+            })
+            .collect())
+    }
+}
+
+/// Input for adding a patch release date on the Insights tab.
+#[derive(Default)]
+struct PatchForm {
+    label: String,
+    start: String,
+}
+
+/// Choices on the first-run introduction window.
+#[derive(Default)]
+struct OnboardingForm {
+    username: String,
+    run_on_login: bool,
+}
+
+/// Data behind a chart whose "PNG" button was clicked, re-rendered with plotters on export.
+struct ChartExport {
+    id: String,
+    title: String,
+    labels: Vec<String>,
+    values: Vec<f64>,
+    series_name: String,
+    color: Color32,
+}
+
+/// Destructive actions that wait for the user to confirm in a dialog.
+enum ConfirmAction {
+    MergeSessions(Vec<Uuid>),
+    DeleteSession {
+        id: Uuid,
+        started: String,
+    },
+    LeaveLeaderboard(String),
+    /// Elapsed time of the session in progress, if any, when the button was clicked.
+    ClearActiveMarker(Option<String>),
+}
+
+impl ConfirmAction {
+    fn message(&self) -> String {
+        match self {
+            ConfirmAction::MergeSessions(ids) => format!(
+                "The {} selected sessions are not next to each other; sessions in between will be left alone and the gaps will not be counted. Merge anyway?",
+                ids.len()
+            ),
+            ConfirmAction::DeleteSession { started, .. } => {
+                format!("Delete the session started {started}? This cannot be undone.")
+            }
+            ConfirmAction::LeaveLeaderboard(username) => format!(
+                "Remove {username} from the leaderboard? Leaderboard sync will be turned off so the entry is not added back."
+            ),
+            ConfirmAction::ClearActiveMarker(Some(elapsed)) => format!(
+                "Clear the active session marker? The session in progress ({elapsed} so far) will be discarded and not saved. If the game is still running, a new session starts from now."
+            ),
+            ConfirmAction::ClearActiveMarker(None) => {
+                "Clear the active session marker? A session in progress will be discarded and not saved. If the game is still running, a new session starts from now."
+                    .to_string()
+            }
+        }
+    }
+
+    fn confirm_label(&self) -> &'static str {
+        match self {
+            ConfirmAction::MergeSessions(_) => "Merge",
+            ConfirmAction::DeleteSession { .. } => "Delete",
+            ConfirmAction::LeaveLeaderboard(_) => "Remove",
+            ConfirmAction::ClearActiveMarker(_) => "Clear",
+        }
+    }
+}
+
+#[cfg(windows)]
+const STARTUP_LABEL: &str = "Start dashboard with Windows";
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+const STARTUP_LABEL: &str = "Start dashboard at login";
+
+/// Longest the cached analytics are reused while `sessions.json` is unchanged; rolling windows
+/// and streaks depend on the current time, so they still need an occasional recompute.
+const ANALYTICS_MAX_AGE: Duration = Duration::from_secs(60);
+
+#[cfg(windows)]
+const TRAY_LOG_FILE: &str = "sc_playtime_tray.log";
+
+/// Number of trailing lines shown by the in-app tray log viewer.
+#[cfg(windows)]
+const TRAY_LOG_TAIL_LINES: usize = 200;
+
+/// The registered startup command if it does not launch this executable. Errors are treated
+/// as "nothing to fix"; the startup checkbox reports registry problems on its own.
+#[cfg(windows)]
+fn stale_startup_command(method: StartupMethod) -> Option<String> {
+    if method != StartupMethod::RunKey {
+        return None;
+    }
+    let command = startup::installed_command().ok()??;
+    let exe = env::current_exe().ok()?;
+    (!startup::command_targets(&command, &exe)).then_some(command)
+}
+
+/// Set to anything but `0` to log every tray event, regardless of the setting.
+#[cfg(windows)]
+const TRAY_DEBUG_ENV: &str = "SC_PLAYTIME_TRAY_DEBUG";
+
+/// Whether routine tray events are written to the tray log (errors always are).
+#[cfg(windows)]
+static TRAY_DEBUG: AtomicBool = AtomicBool::new(false);
+
+#[cfg(windows)]
+fn set_tray_debug(enabled: bool) {
+    let forced = env::var(TRAY_DEBUG_ENV).is_ok_and(|value| !value.is_empty() && value != "0");
+    TRAY_DEBUG.store(enabled || forced, Ordering::Relaxed);
+}
+
+#[cfg(windows)]
+fn tray_log_path() -> PathBuf {
+    env::temp_dir().join(TRAY_LOG_FILE)
+}
+
+/// Reads the last `lines` lines of a log without loading all of it; only the final 64 KiB
+/// are read, which is plenty for a few hundred lines.
+#[cfg(windows)]
+fn read_log_tail(path: &Path, lines: usize) -> std::io::Result<String> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    const TAIL_BYTES: u64 = 64 * 1024;
+    let mut file = std::fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    let offset = len.saturating_sub(TAIL_BYTES);
+    file.seek(SeekFrom::Start(offset))?;
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf)?;
+    let text = String::from_utf8_lossy(&buf);
+    let mut all: Vec<&str> = text.lines().collect();
+    if offset > 0 && !all.is_empty() {
+        // The first line was cut by the seek.
+        all.remove(0);
+    }
+    let start = all.len().saturating_sub(lines);
+    Ok(all[start..].join("\n"))
+}
+
+/// Tray icon color while a session is being tracked, matching the "Tracking" banner.
+#[cfg(windows)]
+const TRAY_TRACKING_COLOR: [u8; 3] = [94, 201, 146];
+
+/// Grayscale tray icon template, tinted at runtime by [`TrayController::tinted_icon`].
+#[cfg(windows)]
+const TRAY_ICON_PNG: &[u8] = include_bytes!("../../assets/tray_icon.png");
+
 #[cfg(windows)]
 struct TrayController {
-    _icon: TrayIcon,
+    icon: TrayIcon,
+    color: [u8; 3],
+    tracking: bool,
+    /// Read-only "Today: ..." and "Total: ..." lines at the top of the menu.
+    today_item: MenuItem,
+    total_item: MenuItem,
     event_rx: std::sync::mpsc::Receiver<TrayAction>,
     repaint_keepalive: Arc<AtomicBool>,
     _window_handle: Arc<AtomicIsize>,
@@ -72,12 +325,25 @@ struct TrayController {
 
 #[cfg(windows)]
 impl TrayController {
-    fn new(repaint_ctx: &egui::Context, window_handle: Arc<AtomicIsize>) -> Option<Self> {
+    fn new(
+        repaint_ctx: &egui::Context,
+        window_handle: Arc<AtomicIsize>,
+        color: [u8; 3],
+    ) -> Option<Self> {
         Self::log_event("initializing tray controller");
 
         let show_id = MenuId::new("open_dashboard");
         let exit_id = MenuId::new("quit_dashboard");
 
+        let today_item = MenuItemBuilder::new()
+            .text("Today: -")
+            .enabled(false)
+            .build();
+        let total_item = MenuItemBuilder::new()
+            .text("Total: -")
+            .enabled(false)
+            .build();
+
         let show_item = MenuItemBuilder::new()
             .id(show_id.clone())
             .text("Open Dashboard")
@@ -91,23 +357,34 @@ impl TrayController {
         let separator = PredefinedMenuItem::separator();
 
         let menu = Menu::new();
+        let playtime_separator = PredefinedMenuItem::separator();
+        for item in [&today_item, &total_item] {
+            if let Err(err) = menu.append(item) {
+                Self::log_error(&format!("failed to append playtime menu item: {err:?}"));
+                return None;
+            }
+        }
+        if let Err(err) = menu.append(&playtime_separator) {
+            Self::log_error(&format!("failed to append separator: {err:?}"));
+            return None;
+        }
         if let Err(err) = menu.append(&show_item) {
-            Self::log_event(&format!("failed to append show menu item: {err:?}"));
+            Self::log_error(&format!("failed to append show menu item: {err:?}"));
             return None;
         }
         if let Err(err) = menu.append(&separator) {
-            Self::log_event(&format!("failed to append separator: {err:?}"));
+            Self::log_error(&format!("failed to append separator: {err:?}"));
             return None;
         }
         if let Err(err) = menu.append(&exit_item) {
-            Self::log_event(&format!("failed to append exit menu item: {err:?}"));
+            Self::log_error(&format!("failed to append exit menu item: {err:?}"));
             return None;
         }
 
-        let icon = match Icon::from_rgba(Self::icon_pixels(), 16, 16) {
+        let icon = match Self::build_icon(color, false) {
             Ok(icon) => icon,
             Err(err) => {
-                Self::log_event(&format!("failed to create tray icon image: {err:?}"));
+                Self::log_error(&format!("failed to create tray icon image: {err:?}"));
                 return None;
             }
         };
@@ -120,7 +397,7 @@ impl TrayController {
         {
             Ok(icon) => icon,
             Err(err) => {
-                Self::log_event(&format!("failed to build tray icon: {err:?}"));
+                Self::log_error(&format!("failed to build tray icon: {err:?}"));
                 return None;
             }
         };
@@ -145,7 +422,7 @@ impl TrayController {
                                 && matches!(event.click_type, ClickType::Left | ClickType::Double)
                             {
                                 if let Err(err) = tx.send(TrayAction::Show) {
-                                    TrayController::log_event(&format!(
+                                    TrayController::log_error(&format!(
                                         "failed to enqueue TrayAction::Show: {err:?}"
                                     ));
                                     break;
@@ -156,7 +433,7 @@ impl TrayController {
                             }
                         }
                         Err(err) => {
-                            TrayController::log_event(&format!(
+                            TrayController::log_error(&format!(
                                 "tray icon event channel closed: {err:?}"
                             ));
                             break;
@@ -183,7 +460,7 @@ impl TrayController {
                             ));
                             if event.id == show_id {
                                 if let Err(err) = tx.send(TrayAction::Show) {
-                                    TrayController::log_event(&format!(
+                                    TrayController::log_error(&format!(
                                         "failed to enqueue TrayAction::Show: {err:?}"
                                     ));
                                     break;
@@ -193,7 +470,7 @@ impl TrayController {
                                 TrayController::show_window(&menu_window_handle);
                             } else if event.id == exit_id {
                                 if let Err(err) = tx.send(TrayAction::Exit) {
-                                    TrayController::log_event(&format!(
+                                    TrayController::log_error(&format!(
                                         "failed to enqueue TrayAction::Exit: {err:?}"
                                     ));
                                     break;
@@ -204,7 +481,7 @@ impl TrayController {
                             }
                         }
                         Err(err) => {
-                            TrayController::log_event(&format!(
+                            TrayController::log_error(&format!(
                                 "menu event channel closed: {err:?}"
                             ));
                             break;
@@ -220,9 +497,11 @@ impl TrayController {
             let keepalive_flag_clone = keepalive_flag.clone();
             thread::spawn(move || {
                 TrayController::log_event("repaint keepalive thread started");
+                // The tray threads repaint on every event; this is only a safety net so queued
+                // actions are still drained while the window is minimized.
                 while keepalive_flag_clone.load(Ordering::SeqCst) {
                     repaint_ctx_keepalive.request_repaint();
-                    thread::sleep(Duration::from_millis(250));
+                    thread::sleep(Duration::from_secs(2));
                 }
                 TrayController::log_event("repaint keepalive thread exiting");
             });
@@ -231,23 +510,82 @@ impl TrayController {
         Self::log_event("tray controller initialized successfully");
 
         Some(Self {
-            _icon: icon,
+            icon,
+            color,
+            tracking: false,
+            today_item,
+            total_item,
             event_rx: rx,
             repaint_keepalive: keepalive_flag,
             _window_handle: window_handle,
         })
     }
 
-    fn icon_pixels() -> Vec<u8> {
-        let mut data = Vec::with_capacity(16 * 16 * 4);
-        for _ in 0..(16 * 16) {
-            data.extend_from_slice(&[0x42, 0x7A, 0xC4, 0xFF]);
+    fn build_icon(color: [u8; 3], tracking: bool) -> Result<Icon> {
+        let color = if tracking { TRAY_TRACKING_COLOR } else { color };
+        let (rgba, width, height) = Self::tinted_icon(color)?;
+        Ok(Icon::from_rgba(rgba, width, height)?)
+    }
+
+    /// Decodes the embedded icon template and tints it with `color`.
+    ///
+    /// The template is grayscale: black areas take the tint color, white areas stay white and
+    /// grays blend between the two, so one asset serves every color setting.
+    fn tinted_icon(color: [u8; 3]) -> Result<(Vec<u8>, u32, u32)> {
+        let template = image::load_from_memory_with_format(TRAY_ICON_PNG, image::ImageFormat::Png)?
+            .into_rgba8();
+        let (width, height) = template.dimensions();
+        let mut rgba = template.into_raw();
+        for pixel in rgba.chunks_exact_mut(4) {
+            let white = pixel[0] as u16;
+            for (channel, tint) in pixel[..3].iter_mut().zip(color) {
+                *channel = ((tint as u16 * (255 - white) + 255 * white) / 255) as u8;
+            }
+        }
+        Ok((rgba, width, height))
+    }
+
+    fn set_playtime(&self, today: &str, total: &str) {
+        if self.today_item.text() != today {
+            self.today_item.set_text(today);
+        }
+        if self.total_item.text() != total {
+            self.total_item.set_text(total);
+        }
+    }
+
+    /// Rebuilds the icon when the color setting or the tracking state changes.
+    fn set_appearance(&mut self, color: [u8; 3], tracking: bool) {
+        if self.color == color && self.tracking == tracking {
+            return;
+        }
+        self.color = color;
+        self.tracking = tracking;
+        match Self::build_icon(color, tracking) {
+            Ok(icon) => {
+                if let Err(err) = self.icon.set_icon(Some(icon)) {
+                    Self::log_error(&format!("failed to update tray icon: {err:?}"));
+                }
+            }
+            Err(err) => Self::log_error(&format!("failed to create tray icon image: {err:?}")),
         }
-        data
     }
 
+    /// Verbose tray tracing; only written when tray debug logging is enabled.
     fn log_event(msg: &str) {
-        let path = std::env::temp_dir().join("sc_playtime_tray.log");
+        if TRAY_DEBUG.load(Ordering::Relaxed) {
+            Self::append_log(msg);
+        }
+    }
+
+    /// Failures are always logged so tray problems can be diagnosed without debug logging.
+    fn log_error(msg: &str) {
+        Self::append_log(msg);
+    }
+
+    fn append_log(msg: &str) {
+        let path = tray_log_path();
+        let _ = rotate_log(&path, LOG_ROTATE_BYTES, LOG_ROTATE_KEEP);
         if let Ok(mut file) = std::fs::OpenOptions::new()
             .create(true)
             .append(true)
@@ -265,12 +603,8 @@ impl TrayController {
 
     fn show_window(handle: &Arc<AtomicIsize>) {
         match Self::hwnd_from(handle) {
-            Some(hwnd) => unsafe {
-                ShowWindow(hwnd, SW_RESTORE);
-                ShowWindow(hwnd, SW_SHOW);
-                let _ = SetForegroundWindow(hwnd);
-            },
-            None => Self::log_event("show requested but window handle unavailable"),
+            Some(hwnd) => bring_to_front(hwnd),
+            None => Self::log_error("show requested but window handle unavailable"),
         }
     }
 
@@ -279,7 +613,7 @@ impl TrayController {
             Some(hwnd) => unsafe {
                 let _ = PostMessageW(hwnd, WM_CLOSE, 0, 0);
             },
-            None => Self::log_event("close requested but window handle unavailable"),
+            None => Self::log_error("close requested but window handle unavailable"),
         }
     }
 
@@ -317,14 +651,90 @@ enum TrayAction {
 #[cfg(windows)]
 const VELOPACK_FEED_URL: &str = "https://playtracker.al1e.dev/releases/win";
 
+const WINDOW_TITLE: &str = "Star Citizen Playtime";
+
+#[cfg(windows)]
+const INSTANCE_MUTEX_NAME: &str = "Local\\StarCitizenPlaytimeDashboard";
+
+#[cfg(windows)]
+fn bring_to_front(hwnd: HWND) {
+    unsafe {
+        ShowWindow(hwnd, SW_RESTORE);
+        ShowWindow(hwnd, SW_SHOW);
+        let _ = SetForegroundWindow(hwnd);
+    }
+}
+
+/// Held for the dashboard's lifetime so later launches can tell it is already running.
+struct InstanceGuard {
+    #[cfg(windows)]
+    _mutex: HANDLE,
+    #[cfg(not(windows))]
+    _lock: std::fs::File,
+}
+
+/// Registers this process as the running dashboard, or returns `None` when another instance
+/// already is. Windows uses a named mutex; elsewhere a lock file in the data directory.
+#[cfg(windows)]
+fn claim_single_instance(_data_dir: &Path) -> Result<Option<InstanceGuard>> {
+    let name: Vec<u16> = INSTANCE_MUTEX_NAME.encode_utf16().chain(Some(0)).collect();
+    let mutex = unsafe { CreateMutexW(std::ptr::null(), 0, name.as_ptr()) };
+    if mutex == 0 {
+        return Err(anyhow!(
+            "failed to create instance mutex: {}",
+            std::io::Error::last_os_error()
+        ));
+    }
+    if unsafe { GetLastError() } == ERROR_ALREADY_EXISTS {
+        return Ok(None);
+    }
+    Ok(Some(InstanceGuard { _mutex: mutex }))
+}
+
+#[cfg(not(windows))]
+fn claim_single_instance(data_dir: &Path) -> Result<Option<InstanceGuard>> {
+    let path = data_dir.join("dashboard.lock");
+    let file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(&path)
+        .map_err(|err| anyhow!("failed to open {}: {err}", path.display()))?;
+    match file.try_lock() {
+        Ok(()) => Ok(Some(InstanceGuard { _lock: file })),
+        Err(std::fs::TryLockError::WouldBlock) => Ok(None),
+        Err(std::fs::TryLockError::Error(err)) => {
+            Err(anyhow!("failed to lock {}: {err}", path.display()))
+        }
+    }
+}
+
+/// Shows the window of the dashboard that is already running, even if it is hidden in the tray.
+#[cfg(windows)]
+fn focus_existing_instance() {
+    let title: Vec<u16> = WINDOW_TITLE.encode_utf16().chain(Some(0)).collect();
+    let hwnd = unsafe { FindWindowW(std::ptr::null(), title.as_ptr()) };
+    if hwnd != 0 {
+        bring_to_front(hwnd);
+    }
+}
+
+#[cfg(not(windows))]
+fn focus_existing_instance() {
+    eprintln!("{WINDOW_TITLE} is already running.");
+}
+
 const SUPPORT_DISCORD_URL: &str = "https://discord.gg/ebBzRMpnnV";
 
+/// How long a fetched board is reused before refreshing or switching tabs hits the network again.
+const LEADERBOARD_CACHE_TTL: Duration = Duration::from_secs(60);
+
 #[cfg(windows)]
 fn terminate_stale_instances() -> Result<Vec<String>> {
     let current_pid =
         get_current_pid().map_err(|err| anyhow!("failed to get current pid: {err}"))?;
-    let mut system = System::new_all();
-    system.refresh_processes();
+    let system =
+        System::new_with_specifics(RefreshKind::new().with_processes(process_refresh_kind()));
 
     let self_name = match env::current_exe()
         .ok()
@@ -387,9 +797,19 @@ fn main() -> Result<()> {
     }
 
     let snapshot = Arc::new(Mutex::new(MonitorSnapshot::default()));
-    let store = Arc::new(SessionStore::new()?);
+    let store = Arc::new(match data_dir_arg() {
+        Some(dir) => SessionStore::with_dir(dir)?,
+        None => SessionStore::new()?,
+    });
     let settings_store = SettingsStore::new(store.data_dir().to_path_buf());
 
+    // Launching again (e.g. from the Start menu while the login copy sits in the tray)
+    // brings the running window forward instead of starting a second monitor.
+    let Some(_instance) = claim_single_instance(store.data_dir())? else {
+        focus_existing_instance();
+        return Ok(());
+    };
+
     let mut status_notes = Vec::new();
 
     #[cfg(windows)]
@@ -421,9 +841,9 @@ fn main() -> Result<()> {
         }
     };
 
-    #[cfg(windows)]
+    #[cfg(any(windows, target_os = "macos", target_os = "linux"))]
     {
-        match startup::is_installed() {
+        match startup::is_installed_with(initial_settings.startup_method) {
             Ok(installed) => initial_settings.run_on_login = installed,
             Err(err) => {
                 let msg = format!("Failed to query startup status: {err}");
@@ -440,12 +860,14 @@ fn main() -> Result<()> {
     };
 
     let mut native_options = eframe::NativeOptions::default();
+    // `ensure_style` applies the theme itself, including following the OS for `Theme::System`.
+    native_options.follow_system_theme = false;
     #[cfg(windows)]
     {
         native_options.viewport = native_options.viewport.clone().with_taskbar(false);
     }
     eframe::run_native(
-        "Star Citizen Playtime",
+        WINDOW_TITLE,
         native_options,
         Box::new(move |cc| {
             Box::new(PlaytimeApp::new(
@@ -465,20 +887,48 @@ struct PlaytimeApp {
     store: Arc<SessionStore>,
     snapshot: Arc<Mutex<MonitorSnapshot>>,
     stop_flag: Arc<AtomicBool>,
-    monitor_handle: Option<JoinHandle<()>>,
+    pause_flag: Arc<AtomicBool>,
+    monitor_handle: Option<JoinHandle<Result<()>>>,
+    /// Why the monitor thread ended without being asked to; shown as a banner until restarted.
+    monitor_failure: Option<String>,
+    local_api: Option<LocalApi>,
+    /// Reloads sessions as soon as another process writes them.
+    data_watcher: Option<DataWatcher>,
     sessions: Vec<Session>,
     analytics: Option<Analytics>,
     last_refresh: Instant,
+    /// `sessions.json` stamp the cached sessions and analytics were computed from.
+    sessions_stamp: Option<(SystemTime, u64)>,
+    sessions_loaded_at: Instant,
     refresh_interval: Duration,
     settings_store: SettingsStore,
     settings: AppSettings,
     pending_settings: AppSettings,
+    process_tokens_input: String,
+    milestones_input: String,
     status_message: Option<String>,
     status_since: Option<Instant>,
     selected_tab: DashboardTab,
-    style_applied: bool,
+    note_editor: Option<NoteEditor>,
+    selected_sessions: HashSet<Uuid>,
+    recent_sort: RecentSort,
+    recent_sort_descending: bool,
+    recent_rows: Option<usize>,
+    session_filter: SessionFilter,
+    session_form: SessionForm,
+    patch_form: PatchForm,
+    /// Shown once on a fresh install, until "Get started" or "Skip" is clicked.
+    onboarding: Option<OnboardingForm>,
+    pending_confirm: Option<ConfirmAction>,
+    /// Whether the current style was built for dark mode; `None` until the first frame.
+    applied_dark_mode: Option<bool>,
     leaderboard_client: Option<LeaderboardClient>,
+    /// The endpoint did not answer when the client was built, so the local board is in use.
+    leaderboard_offline: bool,
     leaderboard_entries: Vec<LeaderboardEntry>,
+    leaderboard_rank: Option<LeaderboardRank>,
+    /// Board shown on the Insights tab; submissions always include both totals.
+    leaderboard_period: LeaderboardPeriod,
     leaderboard_rx: Option<Receiver<LeaderboardSyncResult>>,
     leaderboard_inflight: bool,
     last_leaderboard_attempt: Option<Instant>,
@@ -500,6 +950,13 @@ struct PlaytimeApp {
     last_tray_tick_log: Instant,
     #[cfg(windows)]
     last_update_log: Instant,
+    /// Last lines of the tray log, loaded when "Show log" or "Reload" is clicked.
+    #[cfg(windows)]
+    tray_log_tail: Option<String>,
+    /// Registered startup command when it launches a different executable (e.g. after the
+    /// app was moved), so the settings can offer to fix it.
+    #[cfg(windows)]
+    stale_startup_command: Option<String>,
     #[cfg(windows)]
     update_manager: Option<UpdateManager>,
     #[cfg(windows)]
@@ -518,7 +975,14 @@ struct PlaytimeApp {
 struct LeaderboardSyncResult {
     message: Option<String>,
     entries: Option<Vec<LeaderboardEntry>>,
+    /// `Some(None)` when the lookup succeeded but the username is not on the board.
+    rank: Option<Option<LeaderboardRank>>,
     error: Option<String>,
+    /// Set when the service throttled the submission; the next sync waits this long.
+    retry_after: Option<Duration>,
+    /// Result of an endpoint check: `Some(None)` when the endpoint answered, `Some(Some(_))`
+    /// with the local client to switch to when it did not.
+    endpoint_check: Option<Option<LeaderboardClient>>,
 }
 
 #[cfg(windows)]
@@ -533,8 +997,17 @@ enum LeaderboardJob {
     SubmitAndFetch {
         username: String,
         total_minutes: f64,
+        monthly: MonthlyTotal,
     },
     FetchOnly,
+    Remove {
+        username: String,
+    },
+    /// Pings the endpoint, off the UI thread since it can take `DEFAULT_PING_TIMEOUT`.
+    CheckEndpoint {
+        data_dir: PathBuf,
+        retry: bool,
+    },
 }
 
 impl PlaytimeApp {
@@ -553,6 +1026,13 @@ impl PlaytimeApp {
             None => (None, None),
         };
 
+        let process_tokens_input = initial_settings.process_tokens.join(", ");
+        #[cfg(windows)]
+        let tray_color = initial_settings.tray_icon_color;
+        #[cfg(windows)]
+        set_tray_debug(initial_settings.tray_debug_logging);
+        let milestones_input = format_milestones(&initial_settings.milestone_hours);
+        let onboarding = (!initial_settings.has_onboarded).then(OnboardingForm::default);
         let window_handle = Arc::new(AtomicIsize::new(0));
 
         #[cfg(windows)]
@@ -562,27 +1042,48 @@ impl PlaytimeApp {
             store,
             snapshot,
             stop_flag: Arc::new(AtomicBool::new(false)),
+            pause_flag: Arc::new(AtomicBool::new(initial_settings.tracking_paused)),
             monitor_handle: None,
+            monitor_failure: None,
+            local_api: None,
+            data_watcher: None,
             sessions: Vec::new(),
             analytics: None,
             last_refresh: Instant::now() - refresh_interval,
+            sessions_stamp: None,
+            sessions_loaded_at: Instant::now(),
             refresh_interval,
             settings_store,
             settings: initial_settings.clone(),
             pending_settings: initial_settings,
+            process_tokens_input,
+            milestones_input,
             status_message,
             status_since,
             selected_tab: DashboardTab::Overview,
-            style_applied: false,
+            note_editor: None,
+            selected_sessions: HashSet::new(),
+            recent_sort: RecentSort::Start,
+            recent_sort_descending: true,
+            recent_rows: Some(12),
+            session_filter: SessionFilter::default(),
+            session_form: SessionForm::default(),
+            patch_form: PatchForm::default(),
+            onboarding,
+            pending_confirm: None,
+            applied_dark_mode: None,
             leaderboard_client: None,
+            leaderboard_offline: false,
             leaderboard_entries: Vec::new(),
+            leaderboard_rank: None,
+            leaderboard_period: LeaderboardPeriod::default(),
             leaderboard_rx: None,
             leaderboard_inflight: false,
             last_leaderboard_attempt: None,
             last_leaderboard_success: None,
             leaderboard_sync_interval: Duration::from_secs(300),
             #[cfg(windows)]
-            tray: TrayController::new(&cc.egui_ctx, Arc::clone(&window_handle)),
+            tray: TrayController::new(&cc.egui_ctx, Arc::clone(&window_handle), tray_color),
             #[cfg(windows)]
             first_frame: true,
             #[cfg(windows)]
@@ -598,6 +1099,10 @@ impl PlaytimeApp {
             #[cfg(windows)]
             last_update_log: Instant::now(),
             #[cfg(windows)]
+            tray_log_tail: None,
+            #[cfg(windows)]
+            stale_startup_command: stale_startup_command(initial_settings.startup_method),
+            #[cfg(windows)]
             update_manager,
             #[cfg(windows)]
             update_rx: None,
@@ -615,25 +1120,57 @@ impl PlaytimeApp {
             if app.tray.is_some() {
                 TrayController::log_event("PlaytimeApp acquired tray controller");
             } else {
-                TrayController::log_event("PlaytimeApp tray controller unavailable");
+                TrayController::log_error("PlaytimeApp tray controller unavailable");
             }
         }
+        let repaint_ctx = cc.egui_ctx.clone();
+        match DataWatcher::start(app.store.data_dir(), move || repaint_ctx.request_repaint()) {
+            Ok(watcher) => app.data_watcher = Some(watcher),
+            Err(err) => eprintln!("File watcher unavailable, using timed refresh only: {err:?}"),
+        }
         app.refresh_sessions();
         app.start_monitor();
-        app.initialize_leaderboard_client();
+        app.restart_local_api();
+        app.initialize_leaderboard_client(false);
         app.maybe_queue_initial_leaderboard_fetch();
         app
     }
 
+    /// Timed refresh: reloads only when `sessions.json` changed since the last load.
+    fn refresh_sessions_if_changed(&mut self) {
+        let stamp = self.store.sessions_stamp();
+        if stamp.is_some()
+            && stamp == self.sessions_stamp
+            && self.analytics.is_some()
+            && self.sessions_loaded_at.elapsed() < ANALYTICS_MAX_AGE
+        {
+            self.last_refresh = Instant::now();
+            return;
+        }
+        self.refresh_sessions();
+    }
+
     fn refresh_sessions(&mut self) {
-        match self.store.load_sessions() {
-            Ok(mut sessions) => {
+        // Taken before loading so a write during the load is picked up next time.
+        self.sessions_stamp = self.store.sessions_stamp();
+        self.sessions_loaded_at = Instant::now();
+        match self.store.load_sessions_with_recovery() {
+            Ok((mut sessions, recovery)) => {
+                if let Some(report) = recovery {
+                    self.set_status(report.describe());
+                }
                 sessions.sort_by_key(|s| s.start);
-                self.analytics = Some(compute_analytics(&sessions));
+                self.analytics = Some(compute_analytics_at(
+                    &sessions,
+                    self.settings.analytics_utc_offset(),
+                ));
                 self.sessions = sessions;
+                self.announce_milestones();
+                self.update_best_streak();
             }
             Err(err) => {
                 eprintln!("Failed to load sessions: {err:?}");
+                self.sessions_stamp = None;
                 self.analytics = None;
                 self.sessions.clear();
                 self.set_status(format!("Failed to load sessions: {err}"));
@@ -646,16 +1183,104 @@ impl PlaytimeApp {
         self.stop_monitor();
         self.stop_flag = Arc::new(AtomicBool::new(false));
         let stop = Arc::clone(&self.stop_flag);
+        let pause = Arc::clone(&self.pause_flag);
         let snapshot = Arc::clone(&self.snapshot);
         let poll = self.settings.poll_seconds.max(1);
         let min_session = self.settings.min_session_minutes.max(1);
+        let discard_short = self.settings.discard_short_sessions;
+        let recovered_min_session = self.settings.recovered_min_session_minutes;
+        let grace = Duration::from_secs(self.settings.grace_seconds);
+        let merge_gap = self.settings.merge_gap_minutes;
+        let idle_timeout = self.settings.idle_timeout_minutes;
+        let stale_reset = self.settings.auto_reset_stale_active_minutes;
+        let process_tokens = self.settings.process_tokens.clone();
+        let match_mode = self.settings.process_match_mode;
+        let window_title = self
+            .settings
+            .detect_window_title
+            .then(|| DEFAULT_WINDOW_TITLE.to_string());
+        let data_dir = self.store.data_dir().to_path_buf();
+        let notify_start = self.settings.notify_on_session_start;
+        let notify_end = self.settings.notify_on_session_end;
+        let status_file = self.settings.write_status_file;
+        let event_log = self.settings.write_event_log;
         self.monitor_handle = Some(thread::spawn(move || {
-            let mut monitor =
-                Monitor::new(Duration::from_secs(poll), min_session).with_status_sink(snapshot);
-            if let Err(err) = monitor.run(stop) {
+            let mut monitor = Monitor::new(Duration::from_secs(poll), min_session)
+                .with_data_dir(Some(data_dir))
+                .with_notifications(notify_start, notify_end)
+                .with_status_file(status_file)
+                .with_event_log(event_log)
+                .with_pause_flag(pause)
+                .with_process_tokens(process_tokens)
+                .with_match_mode(match_mode)
+                .with_window_title(window_title)
+                .with_discard_short_sessions(discard_short)
+                .with_recovered_min_session(recovered_min_session)
+                .with_grace_period(grace)
+                .with_merge_gap(merge_gap)
+                .with_idle_timeout(Some(idle_timeout))
+                .with_stale_active_reset(Some(stale_reset))
+                .with_status_sink(snapshot);
+            let result = monitor.run(stop);
+            if let Err(err) = &result {
                 eprintln!("Monitor loop error: {err:?}");
             }
+            result
         }));
+        self.monitor_failure = None;
+    }
+
+    /// Notices a monitor thread that returned or panicked while it was meant to be running.
+    fn check_monitor(&mut self) {
+        if self.stop_flag.load(Ordering::SeqCst)
+            || !self
+                .monitor_handle
+                .as_ref()
+                .is_some_and(|handle| handle.is_finished())
+        {
+            return;
+        }
+        let Some(handle) = self.monitor_handle.take() else {
+            return;
+        };
+        let reason = match handle.join() {
+            Ok(Ok(())) => "the monitor exited unexpectedly".to_string(),
+            Ok(Err(err)) => format!("{err:#}"),
+            Err(panic) => panic
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .map(|message| format!("the monitor crashed: {message}"))
+                .unwrap_or_else(|| "the monitor crashed".to_string()),
+        };
+        self.monitor_failure = Some(reason);
+    }
+
+    fn render_monitor_failure(&mut self, ui: &mut egui::Ui) {
+        let Some(reason) = &self.monitor_failure else {
+            return;
+        };
+        let red = Color32::from_rgb(220, 90, 90);
+        let mut restart = false;
+        Frame::group(ui.style())
+            .stroke(Stroke::new(1.0, red))
+            .inner_margin(Margin::symmetric(12.0, 8.0))
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    restart = ui
+                        .button(
+                            RichText::new("Tracking stopped — click to restart")
+                                .color(red)
+                                .strong(),
+                        )
+                        .clicked();
+                    ui.label(RichText::new(reason.as_str()).small());
+                });
+            });
+        if restart {
+            self.start_monitor();
+            self.set_status("Tracking restarted.");
+        }
     }
 
     #[cfg(windows)]
@@ -670,6 +1295,23 @@ impl PlaytimeApp {
         }
     }
 
+    /// Stops any running local API and starts a new one when the setting is on.
+    fn restart_local_api(&mut self) {
+        self.local_api = None;
+        if !self.settings.enable_local_api {
+            return;
+        }
+        match LocalApi::start(
+            self.settings.local_api_port,
+            Arc::clone(&self.snapshot),
+            Arc::clone(&self.store),
+            self.settings.analytics_utc_offset(),
+        ) {
+            Ok(api) => self.local_api = Some(api),
+            Err(err) => self.set_status(format!("Local API unavailable: {err:#}")),
+        }
+    }
+
     fn stop_monitor(&mut self) {
         self.stop_flag.store(true, Ordering::SeqCst);
         if let Some(handle) = self.monitor_handle.take() {
@@ -778,6 +1420,27 @@ impl PlaytimeApp {
         }
     }
 
+    /// Today's and all-time playtime for the tray menu, including the session in progress.
+    #[cfg(windows)]
+    fn tray_playtime_lines(&self, active: Option<&ActiveSession>) -> (String, String) {
+        let utc_offset = self.settings.analytics_utc_offset();
+        let today = date_at(Local::now(), utc_offset);
+        let mut today_minutes = minutes_on_day(&self.sessions, today, utc_offset);
+        let mut total_minutes = self
+            .analytics
+            .as_ref()
+            .map(|analytics| analytics.total_minutes)
+            .unwrap_or(0.0);
+        if let Some(active) = active {
+            total_minutes += active_session_minutes(active);
+            today_minutes += active_minutes_on_day(active, today, utc_offset);
+        }
+        (
+            format!("Today: {}", format_duration(today_minutes)),
+            format!("Total: {:.0}h", total_minutes / 60.0),
+        )
+    }
+
     #[cfg(windows)]
     fn process_tray(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         if self.first_frame {
@@ -802,6 +1465,18 @@ impl PlaytimeApp {
             }
         }
 
+        let active = self
+            .snapshot
+            .lock()
+            .ok()
+            .and_then(|snapshot| snapshot.active_session.clone());
+        let color = self.settings.tray_icon_color;
+        let (today, total) = self.tray_playtime_lines(active.as_ref());
+        if let Some(tray) = &mut self.tray {
+            tray.set_appearance(color, active.is_some());
+            tray.set_playtime(&today, &total);
+        }
+
         for action in tray_actions {
             match action {
                 TrayAction::Show => {
@@ -852,7 +1527,10 @@ impl PlaytimeApp {
         }
     }
 
-    fn initialize_leaderboard_client(&mut self) {
+    /// Builds the client from the settings. With the local fallback enabled, a remote endpoint is
+    /// checked on the leaderboard worker and replaced by the local board if it does not answer;
+    /// `retry` reports when it answers again.
+    fn initialize_leaderboard_client(&mut self, retry: bool) {
         let override_endpoint = {
             let candidate = self.settings.leaderboard_endpoint.trim();
             if candidate.is_empty() {
@@ -861,18 +1539,35 @@ impl PlaytimeApp {
                 Some(candidate)
             }
         };
-        match LeaderboardClient::auto(self.store.data_dir(), override_endpoint) {
+        let data_dir = self.store.data_dir().to_path_buf();
+        match LeaderboardClient::auto(&data_dir, override_endpoint) {
             Ok(client) => {
-                self.leaderboard_client = Some(client);
+                let remote = matches!(client, LeaderboardClient::Remote { .. });
+                self.leaderboard_client =
+                    Some(client.with_retries(self.settings.leaderboard_retries));
+                self.leaderboard_offline = false;
+                if remote && self.settings.leaderboard_local_fallback {
+                    // Any sync still running used the previous client.
+                    self.leaderboard_rx = None;
+                    self.leaderboard_inflight = false;
+                    self.start_leaderboard_job(LeaderboardJob::CheckEndpoint { data_dir, retry });
+                }
             }
             Err(err) => {
                 self.leaderboard_client = None;
+                self.leaderboard_offline = false;
                 eprintln!("Leaderboard client init failed: {err:?}");
                 self.set_status(format!("Leaderboard unavailable: {err}"));
             }
         }
     }
 
+    /// Rebuilds the client after a fallback to the local board, switching back to the endpoint
+    /// once it answers again.
+    fn retry_leaderboard_endpoint(&mut self) {
+        self.initialize_leaderboard_client(true);
+    }
+
     fn maybe_queue_initial_leaderboard_fetch(&mut self) {
         // Attempt an immediate refresh on startup so the UI has data ready.
         self.last_leaderboard_attempt = None;
@@ -895,6 +1590,11 @@ impl PlaytimeApp {
         self.leaderboard_rx = Some(rx);
         self.leaderboard_inflight = true;
         self.last_leaderboard_attempt = Some(Instant::now());
+        let rank_username = match &job {
+            LeaderboardJob::Remove { .. } | LeaderboardJob::CheckEndpoint { .. } => String::new(),
+            _ => self.leaderboard_identity(),
+        };
+        let period = self.leaderboard_period;
 
         thread::spawn(move || {
             let mut outcome = LeaderboardSyncResult::default();
@@ -902,17 +1602,40 @@ impl PlaytimeApp {
                 LeaderboardJob::SubmitAndFetch {
                     username,
                     total_minutes,
+                    monthly,
                 } => {
-                    match client.submit_total_minutes(&username, total_minutes) {
+                    let submitted = match client.submit_total_minutes(
+                        &username,
+                        total_minutes,
+                        Some(monthly),
+                    ) {
                         Ok(()) => {
                             outcome.message = Some(format!("Leaderboard synced for {username}."));
+                            true
                         }
                         Err(err) => {
-                            outcome.error = Some(format!("Failed to sync leaderboard: {err}"));
+                            if let Some(limit) = err.downcast_ref::<RateLimited>() {
+                                outcome.retry_after = Some(limit.retry_after);
+                                outcome.error = Some(format!("{limit}; your total is queued."));
+                            } else {
+                                outcome.error = Some(format!("Failed to sync leaderboard: {err}"));
+                            }
+                            false
+                        }
+                    };
+                    match client.fetch_top_entries(None, period) {
+                        Ok(mut entries) => {
+                            // The service came back after the submit failed; deliver the queued total.
+                            if !submitted && let Ok(true) = client.flush_pending() {
+                                outcome.error = None;
+                                outcome.message =
+                                    Some(format!("Leaderboard synced for {username}."));
+                                if let Ok(refreshed) = client.fetch_top_entries(None, period) {
+                                    entries = refreshed;
+                                }
+                            }
+                            outcome.entries = Some(entries);
                         }
-                    }
-                    match client.fetch_top_entries() {
-                        Ok(entries) => outcome.entries = Some(entries),
                         Err(err) => {
                             let message = format!("Failed to refresh leaderboard entries: {err}");
                             outcome.error = Some(match outcome.error.take() {
@@ -922,13 +1645,55 @@ impl PlaytimeApp {
                         }
                     }
                 }
-                LeaderboardJob::FetchOnly => match client.fetch_top_entries() {
-                    Ok(entries) => outcome.entries = Some(entries),
-                    Err(err) => {
-                        outcome.error =
-                            Some(format!("Failed to refresh leaderboard entries: {err}"));
+                LeaderboardJob::Remove { username } => {
+                    match client.remove_username(&username) {
+                        Ok(true) => {
+                            outcome.message =
+                                Some(format!("Removed {username} from the leaderboard."));
+                        }
+                        Ok(false) => {
+                            outcome.message =
+                                Some(format!("{username} was not on the leaderboard."));
+                        }
+                        Err(err) => {
+                            outcome.error =
+                                Some(format!("Failed to remove leaderboard entry: {err}"));
+                        }
+                    }
+                    if let Ok(entries) = client.fetch_top_entries(None, period) {
+                        outcome.entries = Some(entries);
+                    }
+                }
+                LeaderboardJob::FetchOnly => {
+                    match client.fetch_top_entries_cached(None, period, LEADERBOARD_CACHE_TTL) {
+                        Ok(entries) => outcome.entries = Some(entries),
+                        Err(err) => {
+                            outcome.error =
+                                Some(format!("Failed to refresh leaderboard entries: {err}"));
+                        }
+                    }
+                }
+                LeaderboardJob::CheckEndpoint { data_dir, retry } => {
+                    match client.local_fallback(&data_dir, DEFAULT_PING_TIMEOUT) {
+                        Ok(fallback) => {
+                            if retry && fallback.is_none() {
+                                outcome.message =
+                                    Some("Leaderboard endpoint reachable again.".to_string());
+                            }
+                            outcome.endpoint_check = Some(fallback);
+                        }
+                        Err(err) => {
+                            outcome.error =
+                                Some(format!("Failed to check leaderboard endpoint: {err}"));
+                        }
                     }
-                },
+                }
+            }
+
+            if rank_username.is_empty() {
+                outcome.rank = Some(None);
+            } else if let Ok(rank) = client.fetch_rank(&rank_username) {
+                outcome.rank = Some(rank);
             }
 
             let _ = tx.send(outcome);
@@ -952,9 +1717,30 @@ impl PlaytimeApp {
 
             match outcome {
                 Ok(result) => {
+                    let checked = result.endpoint_check.is_some();
+                    if let Some(fallback) = result.endpoint_check {
+                        self.leaderboard_entries.clear();
+                        self.leaderboard_rank = None;
+                        if let Some(local) = fallback {
+                            self.leaderboard_client = Some(local);
+                            self.leaderboard_offline = true;
+                            self.set_status(
+                                "Leaderboard endpoint unreachable; using local leaderboard.",
+                            );
+                        }
+                    }
                     if let Some(entries) = result.entries {
                         self.leaderboard_entries = entries;
                     }
+                    if let Some(rank) = result.rank {
+                        self.leaderboard_rank = rank;
+                    }
+                    if let Some(retry_after) = result.retry_after {
+                        // Make the next automatic sync due once the service allows it again.
+                        self.last_leaderboard_attempt = Instant::now().checked_sub(
+                            self.leaderboard_sync_interval.saturating_sub(retry_after),
+                        );
+                    }
                     if let Some(message) = result.message {
                         self.last_leaderboard_success = Some(Instant::now());
                         self.set_status(message);
@@ -962,6 +1748,9 @@ impl PlaytimeApp {
                     if let Some(error) = result.error {
                         self.set_status(error);
                     }
+                    if checked {
+                        self.maybe_queue_initial_leaderboard_fetch();
+                    }
                 }
                 Err(()) => {
                     self.set_status("Leaderboard sync interrupted.");
@@ -988,7 +1777,7 @@ impl PlaytimeApp {
         }
 
         if self.settings.sync_leaderboard {
-            let username = self.settings.leaderboard_username.trim();
+            let username = self.leaderboard_identity();
             if username.is_empty() {
                 if self.leaderboard_entries.is_empty() {
                     self.set_status(
@@ -999,15 +1788,42 @@ impl PlaytimeApp {
                 return;
             }
             let total_minutes = self.current_total_minutes();
+            let monthly = self.current_month_total();
             self.start_leaderboard_job(LeaderboardJob::SubmitAndFetch {
-                username: username.to_string(),
+                username,
                 total_minutes,
+                monthly,
             });
         } else {
             self.start_leaderboard_job(LeaderboardJob::FetchOnly);
         }
     }
 
+    /// The name submitted to the leaderboard: the username, or its hash when anonymizing.
+    /// Empty when no username is set.
+    fn leaderboard_identity(&self) -> String {
+        let username = self.settings.leaderboard_username.trim();
+        if username.is_empty() {
+            String::new()
+        } else if self.settings.leaderboard_anonymize {
+            pseudonymize_username(username)
+        } else {
+            username.to_string()
+        }
+    }
+
+    /// Position of the current user in the fetched leaderboard entries, matched
+    /// case-insensitively.
+    fn own_leaderboard_index(&self) -> Option<usize> {
+        let identity = self.leaderboard_identity().to_lowercase();
+        if identity.is_empty() {
+            return None;
+        }
+        self.leaderboard_entries
+            .iter()
+            .position(|entry| entry.username.trim().to_lowercase() == identity)
+    }
+
     fn current_total_minutes(&self) -> f64 {
         let stored = self
             .analytics
@@ -1015,8 +1831,35 @@ impl PlaytimeApp {
             .map(|analytics| analytics.total_minutes)
             .unwrap_or(0.0);
 
+        stored + self.active_minutes()
+    }
+
+    /// This month's playtime for the monthly leaderboard. Months follow UTC, like the server,
+    /// and the active session only counts from the start of the month.
+    fn current_month_total(&self) -> MonthlyTotal {
+        let now = Utc::now();
         let active = self
             .snapshot
+            .lock()
+            .ok()
+            .and_then(|snapshot| snapshot.active_session.clone());
+        let minutes = self
+            .analytics
+            .as_ref()
+            .map(|analytics| {
+                let mut analytics = analytics.clone();
+                if let Some(active) = &active {
+                    analytics.add_active_session(active);
+                }
+                analytics.minutes_in_utc_month(now)
+            })
+            .unwrap_or_else(|| self.active_minutes());
+
+        MonthlyTotal::new(now.date_naive(), minutes)
+    }
+
+    fn active_minutes(&self) -> f64 {
+        self.snapshot
             .lock()
             .ok()
             .and_then(|snapshot| {
@@ -1025,9 +1868,34 @@ impl PlaytimeApp {
                     .as_ref()
                     .map(|session| active_session_minutes(session))
             })
-            .unwrap_or(0.0);
+            .unwrap_or(0.0)
+    }
+
+    fn switch_leaderboard_period(&mut self, period: LeaderboardPeriod) {
+        if self.leaderboard_period == period {
+            return;
+        }
+        if self.leaderboard_inflight {
+            self.set_status("Leaderboard sync in progress; try again in a moment.");
+            return;
+        }
+        self.leaderboard_period = period;
+        self.leaderboard_entries.clear();
+        self.start_leaderboard_job(LeaderboardJob::FetchOnly);
+    }
 
-        stored + active
+    fn leave_leaderboard(&mut self, username: String) {
+        if self.leaderboard_inflight {
+            self.set_status("Leaderboard sync in progress; try again in a moment.");
+            return;
+        }
+        self.settings.sync_leaderboard = false;
+        self.pending_settings.sync_leaderboard = false;
+        if let Err(err) = self.settings_store.save(&self.settings) {
+            self.set_status(format!("Failed to save settings: {err}"));
+            return;
+        }
+        self.start_leaderboard_job(LeaderboardJob::Remove { username });
     }
 
     fn force_leaderboard_sync(&mut self) {
@@ -1041,15 +1909,17 @@ impl PlaytimeApp {
             return;
         }
         if self.settings.sync_leaderboard {
-            let username = self.settings.leaderboard_username.trim();
+            let username = self.leaderboard_identity();
             if username.is_empty() {
                 self.set_status("Enter a leaderboard username before syncing.");
                 return;
             }
             let total_minutes = self.current_total_minutes();
+            let monthly = self.current_month_total();
             self.start_leaderboard_job(LeaderboardJob::SubmitAndFetch {
-                username: username.to_string(),
+                username,
                 total_minutes,
+                monthly,
             });
         } else {
             self.start_leaderboard_job(LeaderboardJob::FetchOnly);
@@ -1058,9 +1928,39 @@ impl PlaytimeApp {
 
     fn apply_monitor_settings(&mut self) {
         let mut new_settings = self.pending_settings.clone();
+        new_settings.process_tokens = self
+            .process_tokens_input
+            .split(',')
+            .map(str::to_string)
+            .collect();
         new_settings.sanitize();
+        self.process_tokens_input = new_settings.process_tokens.join(", ");
+        if let Err(err) = ProcessMatcher::new(
+            new_settings.process_match_mode,
+            &new_settings.process_tokens,
+        ) {
+            self.set_status(format!("Monitor settings not applied: {err:#}"));
+            return;
+        }
         let changed = new_settings.poll_seconds != self.settings.poll_seconds
             || new_settings.min_session_minutes != self.settings.min_session_minutes
+            || new_settings.discard_short_sessions != self.settings.discard_short_sessions
+            || new_settings.recovered_min_session_minutes
+                != self.settings.recovered_min_session_minutes
+            || new_settings.grace_seconds != self.settings.grace_seconds
+            || new_settings.merge_gap_minutes != self.settings.merge_gap_minutes
+            || new_settings.idle_timeout_minutes != self.settings.idle_timeout_minutes
+            || new_settings.auto_reset_stale_active_minutes
+                != self.settings.auto_reset_stale_active_minutes
+            || new_settings.process_tokens != self.settings.process_tokens
+            || new_settings.process_match_mode != self.settings.process_match_mode
+            || new_settings.detect_window_title != self.settings.detect_window_title
+            || new_settings.notify_on_session_start != self.settings.notify_on_session_start
+            || new_settings.notify_on_session_end != self.settings.notify_on_session_end
+            || new_settings.write_status_file != self.settings.write_status_file
+            || new_settings.write_event_log != self.settings.write_event_log
+            || new_settings.enable_local_api != self.settings.enable_local_api
+            || new_settings.local_api_port != self.settings.local_api_port
             || new_settings.refresh_seconds != self.settings.refresh_seconds;
 
         if !changed {
@@ -1069,6 +1969,8 @@ impl PlaytimeApp {
             return;
         }
 
+        let api_changed = new_settings.enable_local_api != self.settings.enable_local_api
+            || new_settings.local_api_port != self.settings.local_api_port;
         self.settings = new_settings.clone();
         self.pending_settings = new_settings;
         self.refresh_interval = Duration::from_secs(self.settings.refresh_seconds.max(1));
@@ -1085,13 +1987,20 @@ impl PlaytimeApp {
                 "Updated monitor but failed to save settings: {err}"
             )),
         }
+        if api_changed {
+            self.restart_local_api();
+        }
     }
 
     fn apply_leaderboard_settings(&mut self) {
         self.pending_settings.sanitize();
         let changed = self.settings.sync_leaderboard != self.pending_settings.sync_leaderboard
             || self.settings.leaderboard_username != self.pending_settings.leaderboard_username
-            || self.settings.leaderboard_endpoint != self.pending_settings.leaderboard_endpoint;
+            || self.settings.leaderboard_anonymize != self.pending_settings.leaderboard_anonymize
+            || self.settings.leaderboard_endpoint != self.pending_settings.leaderboard_endpoint
+            || self.settings.leaderboard_retries != self.pending_settings.leaderboard_retries
+            || self.settings.leaderboard_local_fallback
+                != self.pending_settings.leaderboard_local_fallback;
 
         if !changed {
             self.set_status("Leaderboard settings already applied.");
@@ -1100,18 +2009,28 @@ impl PlaytimeApp {
 
         self.settings.sync_leaderboard = self.pending_settings.sync_leaderboard;
         self.settings.leaderboard_username = self.pending_settings.leaderboard_username.clone();
+        self.settings.leaderboard_anonymize = self.pending_settings.leaderboard_anonymize;
         self.settings.leaderboard_endpoint = self.pending_settings.leaderboard_endpoint.clone();
+        self.settings.leaderboard_retries = self.pending_settings.leaderboard_retries;
+        self.settings.leaderboard_local_fallback = self.pending_settings.leaderboard_local_fallback;
         self.pending_settings.sync_leaderboard = self.settings.sync_leaderboard;
         self.pending_settings.leaderboard_username = self.settings.leaderboard_username.clone();
         self.pending_settings.leaderboard_endpoint = self.settings.leaderboard_endpoint.clone();
 
         let save_result = self.settings_store.save(&self.settings);
 
-        self.initialize_leaderboard_client();
+        if !self.settings.sync_leaderboard
+            && let Some(client) = &self.leaderboard_client
+            && let Err(err) = client.discard_pending()
+        {
+            eprintln!("Failed to discard queued leaderboard submission: {err:?}");
+        }
         self.leaderboard_entries.clear();
+        self.leaderboard_rank = None;
         self.leaderboard_rx = None;
         self.leaderboard_inflight = false;
         self.last_leaderboard_attempt = None;
+        self.initialize_leaderboard_client(false);
 
         match save_result {
             Ok(()) => {
@@ -1119,6 +2038,12 @@ impl PlaytimeApp {
                     if self.settings.leaderboard_username.trim().is_empty() {
                         "Leaderboard sync enabled. Add a username to share your playtime."
                             .to_string()
+                    } else if self.settings.leaderboard_anonymize {
+                        format!(
+                            "Leaderboard sync enabled for {} as {}.",
+                            self.settings.leaderboard_username,
+                            self.leaderboard_identity()
+                        )
                     } else {
                         format!(
                             "Leaderboard sync enabled for {}.",
@@ -1152,11 +2077,18 @@ impl PlaytimeApp {
         self.maybe_sync_leaderboard();
     }
 
-    fn ensure_style(&mut self, ctx: &egui::Context) {
-        if self.style_applied {
+    /// Applies the spacing and visuals for the chosen theme. Runs again whenever the theme
+    /// setting (or, for `System`, the OS preference) changes.
+    fn ensure_style(&mut self, ctx: &egui::Context, system_theme: Option<eframe::Theme>) {
+        let dark = match self.settings.theme {
+            Theme::Dark => true,
+            Theme::Light => false,
+            Theme::System => system_theme != Some(eframe::Theme::Light),
+        };
+        if self.applied_dark_mode == Some(dark) {
             return;
         }
-        self.style_applied = true;
+        self.applied_dark_mode = Some(dark);
 
         let mut style = (*ctx.style()).clone();
         style.spacing.item_spacing = egui::vec2(12.0, 10.0);
@@ -1166,6 +2098,15 @@ impl PlaytimeApp {
         style.visuals.window_rounding = Rounding::same(12.0);
         ctx.set_style(style);
 
+        if !dark {
+            let mut visuals = Visuals::light();
+            visuals.window_rounding = Rounding::same(12.0);
+            visuals.widgets.inactive.bg_fill = Color32::from_rgb(226, 230, 237);
+            visuals.window_shadow = Shadow::NONE;
+            ctx.set_visuals(visuals);
+            return;
+        }
+
         let mut visuals = Visuals::dark();
         visuals.window_rounding = Rounding::same(12.0);
         visuals.widgets.noninteractive.bg_fill = Color32::from_rgb(26, 30, 39);
@@ -1182,28 +2123,33 @@ impl PlaytimeApp {
     fn render_tab_bar(&mut self, ui: &mut egui::Ui) {
         ui.horizontal(|ui| {
             ui.spacing_mut().item_spacing.x = 12.0;
-            for (tab, label) in [
-                (DashboardTab::Overview, "Overview"),
-                (DashboardTab::Insights, "Insights"),
+            for (tab, label, shortcut) in [
+                (DashboardTab::Overview, "Overview", "Ctrl+1"),
+                (DashboardTab::Insights, "Insights", "Ctrl+2"),
             ] {
                 let is_active = self.selected_tab == tab;
+                let idle_stroke = if ui.visuals().dark_mode {
+                    Color32::from_rgb(60, 66, 80)
+                } else {
+                    Color32::from_rgb(196, 202, 214)
+                };
                 let button = egui::Button::new(label)
                     .min_size(egui::vec2(120.0, 32.0))
                     .fill(if is_active {
                         Color32::from_rgb(82, 96, 122)
                     } else {
-                        Color32::from_rgb(36, 41, 52)
+                        ui.visuals().widgets.inactive.bg_fill
                     })
                     .stroke(Stroke::new(
                         1.0,
                         if is_active {
                             Color32::from_rgb(130, 180, 255)
                         } else {
-                            Color32::from_rgb(60, 66, 80)
+                            idle_stroke
                         },
                     ))
                     .rounding(Rounding::same(10.0));
-                if ui.add(button).clicked() {
+                if ui.add(button).on_hover_text(shortcut).clicked() {
                     self.selected_tab = tab;
                 }
             }
@@ -1211,7 +2157,19 @@ impl PlaytimeApp {
     }
 
     fn render_status_banner(&self, ui: &mut egui::Ui, snapshot: &MonitorSnapshot) {
-        let (status, accent, detail) = if let Some(active) = &snapshot.active_session {
+        let (status, accent, detail) = if let Some(error) = &snapshot.error {
+            (
+                "Not tracking",
+                Color32::from_rgb(220, 90, 90),
+                error.clone(),
+            )
+        } else if self.settings.tracking_paused {
+            (
+                "Paused",
+                Color32::from_rgb(230, 180, 80),
+                String::from("Tracking is paused. Game sessions are not being recorded."),
+            )
+        } else if let Some(active) = &snapshot.active_session {
             let elapsed = format_duration(active_session_minutes(active));
             (
                 "Tracking",
@@ -1239,8 +2197,13 @@ impl PlaytimeApp {
             )
         };
 
+        let fill = if ui.visuals().dark_mode {
+            Color32::from_rgb(33, 38, 49)
+        } else {
+            Color32::from_rgb(238, 241, 246)
+        };
         Frame::group(ui.style())
-            .fill(Color32::from_rgb(33, 38, 49))
+            .fill(fill)
             .stroke(Stroke::new(1.0, accent))
             .rounding(Rounding::same(12.0))
             .inner_margin(Margin::symmetric(16.0, 12.0))
@@ -1253,14 +2216,35 @@ impl PlaytimeApp {
             });
     }
 
-    fn render_summary_cards(&self, ui: &mut egui::Ui) {
+    /// The saved-session analytics, with the session in progress folded in when "Include
+    /// current session" is ticked. Leaderboard totals and milestones keep using `self.analytics`.
+    fn displayed_analytics(&self, snapshot: &MonitorSnapshot) -> Option<Cow<'_, Analytics>> {
+        let analytics = self.analytics.as_ref()?;
+        match &snapshot.active_session {
+            Some(active) if self.settings.include_active_session => {
+                let mut live = analytics.clone();
+                live.add_active_session(active);
+                Some(Cow::Owned(live))
+            }
+            _ => Some(Cow::Borrowed(analytics)),
+        }
+    }
+
+    fn render_summary_cards(&self, ui: &mut egui::Ui, snapshot: &MonitorSnapshot) {
         let cards: Vec<(&'static str, String, String, Color32)> =
-            if let Some(analytics) = &self.analytics {
+            if let Some(analytics) = self.displayed_analytics(snapshot) {
                 vec![
                     (
                         "Total hours",
                         format!("{:.1}", analytics.total_minutes / 60.0),
-                        format!("Across {} sessions", analytics.total_sessions),
+                        if analytics.active_minutes.is_some() {
+                            format!(
+                                "Across {} sessions, including current session",
+                                analytics.total_sessions
+                            )
+                        } else {
+                            format!("Across {} sessions", analytics.total_sessions)
+                        },
                         Color32::from_rgb(86, 156, 214),
                     ),
                     (
@@ -1278,6 +2262,15 @@ impl PlaytimeApp {
                         format!("30-day total {:.1} h", analytics.minutes_last_30 / 60.0),
                         Color32::from_rgb(255, 170, 90),
                     ),
+                    (
+                        "Last 90 days",
+                        format!("{:.1}", analytics.minutes_in_last(90) / 60.0),
+                        format!(
+                            "365-day total {:.1} h",
+                            analytics.minutes_in_last(365) / 60.0
+                        ),
+                        Color32::from_rgb(94, 201, 146),
+                    ),
                 ]
             } else {
                 vec![(
@@ -1308,7 +2301,7 @@ impl PlaytimeApp {
         accent: Color32,
     ) {
         Frame::group(ui.style())
-            .fill(Color32::from_rgb(36, 41, 52))
+            .fill(ui.visuals().widgets.inactive.bg_fill)
             .stroke(Stroke::new(1.0, accent))
             .rounding(Rounding::same(12.0))
             .inner_margin(Margin::symmetric(14.0, 12.0))
@@ -1321,22 +2314,213 @@ impl PlaytimeApp {
             });
     }
 
+    fn render_analytics_timezone(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let mut fixed = self.settings.analytics_utc_offset_minutes.is_some();
+            let mut minutes = self
+                .settings
+                .analytics_utc_offset_minutes
+                .unwrap_or_else(|| Local::now().offset().local_minus_utc() / 60);
+            let mut changed = ui
+                .checkbox(&mut fixed, "Fixed time zone for days and hours")
+                .on_hover_text(
+                    "Keeps day boundaries in place while travelling; untick to follow the system time zone.",
+                )
+                .changed();
+            if fixed {
+                changed |= ui
+                    .add(
+                        egui::DragValue::new(&mut minutes)
+                            .clamp_range(-MAX_UTC_OFFSET_MINUTES..=MAX_UTC_OFFSET_MINUTES)
+                            .speed(15.0)
+                            .suffix(" min"),
+                    )
+                    .changed();
+                ui.label(format_utc_offset(minutes));
+            }
+            if changed {
+                self.settings.analytics_utc_offset_minutes = fixed.then_some(minutes);
+                self.settings.sanitize();
+                self.pending_settings.analytics_utc_offset_minutes =
+                    self.settings.analytics_utc_offset_minutes;
+                let message = match self.settings.analytics_utc_offset_minutes {
+                    Some(minutes) => format!("Analytics now use {}.", format_utc_offset(minutes)),
+                    None => "Analytics now follow the system time zone.".to_string(),
+                };
+                self.persist_visual_setting(message);
+                self.refresh_sessions();
+                self.restart_local_api();
+            }
+        });
+    }
+
+    fn render_active_session_toggle(&mut self, ui: &mut egui::Ui) {
+        let mut include = self.settings.include_active_session;
+        if ui
+            .checkbox(&mut include, "Include current session in totals")
+            .on_hover_text("Overview totals and rolling windows count the session in progress.")
+            .changed()
+        {
+            self.settings.include_active_session = include;
+            self.pending_settings.include_active_session = include;
+            let message = if include {
+                "Totals now include the current session."
+            } else {
+                "Totals now cover saved sessions only."
+            };
+            self.persist_visual_setting(message);
+        }
+    }
+
+    fn render_daily_goal(&mut self, ui: &mut egui::Ui, snapshot: &MonitorSnapshot) {
+        let utc_offset = self.settings.analytics_utc_offset();
+        let today = date_at(Local::now(), utc_offset);
+        let mut played = minutes_on_day(&self.sessions, today, utc_offset);
+        if let Some(active) = &snapshot.active_session {
+            played += active_minutes_on_day(active, today, utc_offset);
+        }
+
+        ui.group(|ui| {
+            ui.horizontal(|ui| {
+                ui.label(RichText::new("Daily goal").strong());
+                let mut goal = self.settings.daily_goal_minutes;
+                let response = ui.add(
+                    egui::DragValue::new(&mut goal)
+                        .clamp_range(0..=1440)
+                        .speed(5.0)
+                        .suffix(" min"),
+                );
+                if response.changed() {
+                    self.settings.daily_goal_minutes = goal;
+                    self.pending_settings.daily_goal_minutes = goal;
+                    let message = if goal == 0 {
+                        String::from("Daily goal disabled.")
+                    } else {
+                        format!("Daily goal set to {}.", format_duration(goal as f64))
+                    };
+                    self.persist_visual_setting(message);
+                }
+            });
+
+            let goal = self.settings.daily_goal_minutes;
+            if goal == 0 {
+                ui.label(
+                    RichText::new("Set a goal above to track today's progress.")
+                        .small()
+                        .weak(),
+                );
+                return;
+            }
+            let progress = (played / goal as f64).clamp(0.0, 1.0) as f32;
+            let met = played >= goal as f64;
+            let fill = if met {
+                Color32::from_rgb(94, 201, 146)
+            } else {
+                Color32::from_rgb(86, 156, 214)
+            };
+            let text = if met {
+                format!(
+                    "Goal met: {} of {}",
+                    format_duration(played),
+                    format_duration(goal as f64)
+                )
+            } else {
+                format!(
+                    "{} of {} today",
+                    format_duration(played),
+                    format_duration(goal as f64)
+                )
+            };
+            ui.add(egui::ProgressBar::new(progress).fill(fill).text(text));
+        });
+    }
+
+    /// Congratulates once per newly reached milestone; several reached at once (e.g. after an
+    /// import) are announced together under the highest.
+    fn announce_milestones(&mut self) {
+        let Some(analytics) = &self.analytics else {
+            return;
+        };
+        let reached = self
+            .settings
+            .unannounced_milestones(analytics.total_minutes);
+        let Some(highest) = reached.iter().max().copied() else {
+            return;
+        };
+        self.settings.announced_milestones.extend(&reached);
+        self.settings.sanitize();
+        self.pending_settings.announced_milestones = self.settings.announced_milestones.clone();
+        let message = format!("Milestone reached: {highest} hours of playtime!");
+        if let Err(err) = notifications::notify("Milestone reached", &message) {
+            eprintln!("{err:#}");
+        }
+        self.persist_visual_setting(message);
+    }
+
+    /// Stores the longest streak in the history when it beats the saved best, so the record
+    /// survives deleted sessions.
+    fn update_best_streak(&mut self) {
+        let Some((days, end)) =
+            longest_streak(&self.sessions, self.settings.analytics_utc_offset())
+        else {
+            return;
+        };
+        if !self.settings.record_streak(days, end) {
+            return;
+        }
+        self.pending_settings.best_streak_days = self.settings.best_streak_days;
+        self.pending_settings.best_streak_end_date = self.settings.best_streak_end_date;
+        if let Err(err) = self.settings_store.save(&self.settings) {
+            eprintln!("Failed to save best streak: {err:?}");
+        }
+    }
+
+    fn render_milestones(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(RichText::new("Milestones").strong());
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.milestones_input)
+                    .hint_text("100, 500, 1000")
+                    .desired_width(180.0),
+            );
+            ui.label("hours");
+            if response.lost_focus() {
+                let hours = parse_milestones(&self.milestones_input);
+                if hours != self.settings.milestone_hours {
+                    self.settings.milestone_hours = hours;
+                    self.settings.sanitize();
+                    self.pending_settings.milestone_hours = self.settings.milestone_hours.clone();
+                    self.milestones_input = format_milestones(&self.settings.milestone_hours);
+                    self.persist_visual_setting("Milestones updated.");
+                    self.announce_milestones();
+                } else {
+                    self.milestones_input = format_milestones(&self.settings.milestone_hours);
+                }
+            }
+        });
+    }
+
     fn render_overview_tab(&mut self, ui: &mut egui::Ui, snapshot: &MonitorSnapshot) {
         self.render_status_banner(ui, snapshot);
         ui.add_space(12.0);
-        self.render_summary_cards(ui);
+        self.render_summary_cards(ui, snapshot);
+        self.render_active_session_toggle(ui);
+        ui.add_space(12.0);
+        self.render_daily_goal(ui, snapshot);
+        ui.add_space(8.0);
+        self.render_milestones(ui);
         ui.add_space(16.0);
 
         ui.collapsing("Monitor & Data", |ui| {
             self.render_settings(ui);
         });
 
-        if let Some(analytics) = &self.analytics {
+        if let Some(analytics) = self.displayed_analytics(snapshot) {
             ui.add_space(16.0);
             ui.collapsing("Playtime Summary", |ui| {
-                self.render_totals(ui, analytics);
+                self.render_totals(ui, &analytics);
                 ui.add_space(8.0);
-                self.render_top_days(ui, analytics);
+                self.render_top_days(ui, &analytics);
             });
         } else {
             ui.add_space(16.0);
@@ -1347,6 +2531,9 @@ impl PlaytimeApp {
         ui.collapsing("Recent Sessions", |ui| {
             self.render_recent(ui);
         });
+        ui.collapsing("Add Session Manually", |ui| {
+            self.render_session_form(ui);
+        });
     }
 
     fn render_insights_tab(&mut self, ui: &mut egui::Ui, snapshot: &MonitorSnapshot) {
@@ -1377,32 +2564,214 @@ impl PlaytimeApp {
                 };
                 self.persist_visual_setting(message);
             }
+            let mut hourly = self.settings.show_hour_chart;
+            if ui.checkbox(&mut hourly, "Time of day").changed() {
+                self.settings.show_hour_chart = hourly;
+                self.pending_settings.show_hour_chart = hourly;
+                let message = if hourly {
+                    "Time-of-day chart enabled."
+                } else {
+                    "Time-of-day chart disabled."
+                };
+                self.persist_visual_setting(message);
+            }
+            let mut weekday = self.settings.show_weekday_chart;
+            if ui.checkbox(&mut weekday, "Weekday").changed() {
+                self.settings.show_weekday_chart = weekday;
+                self.pending_settings.show_weekday_chart = weekday;
+                let message = if weekday {
+                    "Weekday chart enabled."
+                } else {
+                    "Weekday chart disabled."
+                };
+                self.persist_visual_setting(message);
+            }
+            let mut duration = self.settings.show_duration_chart;
+            if ui.checkbox(&mut duration, "Session length").changed() {
+                self.settings.show_duration_chart = duration;
+                self.pending_settings.show_duration_chart = duration;
+                let message = if duration {
+                    "Session length chart enabled."
+                } else {
+                    "Session length chart disabled."
+                };
+                self.persist_visual_setting(message);
+            }
+        });
+        self.render_analytics_timezone(ui);
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    self.analytics.is_some(),
+                    egui::Button::new("Save shareable image..."),
+                )
+                .clicked()
+            {
+                self.export_share_image();
+            }
+            let mut include_name = self.settings.share_include_username;
+            if ui
+                .checkbox(&mut include_name, "Include commander name")
+                .changed()
+            {
+                self.settings.share_include_username = include_name;
+                self.pending_settings.share_include_username = include_name;
+                let message = if include_name {
+                    "Share images will include your commander name."
+                } else {
+                    "Share images will omit your commander name."
+                };
+                self.persist_visual_setting(message);
+            }
         });
 
+        let mut period_request = None;
+        let mut chart_export = None;
         if let Some(analytics) = &self.analytics {
             ui.add_space(12.0);
             ui.columns(2, |columns| {
                 columns[0].vertical(|ui| {
-                    self.render_charts(ui, analytics);
+                    chart_export = self.render_charts(ui, analytics);
                 });
                 columns[1].vertical(|ui| {
                     self.render_insight_stats(ui, analytics);
                     ui.add_space(16.0);
-                    self.render_leaderboard(ui);
+                    self.render_longest_sessions(ui, analytics);
+                    ui.add_space(16.0);
+                    self.render_tag_breakdown(ui, analytics);
+                    if analytics.game_totals.len() > 1 {
+                        ui.add_space(16.0);
+                        self.render_game_breakdown(ui, analytics);
+                    }
+                    ui.add_space(16.0);
+                    period_request = self.render_leaderboard(ui);
                 });
             });
         } else {
             ui.label("Playtime charts will appear after the first session is recorded.");
             ui.add_space(12.0);
-            self.render_leaderboard(ui);
+            period_request = self.render_leaderboard(ui);
+        }
+        if let Some(period) = period_request {
+            self.switch_leaderboard_period(period);
+        }
+        if let Some(chart) = chart_export {
+            self.export_chart_image(chart);
+        }
+
+        ui.add_space(16.0);
+        self.render_patches(ui);
+    }
+
+    fn render_patches(&mut self, ui: &mut egui::Ui) {
+        let per_patch = minutes_per_patch(
+            &self.sessions,
+            &self.settings.patches,
+            self.settings.analytics_utc_offset(),
+        );
+        if !per_patch.is_empty() {
+            let (labels, hours) = per_patch
+                .into_iter()
+                .map(|(label, minutes)| (label, minutes / 60.0))
+                .unzip();
+            if let Some(chart) = render_bar_chart(
+                ui,
+                "patch_plot",
+                "Playtime by patch",
+                labels,
+                hours,
+                Color32::from_rgb(230, 160, 80),
+                "Hours played",
+            ) {
+                self.export_chart_image(chart);
+            }
+        }
+
+        ui.collapsing("Patch dates", |ui| {
+            let mut remove = None;
+            Grid::new("patch_dates_grid")
+                .striped(true)
+                .show(ui, |grid| {
+                    for (idx, patch) in self.settings.patches.iter().enumerate() {
+                        grid.label(patch.label.as_str());
+                        grid.label(fmt_day(patch.start));
+                        if grid
+                            .small_button("🗑")
+                            .on_hover_text("Remove patch")
+                            .clicked()
+                        {
+                            remove = Some(idx);
+                        }
+                        grid.end_row();
+                    }
+                });
+            if self.settings.patches.is_empty() {
+                ui.label(
+                    RichText::new(
+                        "Add each patch with the date it went live to see playtime per patch.",
+                    )
+                    .small()
+                    .weak(),
+                );
+            }
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.patch_form.label)
+                        .hint_text("Alpha 4.0")
+                        .desired_width(120.0),
+                );
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.patch_form.start)
+                        .hint_text("YYYY-MM-DD")
+                        .desired_width(90.0),
+                );
+                if ui
+                    .button("Add")
+                    .on_hover_text("A patch on an existing date replaces it.")
+                    .clicked()
+                {
+                    self.add_patch();
+                }
+            });
+            if let Some(idx) = remove {
+                let patch = self.settings.patches.remove(idx);
+                self.pending_settings.patches = self.settings.patches.clone();
+                self.persist_visual_setting(format!("Removed patch {}.", patch.label));
+            }
+        });
+    }
+
+    fn add_patch(&mut self) {
+        let label = self.patch_form.label.trim().to_string();
+        if label.is_empty() {
+            self.set_status("Enter a patch name.");
+            return;
         }
+        let Ok(start) = NaiveDate::parse_from_str(self.patch_form.start.trim(), "%Y-%m-%d") else {
+            self.set_status("Enter the patch date as YYYY-MM-DD.");
+            return;
+        };
+        self.settings.patches.retain(|patch| patch.start != start);
+        self.settings.patches.push(PatchRelease {
+            label: label.clone(),
+            start,
+        });
+        self.settings.sanitize();
+        self.pending_settings.patches = self.settings.patches.clone();
+        self.patch_form = PatchForm::default();
+        self.persist_visual_setting(format!("Added patch {label} ({start})."));
     }
 
     fn render_totals(&self, ui: &mut egui::Ui, analytics: &Analytics) {
         ui.label(format!(
-            "Total playtime: {:.2} hours across {} sessions",
+            "Total playtime: {:.2} hours across {} sessions{}",
             analytics.total_minutes / 60.0,
-            analytics.total_sessions
+            analytics.total_sessions,
+            if analytics.active_minutes.is_some() {
+                " (including current session)"
+            } else {
+                ""
+            }
         ));
         ui.label(format!(
             "Average session: {} | Median session: {}",
@@ -1419,62 +2788,539 @@ impl PlaytimeApp {
         ));
     }
 
-    fn render_recent(&self, ui: &mut egui::Ui) {
+    fn render_recent(&mut self, ui: &mut egui::Ui) {
         if self.sessions.is_empty() {
             ui.label("No sessions recorded yet.");
             return;
         }
-        Grid::new("recent_sessions_grid")
-            .striped(true)
-            .show(ui, |grid| {
-                grid.label(RichText::new("Start").strong());
-                grid.label(RichText::new("Duration").strong());
-                grid.end_row();
-                for session in self.sessions.iter().rev().take(12) {
-                    grid.label(session.start.format("%Y-%m-%d %H:%M").to_string());
-                    grid.label(format_duration(session.duration_minutes));
-                    grid.end_row();
-                }
-            });
-    }
+        ui.horizontal(|ui| {
+            ui.label("Show");
+            for choice in RECENT_ROW_CHOICES {
+                let label = match choice {
+                    Some(rows) => rows.to_string(),
+                    None => "All".to_string(),
+                };
+                ui.selectable_value(&mut self.recent_rows, choice, label);
+            }
+        });
 
-    fn render_charts(&self, ui: &mut egui::Ui, analytics: &Analytics) {
-        let mut any_rendered = false;
+        ui.horizontal(|ui| {
+            ui.label("Filter");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.session_filter.text)
+                    .hint_text("Note or tag")
+                    .desired_width(160.0),
+            );
+            ui.label("From");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.session_filter.from)
+                    .hint_text("YYYY-MM-DD")
+                    .desired_width(90.0),
+            );
+            ui.label("To");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.session_filter.to)
+                    .hint_text("YYYY-MM-DD")
+                    .desired_width(90.0),
+            );
+            if self.session_filter.is_active() && ui.button("Clear").clicked() {
+                self.session_filter = SessionFilter::default();
+            }
+        });
 
-        if self.settings.show_daily_chart {
-            self.render_daily_chart(ui, analytics);
-            any_rendered = true;
+        let mut rows = match self.session_filter.apply(&self.sessions) {
+            Ok(rows) => rows,
+            Err(err) => {
+                ui.colored_label(Color32::from_rgb(230, 160, 80), err);
+                self.sessions.iter().collect()
+            }
+        };
+        if self.session_filter.is_active() {
+            let minutes = rows
+                .iter()
+                .map(|session| session.duration_minutes)
+                .sum::<f64>();
+            ui.label(format!(
+                "{} matching session{} — {}",
+                rows.len(),
+                if rows.len() == 1 { "" } else { "s" },
+                format_duration(minutes)
+            ));
         }
 
-        if self.settings.show_weekly_chart {
-            if any_rendered {
-                ui.add_space(12.0);
-            }
-            self.render_weekly_chart(ui, analytics);
-            any_rendered = true;
+        match self.recent_sort {
+            RecentSort::Start => rows.sort_by_key(|session| session.start),
+            RecentSort::Duration => rows.sort_by(|a, b| {
+                a.duration_minutes
+                    .total_cmp(&b.duration_minutes)
+                    .then(a.start.cmp(&b.start))
+            }),
         }
+        if self.recent_sort_descending {
+            rows.reverse();
+        }
+        rows.truncate(self.recent_rows.unwrap_or(usize::MAX));
+
+        let mut edit_request = None;
+        let mut delete_request = None;
+        let mut sort_request = None;
+        let (sort, descending) = (self.recent_sort, self.recent_sort_descending);
+        let header = |column: RecentSort, title: &str| {
+            let text = if sort == column {
+                format!("{title} {}", if descending { "⏷" } else { "⏶" })
+            } else {
+                title.to_string()
+            };
+            egui::Label::new(RichText::new(text).strong()).sense(egui::Sense::click())
+        };
+        ScrollArea::vertical()
+            .id_source("recent_sessions_scroll")
+            .max_height(360.0)
+            .show(ui, |ui| {
+                Grid::new("recent_sessions_grid")
+                    .striped(true)
+                    .show(ui, |grid| {
+                        grid.label("");
+                        for (column, title) in [
+                            (RecentSort::Start, "Start"),
+                            (RecentSort::Duration, "Duration"),
+                        ] {
+                            if grid
+                                .add(header(column, title))
+                                .on_hover_text("Click to sort")
+                                .clicked()
+                            {
+                                sort_request = Some(column);
+                            }
+                        }
+                        grid.label(RichText::new("Note").strong());
+                        grid.label(RichText::new("Tags").strong());
+                        grid.label("");
+                        grid.end_row();
+                        for session in rows {
+                            let mut selected = self.selected_sessions.contains(&session.id);
+                            if grid
+                                .checkbox(&mut selected, "")
+                                .on_hover_text("Select to merge")
+                                .changed()
+                            {
+                                if selected {
+                                    self.selected_sessions.insert(session.id);
+                                } else {
+                                    self.selected_sessions.remove(&session.id);
+                                }
+                            }
+                            let started = session.start.format("%Y-%m-%d %H:%M").to_string();
+                            grid.label(started.clone());
+                            grid.label(format_duration_precise(session.duration_minutes));
+                            let note = if session.note.is_empty() {
+                                RichText::new("Add note").weak()
+                            } else {
+                                RichText::new(session.note.as_str())
+                            };
+                            if grid
+                                .add(egui::Label::new(note).sense(egui::Sense::click()))
+                                .on_hover_text("Click to edit the note and tags")
+                                .clicked()
+                            {
+                                edit_request = Some(NoteEditor {
+                                    id: session.id,
+                                    started,
+                                    text: session.note.clone(),
+                                    tags: session.tags.join(", "),
+                                });
+                            }
+                            grid.label(if session.tags.is_empty() {
+                                RichText::new("—").weak()
+                            } else {
+                                RichText::new(session.tags.join(", "))
+                            });
+                            if grid
+                                .small_button("🗑")
+                                .on_hover_text("Delete this session")
+                                .clicked()
+                            {
+                                delete_request = Some(ConfirmAction::DeleteSession {
+                                    id: session.id,
+                                    started: session.start.format("%Y-%m-%d %H:%M").to_string(),
+                                });
+                            }
+                            grid.end_row();
+                        }
+                    });
+            });
 
-        if !any_rendered {
-            ui.label("Enable a chart using the toggles above to view playtime trends.");
+        if let Some(column) = sort_request {
+            if self.recent_sort == column {
+                self.recent_sort_descending = !self.recent_sort_descending;
+            } else {
+                self.recent_sort = column;
+                self.recent_sort_descending = true;
+            }
+        }
+        if edit_request.is_some() {
+            self.note_editor = edit_request;
+        }
+        if delete_request.is_some() {
+            self.pending_confirm = delete_request;
         }
-    }
 
-    fn render_insight_stats(&self, ui: &mut egui::Ui, analytics: &Analytics) {
-        ui.heading("Live statistics");
+        self.selected_sessions
+            .retain(|id| self.sessions.iter().any(|session| session.id == *id));
+        let selected = self.selected_sessions.len();
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    selected >= 2,
+                    egui::Button::new(format!("Merge selected ({selected})")),
+                )
+                .on_hover_text("Combine sessions split by a crash or quick relaunch")
+                .clicked()
+            {
+                self.request_merge();
+            }
+            if selected > 0 && ui.button("Clear selection").clicked() {
+                self.selected_sessions.clear();
+            }
+        });
 
-        let today = Local::now().date_naive();
-        let cutoff_7 = today - ChronoDuration::days(6);
-        let session_days: HashSet<NaiveDate> =
-            self.sessions.iter().map(|s| s.start.date_naive()).collect();
+        self.render_note_editor(ui);
+    }
 
-        let sessions_last_7: usize = self
-            .sessions
-            .iter()
-            .filter(|s| s.start.date_naive() >= cutoff_7)
-            .count();
-        let avg_last_7 = if sessions_last_7 > 0 {
-            analytics.minutes_last_7 / sessions_last_7 as f64
-        } else {
+    fn render_session_form(&mut self, ui: &mut egui::Ui) {
+        let form = &mut self.session_form;
+        Grid::new("add_session_grid").show(ui, |grid| {
+            grid.label("Start");
+            grid.add(
+                egui::TextEdit::singleline(&mut form.start)
+                    .hint_text("YYYY-MM-DD HH:MM")
+                    .desired_width(160.0),
+            );
+            grid.end_row();
+            grid.label("End");
+            grid.add(
+                egui::TextEdit::singleline(&mut form.end)
+                    .hint_text("YYYY-MM-DD HH:MM")
+                    .desired_width(160.0),
+            );
+            grid.end_row();
+            grid.label("Note");
+            grid.add(egui::TextEdit::singleline(&mut form.note).desired_width(240.0));
+            grid.end_row();
+        });
+        ui.checkbox(
+            &mut form.allow_overlap,
+            "Allow overlap with recorded sessions",
+        );
+        if ui.button("Add session").clicked() {
+            self.submit_session_form();
+        }
+    }
+
+    fn submit_session_form(&mut self) {
+        let form = &self.session_form;
+        let result = parse_local_datetime(&form.start).and_then(|start| {
+            let end = parse_local_datetime(&form.end)?;
+            self.store.add_manual_session(
+                start,
+                end,
+                form.note.trim().to_string(),
+                form.allow_overlap,
+            )
+        });
+        match result {
+            Ok(session) => {
+                self.session_form = SessionForm::default();
+                self.refresh_sessions();
+                self.set_status(format!(
+                    "Added session {} for {}.",
+                    session.start.format("%Y-%m-%d %H:%M"),
+                    format_duration(session.duration_minutes)
+                ));
+            }
+            Err(err) => self.set_status(format!("Could not add session: {err}")),
+        }
+    }
+
+    fn request_merge(&mut self) {
+        let ids = self.selected_sessions.iter().copied().collect::<Vec<_>>();
+        if sessions_are_adjacent(&self.sessions, &ids) {
+            self.merge_sessions(&ids);
+        } else {
+            self.pending_confirm = Some(ConfirmAction::MergeSessions(ids));
+        }
+    }
+
+    fn merge_sessions(&mut self, ids: &[Uuid]) {
+        match self.store.merge_sessions(ids) {
+            Ok(merged) => {
+                self.selected_sessions.clear();
+                self.refresh_sessions();
+                self.set_status(format!(
+                    "Merged {} sessions into one of {} starting {}.",
+                    ids.len(),
+                    format_duration(merged.duration_minutes),
+                    merged.start.format("%Y-%m-%d %H:%M")
+                ));
+            }
+            Err(err) => self.set_status(format!("Failed to merge sessions: {err}")),
+        }
+    }
+
+    fn render_onboarding(&mut self, ctx: &egui::Context) {
+        let Some(form) = &mut self.onboarding else {
+            return;
+        };
+        let mut finished = false;
+        let mut skipped = false;
+        egui::Window::new("Welcome to Star Citizen Playtime")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(
+                    "Tracking is automatic: whenever Star Citizen is running, the dashboard \
+                     records a session and saves it when the game closes.",
+                );
+                ui.label(format!(
+                    "Sessions shorter than {} minutes are ignored. Closing this window keeps \
+                     tracking in the background.",
+                    self.settings.min_session_minutes
+                ));
+                ui.add_space(8.0);
+                #[cfg(any(windows, target_os = "macos", target_os = "linux"))]
+                ui.checkbox(&mut form.run_on_login, STARTUP_LABEL);
+                ui.horizontal(|ui| {
+                    ui.label("Leaderboard username");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut form.username)
+                            .hint_text("optional")
+                            .desired_width(160.0),
+                    );
+                });
+                ui.label(
+                    RichText::new("Leave it empty to keep your playtime off the leaderboard.")
+                        .small()
+                        .weak(),
+                );
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Get started").clicked() {
+                        finished = true;
+                    }
+                    if ui.button("Skip").clicked() {
+                        skipped = true;
+                    }
+                });
+            });
+
+        if !finished && !skipped {
+            return;
+        }
+        let form = self.onboarding.take().unwrap_or_default();
+        self.settings.has_onboarded = true;
+        self.pending_settings.has_onboarded = true;
+        self.persist_visual_setting("Welcome aboard! Tracking starts when the game launches.");
+        if skipped {
+            return;
+        }
+        if form.run_on_login && !self.settings.run_on_login {
+            self.enable_startup();
+        }
+        let username = form.username.trim();
+        if !username.is_empty() {
+            self.pending_settings.leaderboard_username = username.to_string();
+            self.apply_leaderboard_settings();
+        }
+    }
+
+    fn render_confirmation(&mut self, ctx: &egui::Context) {
+        let Some(action) = &self.pending_confirm else {
+            return;
+        };
+        let mut confirmed = false;
+        let mut cancelled = false;
+        egui::Window::new("Please confirm")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(action.message());
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui.button(action.confirm_label()).clicked() {
+                        confirmed = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        cancelled = true;
+                    }
+                });
+            });
+
+        if cancelled {
+            self.pending_confirm = None;
+        } else if confirmed {
+            match self.pending_confirm.take() {
+                Some(ConfirmAction::MergeSessions(ids)) => self.merge_sessions(&ids),
+                Some(ConfirmAction::DeleteSession { id, started }) => {
+                    self.delete_session(id, &started)
+                }
+                Some(ConfirmAction::LeaveLeaderboard(username)) => self.leave_leaderboard(username),
+                Some(ConfirmAction::ClearActiveMarker(_)) => self.reset_active(),
+                None => {}
+            }
+        }
+    }
+
+    fn delete_session(&mut self, id: Uuid, started: &str) {
+        match self.store.delete_session(id) {
+            Ok(true) => {
+                self.selected_sessions.remove(&id);
+                if self
+                    .note_editor
+                    .as_ref()
+                    .is_some_and(|editor| editor.id == id)
+                {
+                    self.note_editor = None;
+                }
+                self.refresh_sessions();
+                self.set_status(format!("Deleted the session started {started}."));
+            }
+            Ok(false) => self.set_status("That session no longer exists."),
+            Err(err) => self.set_status(format!("Failed to delete session: {err}")),
+        }
+    }
+
+    fn render_note_editor(&mut self, ui: &mut egui::Ui) {
+        let Some(editor) = self.note_editor.as_mut() else {
+            return;
+        };
+        let mut save = false;
+        let mut cancel = false;
+        ui.add_space(8.0);
+        ui.group(|ui| {
+            ui.label(format!(
+                "Note & tags for session started {}",
+                editor.started
+            ));
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut editor.text)
+                    .hint_text("e.g. mining run, org event")
+                    .desired_width(320.0),
+            );
+            if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                save = true;
+            }
+            ui.horizontal(|ui| {
+                ui.label("Tags");
+                ui.add(
+                    egui::TextEdit::singleline(&mut editor.tags)
+                        .hint_text("mining, combat, exploration")
+                        .desired_width(260.0),
+                );
+            });
+            ui.horizontal(|ui| {
+                if ui.button("Save").clicked() {
+                    save = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    cancel = true;
+                }
+            });
+        });
+
+        if cancel {
+            self.note_editor = None;
+        } else if save {
+            if let Some(editor) = self.note_editor.take() {
+                self.save_note(
+                    editor.id,
+                    editor.text.trim().to_string(),
+                    parse_tags(&editor.tags),
+                );
+            }
+        }
+    }
+
+    fn save_note(&mut self, id: Uuid, note: String, tags: Vec<String>) {
+        let result = match self.store.update_note(id, note) {
+            Ok(true) => self.store.update_tags(id, tags),
+            other => other,
+        };
+        match result {
+            Ok(true) => {
+                self.refresh_sessions();
+                self.set_status("Session note and tags saved.");
+            }
+            Ok(false) => self.set_status("Session no longer exists; note not saved."),
+            Err(err) => self.set_status(format!("Failed to save note: {err}")),
+        }
+    }
+
+    fn render_charts(&self, ui: &mut egui::Ui, analytics: &Analytics) -> Option<ChartExport> {
+        let mut any_rendered = false;
+        let mut export = None;
+
+        if self.settings.show_daily_chart {
+            export = export.or(self.render_daily_chart(ui, analytics));
+            any_rendered = true;
+        }
+
+        if self.settings.show_weekly_chart {
+            if any_rendered {
+                ui.add_space(12.0);
+            }
+            export = export.or(self.render_weekly_chart(ui, analytics));
+            ui.add_space(12.0);
+            export = export.or(self.render_avg_session_chart(ui, analytics));
+            any_rendered = true;
+        }
+
+        if self.settings.show_hour_chart {
+            if any_rendered {
+                ui.add_space(12.0);
+            }
+            export = export.or(self.render_hour_chart(ui, analytics));
+            any_rendered = true;
+        }
+
+        if self.settings.show_weekday_chart {
+            if any_rendered {
+                ui.add_space(12.0);
+            }
+            export = export.or(self.render_weekday_chart(ui, analytics));
+            any_rendered = true;
+        }
+
+        if self.settings.show_duration_chart {
+            if any_rendered {
+                ui.add_space(12.0);
+            }
+            export = export.or(self.render_duration_chart(ui, analytics));
+            any_rendered = true;
+        }
+
+        if !any_rendered {
+            ui.label("Enable a chart using the toggles above to view playtime trends.");
+        }
+        export
+    }
+
+    fn render_insight_stats(&self, ui: &mut egui::Ui, analytics: &Analytics) {
+        ui.heading("Live statistics");
+
+        let utc_offset = self.settings.analytics_utc_offset();
+        let today = date_at(Local::now(), utc_offset);
+        let cutoff_7 = today - ChronoDuration::days(6);
+
+        let sessions_last_7: usize = self
+            .sessions
+            .iter()
+            .filter(|s| date_at(s.start, utc_offset) >= cutoff_7)
+            .count();
+        let avg_last_7 = if sessions_last_7 > 0 {
+            analytics.minutes_last_7 / sessions_last_7 as f64
+        } else {
             0.0
         };
 
@@ -1484,12 +3330,7 @@ impl PlaytimeApp {
             .map(|s| s.duration_minutes)
             .fold(0.0, f64::max);
 
-        let mut streak = 0;
-        let mut cursor = today;
-        while session_days.contains(&cursor) {
-            streak += 1;
-            cursor -= ChronoDuration::days(1);
-        }
+        let streak = current_streak(&self.sessions, today, utc_offset);
 
         let most_recent = self.sessions.last();
 
@@ -1501,6 +3342,18 @@ impl PlaytimeApp {
             "Avg session (7 days): {}",
             format_duration(avg_last_7)
         ));
+        render_period_delta(
+            ui,
+            "Last 7 days vs the 7 before",
+            analytics.minutes_last_7,
+            analytics.minutes_prev_7,
+        );
+        render_period_delta(
+            ui,
+            "Last 30 days vs the 30 before",
+            analytics.minutes_last_30,
+            analytics.minutes_prev_30,
+        );
         ui.label(format!(
             "Longest session recorded: {}",
             if longest_minutes > 0.0 {
@@ -1510,6 +3363,17 @@ impl PlaytimeApp {
             }
         ));
         ui.label(format!("Current daily streak: {} day(s)", streak));
+        if let Some(end) = self.settings.best_streak_end_date {
+            let best = self.settings.best_streak_days;
+            ui.label(if streak > 0 && streak >= best {
+                format!("Best streak: {best} day(s) (current)")
+            } else {
+                format!(
+                    "Best streak: {best} day(s) (ended {})",
+                    end.format("%Y-%m-%d")
+                )
+            });
+        }
         if let Some(session) = most_recent {
             ui.label(format!(
                 "Most recent session: {} for {}",
@@ -1526,10 +3390,34 @@ impl PlaytimeApp {
         }
     }
 
-    fn render_leaderboard(&self, ui: &mut egui::Ui) {
+    /// Returns the board the user switched to, if they picked a different one.
+    fn render_leaderboard(&self, ui: &mut egui::Ui) -> Option<LeaderboardPeriod> {
         ui.heading("Global leaderboard");
+        let mut period_request = None;
+        ui.horizontal(|ui| {
+            for (period, label) in [
+                (LeaderboardPeriod::All, "All time"),
+                (LeaderboardPeriod::Month, "This month"),
+            ] {
+                if ui
+                    .selectable_label(self.leaderboard_period == period, label)
+                    .clicked()
+                {
+                    period_request = Some(period);
+                }
+            }
+        });
         if self.leaderboard_inflight {
-            ui.label("Syncing leaderboard…");
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("Syncing leaderboard…");
+            });
+        }
+        if self.leaderboard_offline {
+            ui.colored_label(
+                Color32::from_rgb(230, 160, 80),
+                "Using local leaderboard: the endpoint could not be reached.",
+            );
         }
 
         if self.settings.sync_leaderboard {
@@ -1557,22 +3445,86 @@ impl PlaytimeApp {
         if self.leaderboard_entries.is_empty() {
             ui.label("No leaderboard data yet.");
         } else {
+            let identity = self.leaderboard_identity();
+            let own_index = self.own_leaderboard_index();
+            // Row 0 is the header, so entry `idx` sits on grid row `idx + 1`.
+            let own_row = own_index.map(|idx| idx + 1);
             Grid::new("leaderboard_grid")
-                .striped(true)
+                .with_row_color(move |row, style| {
+                    if Some(row) == own_row {
+                        Some(style.visuals.selection.bg_fill.gamma_multiply(0.5))
+                    } else {
+                        (row % 2 == 1).then_some(style.visuals.faint_bg_color)
+                    }
+                })
                 .show(ui, |grid| {
                     grid.label(RichText::new("#").strong());
                     grid.label(RichText::new("Commander").strong());
                     grid.label(RichText::new("Hours").strong());
+                    grid.label(RichText::new("Last active").strong());
                     grid.end_row();
                     for (idx, entry) in self.leaderboard_entries.iter().enumerate() {
-                        grid.label((idx + 1).to_string());
-                        grid.label(entry.username.clone());
-                        grid.label(format!("{:.2}", entry.total_minutes / 60.0));
+                        if Some(idx) == own_index {
+                            grid.label(RichText::new((idx + 1).to_string()).strong());
+                            let name = if self.settings.leaderboard_anonymize {
+                                format!("You ({})", self.settings.leaderboard_username.trim())
+                            } else {
+                                format!("{} (you)", entry.username)
+                            };
+                            let label = grid.label(RichText::new(name).strong());
+                            if self.settings.leaderboard_anonymize {
+                                label.on_hover_text(format!("Shown to others as {identity}"));
+                            }
+                            grid.label(
+                                RichText::new(format!("{:.2}", entry.total_minutes / 60.0))
+                                    .strong(),
+                            );
+                        } else {
+                            grid.label((idx + 1).to_string());
+                            grid.label(entry.username.clone());
+                            grid.label(format!("{:.2}", entry.total_minutes / 60.0));
+                        }
+                        match entry.last_submission {
+                            Some(at) => grid.label(format_last_active(at)),
+                            None => grid.label(RichText::new("—").weak()),
+                        };
                         grid.end_row();
                     }
                 });
         }
 
+        // Rank lookups cover the all-time board only.
+        if self.leaderboard_period == LeaderboardPeriod::All {
+            if let Some(rank) = &self.leaderboard_rank {
+                if self.own_leaderboard_index().is_some() {
+                    ui.label(
+                        RichText::new(format!(
+                            "Your rank: #{} of {}",
+                            rank.rank, rank.total_players
+                        ))
+                        .strong(),
+                    );
+                } else {
+                    ui.label(
+                        RichText::new(format!(
+                            "You: #{} of {} ({:.2} h)",
+                            rank.rank,
+                            rank.total_players,
+                            rank.total_minutes / 60.0
+                        ))
+                        .strong(),
+                    );
+                }
+            } else if !self.settings.leaderboard_username.trim().is_empty()
+                && !self.leaderboard_entries.is_empty()
+            {
+                ui.label(format!(
+                    "{} is not on the leaderboard yet.",
+                    self.settings.leaderboard_username.trim()
+                ));
+            }
+        }
+
         if let Some(success) = self.last_leaderboard_success {
             ui.label(format!(
                 "Last updated {} ago.",
@@ -1585,13 +3537,15 @@ impl PlaytimeApp {
         } else if self.settings.leaderboard_username.trim().is_empty() {
             ui.label("Add a username in settings to appear on the leaderboard.");
         }
+
+        period_request.filter(|period| *period != self.leaderboard_period)
     }
 
-    fn render_daily_chart(&self, ui: &mut egui::Ui, analytics: &Analytics) {
+    fn render_daily_chart(&self, ui: &mut egui::Ui, analytics: &Analytics) -> Option<ChartExport> {
         let mut data = analytics.recent_daily.clone();
         if data.is_empty() {
             ui.label("Daily chart not available yet.");
-            return;
+            return None;
         }
 
         data.reverse();
@@ -1616,7 +3570,14 @@ impl PlaytimeApp {
         let chart_bars = bars;
         let max_hours = max_hours;
 
-        ui.heading("Daily playtime (last 14 days)");
+        let export = chart_heading(ui, "Daily playtime (last 14 days)").then(|| ChartExport {
+            id: "daily_playtime_plot".to_string(),
+            title: "Daily playtime (last 14 days)".to_string(),
+            labels: label_arc.to_vec(),
+            values: data.iter().map(|(_, minutes)| minutes / 60.0).collect(),
+            series_name: "Hours per day".to_string(),
+            color: bar_color,
+        });
         Plot::new("daily_playtime_plot")
             .height(180.0)
             .allow_zoom(false)
@@ -1661,13 +3622,105 @@ impl PlaytimeApp {
                 chart = chart.color(bar_color).name("Hours per day");
                 plot_ui.bar_chart(chart);
             });
+        export
     }
 
-    fn render_weekly_chart(&self, ui: &mut egui::Ui, analytics: &Analytics) {
+    fn render_hour_chart(&self, ui: &mut egui::Ui, analytics: &Analytics) -> Option<ChartExport> {
+        let labels = (0..24).map(|hour| format!("{hour:02}")).collect();
+        let hours = analytics
+            .play_by_hour
+            .iter()
+            .map(|minutes| minutes / 60.0)
+            .collect();
+        render_bar_chart(
+            ui,
+            "hour_of_day_plot",
+            "When I play (hour of day)",
+            labels,
+            hours,
+            Color32::from_rgb(170, 120, 255),
+            "Hours played",
+        )
+    }
+
+    fn render_weekday_chart(
+        &self,
+        ui: &mut egui::Ui,
+        analytics: &Analytics,
+    ) -> Option<ChartExport> {
+        let labels = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"]
+            .iter()
+            .map(|day| day.to_string())
+            .collect();
+        let hours = analytics
+            .weekday_totals
+            .iter()
+            .map(|minutes| minutes / 60.0)
+            .collect();
+        render_bar_chart(
+            ui,
+            "weekday_plot",
+            "Playtime by weekday",
+            labels,
+            hours,
+            Color32::from_rgb(94, 201, 146),
+            "Hours played",
+        )
+    }
+
+    fn render_avg_session_chart(
+        &self,
+        ui: &mut egui::Ui,
+        analytics: &Analytics,
+    ) -> Option<ChartExport> {
+        if analytics.avg_session_by_week.is_empty() {
+            return None;
+        }
+        let (labels, minutes) = analytics
+            .avg_session_by_week
+            .iter()
+            .rev()
+            .map(|((year, week), minutes)| (format!("{year}-W{week:02}"), *minutes))
+            .unzip();
+        render_chart(
+            ui,
+            "avg_session_plot",
+            "Average session length by week (minutes)",
+            labels,
+            minutes,
+            Color32::from_rgb(240, 140, 170),
+            "Average session",
+            format_duration,
+        )
+    }
+
+    fn render_duration_chart(
+        &self,
+        ui: &mut egui::Ui,
+        analytics: &Analytics,
+    ) -> Option<ChartExport> {
+        let (labels, counts) = analytics
+            .duration_histogram
+            .iter()
+            .map(|(label, count)| (label.clone(), *count as f64))
+            .unzip();
+        render_chart(
+            ui,
+            "duration_plot",
+            "Session lengths",
+            labels,
+            counts,
+            Color32::from_rgb(120, 200, 230),
+            "Sessions",
+            |count| format!("{count:.0} sessions"),
+        )
+    }
+
+    fn render_weekly_chart(&self, ui: &mut egui::Ui, analytics: &Analytics) -> Option<ChartExport> {
         let mut data = analytics.recent_weekly.clone();
         if data.is_empty() {
             ui.label("Weekly chart not available yet.");
-            return;
+            return None;
         }
 
         data.reverse();
@@ -1692,7 +3745,14 @@ impl PlaytimeApp {
         let chart_bars = bars;
         let max_hours = max_hours;
 
-        ui.heading("Weekly playtime (last 8 weeks)");
+        let export = chart_heading(ui, "Weekly playtime (last 8 weeks)").then(|| ChartExport {
+            id: "weekly_playtime_plot".to_string(),
+            title: "Weekly playtime (last 8 weeks)".to_string(),
+            labels: label_arc.to_vec(),
+            values: data.iter().map(|(_, minutes)| minutes / 60.0).collect(),
+            series_name: "Hours per week".to_string(),
+            color: bar_color,
+        });
         Plot::new("weekly_playtime_plot")
             .height(180.0)
             .allow_zoom(false)
@@ -1737,6 +3797,7 @@ impl PlaytimeApp {
                 chart = chart.color(bar_color).name("Hours per week");
                 plot_ui.bar_chart(chart);
             });
+        export
     }
 
     fn render_top_days(&self, ui: &mut egui::Ui, analytics: &Analytics) {
@@ -1759,6 +3820,53 @@ impl PlaytimeApp {
         });
     }
 
+    fn render_longest_sessions(&self, ui: &mut egui::Ui, analytics: &Analytics) {
+        if analytics.longest_sessions.is_empty() {
+            return;
+        }
+        ui.heading("Longest Sessions");
+        Grid::new("longest_sessions_grid")
+            .striped(true)
+            .show(ui, |grid| {
+                grid.label(RichText::new("#").strong());
+                grid.label(RichText::new("Start").strong());
+                grid.label(RichText::new("Duration").strong());
+                grid.end_row();
+                for (idx, session) in analytics.longest_sessions.iter().enumerate() {
+                    grid.label((idx + 1).to_string());
+                    grid.label(session.start.format("%Y-%m-%d %H:%M").to_string());
+                    grid.label(format_duration(session.duration_minutes));
+                    grid.end_row();
+                }
+            });
+    }
+
+    fn render_tag_breakdown(&self, ui: &mut egui::Ui, analytics: &Analytics) {
+        ui.heading("Activity breakdown");
+        if analytics.tag_totals.is_empty() {
+            ui.label("Tag sessions in Recent Sessions to see an activity breakdown.");
+            return;
+        }
+        render_share_grid(
+            ui,
+            "tag_breakdown_grid",
+            "Tag",
+            &analytics.tag_totals,
+            analytics.total_minutes,
+        );
+    }
+
+    fn render_game_breakdown(&self, ui: &mut egui::Ui, analytics: &Analytics) {
+        ui.heading("Playtime by game");
+        render_share_grid(
+            ui,
+            "game_breakdown_grid",
+            "Game",
+            &analytics.game_totals,
+            analytics.total_minutes,
+        );
+    }
+
     fn render_settings(&mut self, ui: &mut egui::Ui) {
         ui.group(|ui| {
             ui.heading("Monitor Settings");
@@ -1778,6 +3886,180 @@ impl PlaytimeApp {
                         .speed(1.0),
                 );
             });
+            ui.checkbox(
+                &mut self.pending_settings.discard_short_sessions,
+                "Discard sessions shorter than the minimum",
+            );
+            ui.horizontal(|ui| {
+                let mut separate = self.pending_settings.recovered_min_session_minutes.is_some();
+                if ui
+                    .checkbox(&mut separate, "Recovered sessions minimum (minutes)")
+                    .on_hover_text(
+                        "Sessions restored after a crash or unclean shutdown use this minimum instead",
+                    )
+                    .changed()
+                {
+                    self.pending_settings.recovered_min_session_minutes = separate
+                        .then_some(self.pending_settings.min_session_minutes);
+                }
+                if let Some(minutes) = self.pending_settings.recovered_min_session_minutes.as_mut()
+                {
+                    ui.add(
+                        egui::DragValue::new(minutes)
+                            .clamp_range(0..=240)
+                            .speed(1.0),
+                    );
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("Keep session through game restarts of up to (seconds)");
+                ui.add(
+                    egui::DragValue::new(&mut self.pending_settings.grace_seconds)
+                        .clamp_range(0..=600)
+                        .speed(1.0),
+                )
+                .on_hover_text("A relaunch within this window continues the current session.");
+            });
+            ui.horizontal(|ui| {
+                ui.label("Merge with the previous session if restarted within (minutes, 0 = never)");
+                ui.add(
+                    egui::DragValue::new(&mut self.pending_settings.merge_gap_minutes)
+                        .clamp_range(0..=240)
+                        .speed(1.0),
+                )
+                .on_hover_text(
+                    "Applied when a session is saved; the time between the two is not counted.",
+                );
+            });
+            ui.horizontal(|ui| {
+                ui.label("End session when idle for (minutes, 0 = never)");
+                ui.add(
+                    egui::DragValue::new(&mut self.pending_settings.idle_timeout_minutes)
+                        .clamp_range(0..=1440)
+                        .speed(1.0),
+                );
+            });
+            ui.horizontal(|ui| {
+                ui.label("Close stale session after (minutes, 0 = never)");
+                ui.add(
+                    egui::DragValue::new(
+                        &mut self.pending_settings.auto_reset_stale_active_minutes,
+                    )
+                    .clamp_range(0..=10080)
+                    .speed(5.0),
+                );
+            });
+            #[cfg(windows)]
+            ui.horizontal(|ui| {
+                ui.label("Notify when a session");
+                ui.checkbox(&mut self.pending_settings.notify_on_session_start, "starts");
+                ui.checkbox(&mut self.pending_settings.notify_on_session_end, "is saved");
+            });
+            ui.horizontal(|ui| {
+                ui.label("Theme");
+                let mut theme = self.settings.theme;
+                for (option, label) in [
+                    (Theme::Dark, "Dark"),
+                    (Theme::Light, "Light"),
+                    (Theme::System, "System"),
+                ] {
+                    ui.selectable_value(&mut theme, option, label);
+                }
+                if theme != self.settings.theme {
+                    self.settings.theme = theme;
+                    self.pending_settings.theme = theme;
+                    self.persist_visual_setting(format!("Switched to the {theme:?} theme."));
+                }
+            });
+            #[cfg(windows)]
+            ui.horizontal(|ui| {
+                ui.label("Tray icon color");
+                let mut color = self.settings.tray_icon_color;
+                if ui
+                    .color_edit_button_srgb(&mut color)
+                    .on_hover_text("The icon turns green while a session is being tracked.")
+                    .changed()
+                {
+                    self.settings.tray_icon_color = color;
+                    self.pending_settings.tray_icon_color = color;
+                    self.persist_visual_setting("Tray icon color updated.");
+                }
+            });
+            ui.checkbox(
+                &mut self.pending_settings.write_status_file,
+                "Write status.txt / status.json for stream overlays",
+            )
+            .on_hover_text(
+                "Updated every poll in the data folder; point an OBS text source at status.txt.",
+            );
+            ui.checkbox(
+                &mut self.pending_settings.write_event_log,
+                "Log session events to events.jsonl",
+            )
+            .on_hover_text(
+                "One JSON line per session start, poll, recovery and save in the data folder, for debugging.",
+            );
+            ui.horizontal(|ui| {
+                ui.checkbox(
+                    &mut self.pending_settings.enable_local_api,
+                    "Serve status JSON on localhost port",
+                );
+                ui.add(
+                    egui::DragValue::new(&mut self.pending_settings.local_api_port)
+                        .clamp_range(1024..=65535),
+                );
+            });
+            if let Some(api) = &self.local_api {
+                ui.label(
+                    egui::RichText::new(format!(
+                        "Overlay API: http://{0}/status and http://{0}/analytics",
+                        api.addr()
+                    ))
+                    .small(),
+                );
+            }
+            ui.horizontal(|ui| {
+                ui.label("Tracked processes");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.process_tokens_input)
+                        .hint_text("starcitizen, star citizen")
+                        .desired_width(240.0),
+                )
+                .on_hover_text(
+                    "Comma-separated and case-insensitive. Each entry is a fragment, a full name or a regular expression depending on the match mode.",
+                );
+            });
+            ui.horizontal(|ui| {
+                ui.label("Match mode");
+                for (mode, label, hint) in [
+                    (
+                        MatchMode::Contains,
+                        "Contains",
+                        "The process name contains an entry.",
+                    ),
+                    (
+                        MatchMode::Exact,
+                        "Exact",
+                        "The process name is an entry, e.g. StarCitizen.exe (.exe optional).",
+                    ),
+                    (
+                        MatchMode::Regex,
+                        "Regex",
+                        "Each entry is a regular expression searched for in the process name.",
+                    ),
+                ] {
+                    ui.selectable_value(&mut self.pending_settings.process_match_mode, mode, label)
+                        .on_hover_text(hint);
+                }
+            });
+            #[cfg(windows)]
+            ui.checkbox(
+                &mut self.pending_settings.detect_window_title,
+                format!("Also detect a \"{DEFAULT_WINDOW_TITLE}\" window"),
+            )
+            .on_hover_text(
+                "Fallback for when the game's process name is not recognized. Checks window titles only when no tracked process is found.",
+            );
             ui.horizontal(|ui| {
                 ui.label("Dashboard refresh (seconds)");
                 ui.add(
@@ -1786,34 +4068,59 @@ impl PlaytimeApp {
                         .speed(0.2),
                 );
             });
-            if ui.button("Apply monitor settings").clicked() {
-                self.apply_monitor_settings();
-            }
+            ui.horizontal(|ui| {
+                if ui.button("Apply monitor settings").clicked() {
+                    self.apply_monitor_settings();
+                }
+                let pause_label = if self.settings.tracking_paused {
+                    "Resume tracking"
+                } else {
+                    "Pause tracking"
+                };
+                if ui.button(pause_label).clicked() {
+                    self.set_tracking_paused(!self.settings.tracking_paused);
+                }
+            });
 
             ui.separator();
-            if ui.button("Refresh now").clicked() {
+            if ui.button("Refresh now").on_hover_text("Ctrl+R").clicked() {
                 self.refresh_sessions();
                 self.set_status("Sessions refreshed.");
             }
 
-            #[cfg(windows)]
+            #[cfg(any(windows, target_os = "macos", target_os = "linux"))]
             {
                 let mut run_on_login = self.settings.run_on_login;
-                if ui
-                    .checkbox(&mut run_on_login, "Start dashboard with Windows")
-                    .changed()
-                {
+                if ui.checkbox(&mut run_on_login, STARTUP_LABEL).changed() {
                     if run_on_login {
                         self.enable_startup();
                     } else {
                         self.disable_startup();
                     }
                 }
+                #[cfg(windows)]
+                if let Some(command) = &self.stale_startup_command {
+                    let (registered, _) = startup::split_command(command);
+                    let mut fix = false;
+                    ui.horizontal(|ui| {
+                        ui.colored_label(
+                            Color32::from_rgb(230, 160, 80),
+                            "Startup entry points to a different location.",
+                        )
+                        .on_hover_text(registered.display().to_string());
+                        fix = ui.button("Fix").clicked();
+                    });
+                    if fix {
+                        self.fix_startup_entry();
+                    }
+                }
+                #[cfg(windows)]
+                self.render_startup_method(ui);
             }
 
-            #[cfg(not(windows))]
+            #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
             {
-                ui.label("Startup registration is only available on Windows.");
+                ui.label("Startup registration is not available on this platform.");
             }
 
             ui.separator();
@@ -1830,10 +4137,19 @@ impl PlaytimeApp {
                 ui.label("Leaderboard username");
                 ui.add(
                     egui::TextEdit::singleline(&mut self.pending_settings.leaderboard_username)
+                        .char_limit(MAX_USERNAME_CHARS)
                         .hint_text("Commander name")
                         .desired_width(200.0),
                 );
             });
+            ui.checkbox(
+                &mut self.pending_settings.leaderboard_anonymize,
+                "Show a pseudonymous ID on the leaderboard",
+            )
+            .on_hover_text(
+                "Submits a short hash of the username instead of the name. This is pseudonymous, \
+                 not anonymous: anyone who knows your name can compute the same ID.",
+            );
 
             ui.horizontal(|ui| {
                 ui.label("Leaderboard endpoint");
@@ -1850,6 +4166,22 @@ impl PlaytimeApp {
                 .small(),
             );
 
+            ui.horizontal(|ui| {
+                ui.label("Retries on network errors");
+                ui.add(
+                    egui::DragValue::new(&mut self.pending_settings.leaderboard_retries)
+                        .clamp_range(0..=10),
+                );
+            });
+            ui.checkbox(
+                &mut self.pending_settings.leaderboard_local_fallback,
+                "Use the local leaderboard when the endpoint is unreachable",
+            )
+            .on_hover_text("The endpoint is checked when the dashboard starts or settings are applied.");
+            if self.leaderboard_offline && ui.button("Retry endpoint").clicked() {
+                self.retry_leaderboard_endpoint();
+            }
+
             if ui.button("Apply leaderboard settings").clicked() {
                 self.apply_leaderboard_settings();
             }
@@ -1910,20 +4242,51 @@ impl PlaytimeApp {
                 ui.label("Leaderboard sync is disabled.");
             }
 
+            let username = self.settings.leaderboard_username.trim();
+            if !username.is_empty()
+                && ui
+                    .add_enabled(
+                        self.leaderboard_client.is_some() && !self.leaderboard_inflight,
+                        egui::Button::new("Remove me from leaderboard"),
+                    )
+                    .on_hover_text(format!("Delete the entry for {username}"))
+                    .clicked()
+            {
+                self.pending_confirm = Some(ConfirmAction::LeaveLeaderboard(
+                    self.leaderboard_identity(),
+                ));
+            }
+
             ui.separator();
             ui.horizontal(|ui| {
-                if ui.button("Export CSV...").clicked() {
+                if ui.button("Export CSV...").on_hover_text("Ctrl+E").clicked() {
                     self.export_sessions();
                 }
                 if ui.button("Clear active session marker").clicked() {
-                    self.reset_active();
+                    let elapsed = self.snapshot.lock().ok().and_then(|snapshot| {
+                        snapshot
+                            .active_session
+                            .as_ref()
+                            .map(|active| format_duration(active_session_minutes(active)))
+                    });
+                    self.pending_confirm = Some(ConfirmAction::ClearActiveMarker(elapsed));
                 }
                 if ui.button("Open data folder").clicked() {
                     self.open_data_dir();
                 }
+                #[cfg(windows)]
+                if ui
+                    .button("Open tray log")
+                    .on_hover_text(tray_log_path().display().to_string())
+                    .clicked()
+                {
+                    self.open_tray_log();
+                }
             });
 
             ui.label(format!("Data folder: {}", self.store.data_dir().display()));
+            #[cfg(windows)]
+            self.render_tray_log(ui);
             ui.separator();
             ui.horizontal(|ui| {
                 if ui.button("Join support (Discord)").clicked() {
@@ -1938,6 +4301,33 @@ impl PlaytimeApp {
         });
     }
 
+    /// Ctrl+1/Ctrl+2 switch tabs, Ctrl+R reloads sessions and Ctrl+E opens the CSV export.
+    fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        let shortcut = |key| KeyboardShortcut::new(Modifiers::COMMAND, key);
+        let (overview, insights, refresh, export) = ctx.input_mut(|input| {
+            (
+                input.consume_shortcut(&shortcut(Key::Num1)),
+                input.consume_shortcut(&shortcut(Key::Num2)),
+                input.consume_shortcut(&shortcut(Key::R)),
+                input.consume_shortcut(&shortcut(Key::E)),
+            )
+        });
+
+        if overview {
+            self.selected_tab = DashboardTab::Overview;
+        }
+        if insights {
+            self.selected_tab = DashboardTab::Insights;
+        }
+        if refresh {
+            self.refresh_sessions();
+            self.set_status("Sessions refreshed.");
+        }
+        if export {
+            self.export_sessions();
+        }
+    }
+
     fn export_sessions(&mut self) {
         if let Some(path) = FileDialog::new()
             .set_file_name("star_citizen_playtime.csv")
@@ -1954,8 +4344,57 @@ impl PlaytimeApp {
         }
     }
 
+    fn export_chart_image(&mut self, chart: ChartExport) {
+        let file_name = format!("{}.png", chart.id.trim_end_matches("_plot"));
+        if let Some(path) = FileDialog::new().set_file_name(file_name).save_file() {
+            let [r, g, b, _] = chart.color.to_array();
+            match charts::render_chart_image(
+                &path,
+                &chart.title,
+                &chart.labels,
+                &chart.values,
+                &chart.series_name,
+                (r, g, b),
+            ) {
+                Ok(actual) => self.set_status(format!("Saved chart to {}", actual.display())),
+                Err(err) => self.set_status(format!("Failed to save chart: {err}")),
+            }
+        }
+    }
+
+    fn export_share_image(&mut self) {
+        if let Some(path) = FileDialog::new()
+            .set_file_name("star_citizen_playtime.png")
+            .save_file()
+        {
+            let commander = if self.settings.share_include_username {
+                let name = self.settings.leaderboard_username.trim();
+                (!name.is_empty()).then_some(name)
+            } else {
+                None
+            };
+            let result = match &self.analytics {
+                Some(analytics) => charts::render_share_image(&path, analytics, commander),
+                None => Err(anyhow!("No playtime recorded yet.")),
+            };
+            match result {
+                Ok(actual) => {
+                    self.set_status(format!("Saved share image to {}", actual.display()));
+                }
+                Err(err) => {
+                    self.set_status(format!("Failed to create share image: {err}"));
+                }
+            }
+        }
+    }
+
+    /// Drops the session in progress. The monitor keeps it in memory and would rewrite the
+    /// marker on its next poll, so it is stopped first (saving the marker) and restarted after.
     fn reset_active(&mut self) {
-        match self.store.clear_active() {
+        self.stop_monitor();
+        let result = self.store.clear_active();
+        self.start_monitor();
+        match result {
             Ok(()) => {
                 self.refresh_sessions();
                 self.set_status("Cleared active session marker.");
@@ -1964,6 +4403,18 @@ impl PlaytimeApp {
         }
     }
 
+    fn set_tracking_paused(&mut self, paused: bool) {
+        self.pause_flag.store(paused, Ordering::SeqCst);
+        self.settings.tracking_paused = paused;
+        self.pending_settings.tracking_paused = paused;
+        let message = if paused {
+            "Tracking paused. Any session in progress will be saved at its last activity."
+        } else {
+            "Tracking resumed."
+        };
+        self.persist_visual_setting(message);
+    }
+
     fn persist_visual_setting<S: Into<String>>(&mut self, message: S) {
         match self.settings_store.save(&self.settings) {
             Ok(()) => self.set_status(message),
@@ -1980,12 +4431,72 @@ impl PlaytimeApp {
         }
     }
 
+    /// Opens the tray log with the default app for `.log` files.
+    #[cfg(windows)]
+    fn open_tray_log(&mut self) {
+        let path = tray_log_path();
+        if !path.exists() {
+            self.set_status("The tray log has not been written yet.");
+            return;
+        }
+        match Command::new("explorer").arg(&path).status() {
+            Ok(_) => self.set_status("Opened tray log."),
+            Err(err) => self.set_status(format!("Failed to open tray log: {err}")),
+        }
+    }
+
+    #[cfg(windows)]
+    fn render_tray_log(&mut self, ui: &mut egui::Ui) {
+        ui.collapsing("Tray log", |ui| {
+            let mut debug = self.settings.tray_debug_logging;
+            if ui
+                .checkbox(&mut debug, "Log every tray event")
+                .on_hover_text(format!(
+                    "Errors are always logged. Setting {TRAY_DEBUG_ENV}=1 also enables this."
+                ))
+                .changed()
+            {
+                self.settings.tray_debug_logging = debug;
+                self.pending_settings.tray_debug_logging = debug;
+                set_tray_debug(debug);
+                let message = if debug {
+                    "Verbose tray logging enabled."
+                } else {
+                    "Verbose tray logging disabled."
+                };
+                self.persist_visual_setting(message);
+            }
+            let label = if self.tray_log_tail.is_some() {
+                "Reload"
+            } else {
+                "Show log"
+            };
+            if ui.button(label).clicked() {
+                self.tray_log_tail =
+                    Some(match read_log_tail(&tray_log_path(), TRAY_LOG_TAIL_LINES) {
+                        Ok(text) if text.is_empty() => "The tray log is empty.".to_string(),
+                        Ok(text) => text,
+                        Err(err) => format!("Failed to read tray log: {err}"),
+                    });
+            }
+            if let Some(text) = &self.tray_log_tail {
+                ScrollArea::vertical()
+                    .id_source("tray_log_scroll")
+                    .max_height(220.0)
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        ui.label(RichText::new(text.as_str()).monospace().small());
+                    });
+            }
+        });
+    }
+
     #[cfg(not(windows))]
     fn open_data_dir(&mut self) {
         self.set_status(format!("Data folder: {}", self.store.data_dir().display()));
     }
 
-    #[cfg(windows)]
+    #[cfg(any(windows, target_os = "macos", target_os = "linux"))]
     fn enable_startup(&mut self) {
         let exe = match env::current_exe() {
             Ok(path) => path,
@@ -1994,12 +4505,21 @@ impl PlaytimeApp {
                 return;
             }
         };
-        match startup::install(&exe, "") {
+        match startup::install_with(
+            self.settings.startup_method,
+            &exe,
+            "",
+            self.settings.startup_delay_seconds,
+        ) {
             Ok(()) => {
+                #[cfg(windows)]
+                {
+                    self.stale_startup_command = None;
+                }
                 self.settings.run_on_login = true;
                 self.pending_settings.run_on_login = true;
                 match self.settings_store.save(&self.settings) {
-                    Ok(()) => self.set_status("Dashboard will start with Windows."),
+                    Ok(()) => self.set_status("Dashboard will start at login."),
                     Err(err) => self.set_status(format!(
                         "Enabled startup but failed to save settings: {err}"
                     )),
@@ -2010,13 +4530,99 @@ impl PlaytimeApp {
     }
 
     #[cfg(windows)]
+    fn render_startup_method(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Start using");
+            let mut method = self.settings.startup_method;
+            ui.selectable_value(&mut method, StartupMethod::RunKey, "Run key")
+                .on_hover_text("Starts right after login (default).");
+            ui.selectable_value(&mut method, StartupMethod::TaskScheduler, "Task Scheduler")
+                .on_hover_text("Supports a delay after login.");
+            if method != self.settings.startup_method {
+                self.switch_startup_method(method);
+            }
+        });
+        if self.settings.startup_method != StartupMethod::TaskScheduler {
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.label("Delay after login");
+            ui.add(
+                egui::DragValue::new(&mut self.pending_settings.startup_delay_seconds)
+                    .clamp_range(0..=MAX_STARTUP_DELAY_SECONDS)
+                    .suffix(" s"),
+            );
+            let changed =
+                self.pending_settings.startup_delay_seconds != self.settings.startup_delay_seconds;
+            if ui
+                .add_enabled(changed, egui::Button::new("Apply"))
+                .clicked()
+            {
+                self.settings.startup_delay_seconds = self.pending_settings.startup_delay_seconds;
+                if self.settings.run_on_login {
+                    self.enable_startup();
+                } else {
+                    self.persist_visual_setting("Startup delay updated.");
+                }
+            }
+        });
+    }
+
+    /// Moves an existing registration to the new method so the app is not started twice.
+    #[cfg(windows)]
+    fn switch_startup_method(&mut self, method: StartupMethod) {
+        if self.settings.run_on_login
+            && let Err(err) = startup::uninstall_with(self.settings.startup_method)
+        {
+            self.set_status(format!(
+                "Failed to remove the previous startup entry: {err}"
+            ));
+            return;
+        }
+        self.settings.startup_method = method;
+        self.pending_settings.startup_method = method;
+        if self.settings.run_on_login {
+            self.enable_startup();
+        } else {
+            self.persist_visual_setting("Startup method updated.");
+        }
+    }
+
+    /// Re-registers the startup entry for the running executable, keeping its arguments.
+    #[cfg(windows)]
+    fn fix_startup_entry(&mut self) {
+        let Some(command) = self.stale_startup_command.clone() else {
+            return;
+        };
+        let exe = match env::current_exe() {
+            Ok(path) => path,
+            Err(err) => {
+                self.set_status(format!("Failed to resolve executable path: {err}"));
+                return;
+            }
+        };
+        let (_, args) = startup::split_command(&command);
+        match startup::install(&exe, args) {
+            Ok(()) => {
+                self.stale_startup_command = None;
+                self.set_status(format!("Startup entry now points to {}.", exe.display()));
+            }
+            Err(err) => self.set_status(format!("Failed to update startup entry: {err}")),
+        }
+    }
+
+    #[cfg(any(windows, target_os = "macos", target_os = "linux"))]
     fn disable_startup(&mut self) {
-        match startup::uninstall() {
+        match startup::uninstall_with(self.settings.startup_method) {
             Ok(()) => {
+                #[cfg(windows)]
+                {
+                    self.stale_startup_command = None;
+                }
                 self.settings.run_on_login = false;
                 self.pending_settings.run_on_login = false;
                 match self.settings_store.save(&self.settings) {
-                    Ok(()) => self.set_status("Removed Windows startup entry."),
+                    Ok(()) => self.set_status("Removed startup entry."),
                     Err(err) => self.set_status(format!(
                         "Disabled startup but failed to save settings: {err}"
                     )),
@@ -2026,14 +4632,14 @@ impl PlaytimeApp {
         }
     }
 
-    #[cfg(not(windows))]
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
     fn enable_startup(&mut self) {
-        self.set_status("Startup registration is only available on Windows.");
+        self.set_status("Startup registration is not available on this platform.");
     }
 
-    #[cfg(not(windows))]
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
     fn disable_startup(&mut self) {
-        self.set_status("Startup registration is only available on Windows.");
+        self.set_status("Startup registration is not available on this platform.");
     }
 
     fn set_status<S: Into<String>>(&mut self, message: S) {
@@ -2041,6 +4647,45 @@ impl PlaytimeApp {
         self.status_since = Some(Instant::now());
     }
 
+    fn update_inflight(&self) -> bool {
+        #[cfg(windows)]
+        let inflight = self.update_inflight;
+        #[cfg(not(windows))]
+        let inflight = false;
+        inflight
+    }
+
+    /// Spinners for network jobs still running in the background, shown under every tab.
+    fn render_background_jobs(&self, ui: &mut egui::Ui) {
+        let update_inflight = self.update_inflight();
+        if !self.leaderboard_inflight && !update_inflight {
+            return;
+        }
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.spinner();
+            if self.leaderboard_inflight {
+                ui.label("Syncing leaderboard…");
+            }
+            if update_inflight {
+                ui.label("Checking for updates…");
+            }
+        });
+    }
+
+    /// Input events repaint on their own; this only covers what changes without user input.
+    /// Background jobs are polled quickly, an active session ticks once a second, and an idle
+    /// dashboard wakes at the refresh interval to pick up new sessions and monitor changes.
+    fn next_repaint_delay(&self, snapshot: &MonitorSnapshot) -> Duration {
+        if self.leaderboard_inflight || self.update_inflight() {
+            Duration::from_millis(200)
+        } else if snapshot.active_session.is_some() || self.status_message.is_some() {
+            Duration::from_secs(1)
+        } else {
+            self.refresh_interval
+        }
+    }
+
     fn maybe_clear_status(&mut self) {
         if let Some(since) = self.status_since {
             if since.elapsed() > Duration::from_secs(10) {
@@ -2079,7 +4724,7 @@ impl eframe::App for PlaytimeApp {
         }
 
         self.maybe_clear_status();
-        self.ensure_style(ctx);
+        self.ensure_style(ctx, frame.info().system_theme);
 
         #[cfg(windows)]
         self.process_tray(ctx, frame);
@@ -2090,10 +4735,18 @@ impl eframe::App for PlaytimeApp {
         #[cfg(windows)]
         self.maybe_check_for_updates();
 
-        if self.last_refresh.elapsed() >= self.refresh_interval {
+        let files_changed = self
+            .data_watcher
+            .as_mut()
+            .is_some_and(|watcher| watcher.poll(DEFAULT_DEBOUNCE));
+        if files_changed {
             self.refresh_sessions();
+        } else if self.last_refresh.elapsed() >= self.refresh_interval {
+            self.refresh_sessions_if_changed();
         }
+        self.check_monitor();
 
+        self.handle_shortcuts(ctx);
         self.poll_leaderboard_updates();
         self.maybe_sync_leaderboard();
 
@@ -2105,6 +4758,7 @@ impl eframe::App for PlaytimeApp {
 
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.heading("Star Citizen Playtime");
+            self.render_monitor_failure(ui);
             ui.separator();
             self.render_tab_bar(ui);
             ui.separator();
@@ -2115,14 +4769,24 @@ impl eframe::App for PlaytimeApp {
                     DashboardTab::Insights => self.render_insights_tab(ui, &snapshot),
                 });
 
+            self.render_background_jobs(ui);
             if let Some(message) = &self.status_message {
                 ui.separator();
                 ui.label(message);
             }
         });
 
-        ctx.request_repaint();
-        ctx.request_repaint_after(Duration::from_millis(200));
+        self.render_onboarding(ctx);
+        self.render_confirmation(ctx);
+
+        ctx.request_repaint_after(self.next_repaint_delay(&snapshot));
+        if self
+            .data_watcher
+            .as_ref()
+            .is_some_and(DataWatcher::is_pending)
+        {
+            ctx.request_repaint_after(DEFAULT_DEBOUNCE);
+        }
     }
 }
 
@@ -2132,10 +4796,214 @@ impl Drop for PlaytimeApp {
     }
 }
 
+/// Parses a comma-separated list of hour thresholds, ignoring anything that is not a number.
+fn parse_milestones(input: &str) -> Vec<u64> {
+    input
+        .split(',')
+        .filter_map(|part| part.trim().parse().ok())
+        .collect()
+}
+
+/// `label: +2.3h (+18%)`, green when playtime went up and red when it went down.
+fn render_period_delta(ui: &mut egui::Ui, label: &str, current: f64, previous: f64) {
+    let delta = current - previous;
+    let percent = if previous > 0.0 {
+        format!("{:+.0}%", delta / previous * 100.0)
+    } else if current > 0.0 {
+        "new".to_string()
+    } else {
+        "no change".to_string()
+    };
+    let text = format!("{label}: {:+.1}h ({percent})", delta / 60.0);
+    let color = if delta > 0.0 {
+        Color32::from_rgb(94, 201, 146)
+    } else if delta < 0.0 {
+        Color32::from_rgb(230, 110, 110)
+    } else {
+        ui.visuals().weak_text_color()
+    };
+    ui.colored_label(color, text);
+}
+
+/// `UTC+05:30` style label for an offset in minutes.
+fn format_utc_offset(minutes: i32) -> String {
+    let sign = if minutes < 0 { '-' } else { '+' };
+    let minutes = minutes.unsigned_abs();
+    format!("UTC{sign}{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+fn format_milestones(hours: &[u64]) -> String {
+    hours
+        .iter()
+        .map(u64::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Reads `--data-dir <DIR>` (or `--data-dir=<DIR>`) from the command line.
+fn data_dir_arg() -> Option<PathBuf> {
+    let mut args = std::env::args_os().skip(1);
+    while let Some(arg) = args.next() {
+        let arg = arg.to_string_lossy();
+        if arg == "--data-dir" {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(value) = arg.strip_prefix("--data-dir=") {
+            return Some(PathBuf::from(value));
+        }
+    }
+    None
+}
+
 fn fmt_day(day: NaiveDate) -> String {
     day.format("%Y-%m-%d").to_string()
 }
 
+/// Chart heading with a small button to save the chart as a PNG; returns whether it was clicked.
+fn chart_heading(ui: &mut egui::Ui, heading: &str) -> bool {
+    ui.horizontal(|ui| {
+        ui.heading(heading);
+        ui.small_button("PNG")
+            .on_hover_text("Export chart as PNG")
+            .clicked()
+    })
+    .inner
+}
+
+/// Simple labelled bar chart (values in hours) with fixed bounds, matching the daily and
+/// weekly charts.
+fn render_bar_chart(
+    ui: &mut egui::Ui,
+    id: &str,
+    heading: &str,
+    labels: Vec<String>,
+    hours: Vec<f64>,
+    color: Color32,
+    series_name: &str,
+) -> Option<ChartExport> {
+    render_chart(
+        ui,
+        id,
+        heading,
+        labels,
+        hours,
+        color,
+        series_name,
+        |hours| format!("{hours:.2} h"),
+    );
+}
+
+/// Labelled bar chart with fixed bounds; `format_value` renders a bar's value in tooltips.
+#[allow(clippy::too_many_arguments)]
+fn render_chart(
+    ui: &mut egui::Ui,
+    id: &str,
+    heading: &str,
+    labels: Vec<String>,
+    values: Vec<f64>,
+    color: Color32,
+    series_name: &str,
+    format_value: fn(f64) -> String,
+) -> Option<ChartExport> {
+    let export = chart_heading(ui, heading).then(|| ChartExport {
+        id: id.to_string(),
+        title: heading.to_string(),
+        labels: labels.clone(),
+        values: values.clone(),
+        series_name: series_name.to_string(),
+        color,
+    });
+    let label_arc = Arc::new(labels);
+    let axis_labels = Arc::clone(&label_arc);
+    let tooltip_labels = Arc::clone(&label_arc);
+    let max_value = values.iter().copied().fold(0.0, f64::max);
+    let bars: Vec<Bar> = values
+        .iter()
+        .enumerate()
+        .map(|(idx, value)| Bar::new(idx as f64, *value).width(0.8))
+        .collect();
+    let count = bars.len();
+    let series_name = series_name.to_string();
+
+    Plot::new(id)
+        .height(180.0)
+        .allow_zoom(false)
+        .allow_drag(false)
+        .include_y(0.0)
+        .legend(Legend::default())
+        .x_axis_formatter(move |value, _range, _formatter| {
+            let idx = value.value.round() as usize;
+            axis_labels.get(idx).cloned().unwrap_or_default()
+        })
+        .label_formatter(move |series, value: &PlotPoint| {
+            let idx = value.x.round() as usize;
+            let label = tooltip_labels
+                .get(idx)
+                .cloned()
+                .unwrap_or_else(|| String::from("?"));
+            let value = format_value(value.y);
+            if series.is_empty() {
+                format!("{label}\n{value}")
+            } else {
+                format!("{series}\n{label}\n{value}")
+            }
+        })
+        .show(ui, move |plot_ui| {
+            let upper = if max_value <= 0.0 {
+                1.0
+            } else {
+                (max_value * 1.1).ceil()
+            };
+            let x_min = -0.5;
+            let x_max = ((count as f64) - 0.5).max(x_min + 1.0);
+            plot_ui.set_auto_bounds(Vec2b::new(false, false));
+            plot_ui.set_plot_bounds(PlotBounds::from_min_max([x_min, 0.0], [x_max, upper]));
+            plot_ui.bar_chart(BarChart::new(bars).color(color).name(series_name));
+        });
+    export
+}
+
+/// Label / playtime / share-of-total table used by the Insights breakdowns.
+fn render_share_grid(
+    ui: &mut egui::Ui,
+    id: &str,
+    label_header: &str,
+    totals: &[(String, f64)],
+    total_minutes: f64,
+) {
+    Grid::new(id).striped(true).show(ui, |grid| {
+        grid.label(RichText::new(label_header).strong());
+        grid.label(RichText::new("Playtime").strong());
+        grid.label(RichText::new("Share").strong());
+        grid.end_row();
+        for (label, minutes) in totals {
+            let share = if total_minutes > 0.0 {
+                minutes / total_minutes * 100.0
+            } else {
+                0.0
+            };
+            grid.label(label.as_str());
+            grid.label(format_duration(*minutes));
+            grid.label(format!("{share:.0}%"));
+            grid.end_row();
+        }
+    });
+}
+
+/// Coarse "active 2d ago" label for leaderboard entries.
+fn format_last_active(at: DateTime<Utc>) -> String {
+    let elapsed = (Utc::now() - at).to_std().unwrap_or_default();
+    if elapsed < Duration::from_secs(60) {
+        "active just now".to_string()
+    } else if elapsed < Duration::from_secs(3600) {
+        format!("active {}m ago", elapsed.as_secs() / 60)
+    } else if elapsed < Duration::from_secs(86_400) {
+        format!("active {}h ago", elapsed.as_secs() / 3600)
+    } else {
+        format!("active {}d ago", elapsed.as_secs() / 86_400)
+    }
+}
+
 fn format_elapsed(duration: Duration) -> String {
     let seconds = duration.as_secs();
     if seconds < 60 {
@@ -1,24 +1,62 @@
-use std::{env, net::SocketAddr, path::PathBuf, sync::Arc};
+use std::{
+    collections::HashMap,
+    env,
+    net::{IpAddr, SocketAddr},
+    path::PathBuf,
+    sync::{
+        Arc, Mutex as StdMutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, anyhow};
 use axum::{
     Json, Router,
-    extract::State,
-    http::StatusCode,
-    response::IntoResponse,
-    routing::{get, post},
+    extract::{ConnectInfo, Path, Query, State},
+    http::{
+        HeaderMap, StatusCode,
+        header::{AUTHORIZATION, RETRY_AFTER},
+    },
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+};
+use chrono::{NaiveDate, Utc};
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::{Deserialize, Serialize};
+use star_citizen_playtime::leaderboard::{
+    ClaimResponse, LeaderboardEntry, LeaderboardPeriod, LeaderboardRank, MAX_STORED_ENTRIES,
+    MonthlyTotal, clamp_top_limit, dedupe_entries, month_key, monthly_entries, rank_of,
+    remove_local_entry, update_local_entries, username_key, validate_username,
+};
+use tokio::{
+    fs,
+    net::TcpListener,
+    sync::{Mutex, RwLock},
+    task,
 };
-use serde::Deserialize;
-use star_citizen_playtime::leaderboard::{LeaderboardEntry, update_local_entries};
-use tokio::{fs, net::TcpListener, sync::RwLock};
+use uuid::Uuid;
 
-struct LeaderboardState {
-    entries: RwLock<Vec<LeaderboardEntry>>,
-    path: PathBuf,
+/// Minimum time between accepted submissions for the same username from the same address.
+const SUBMIT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Where leaderboard entries live. JSON keeps everything in memory and rewrites the file on
+/// each change; SQLite (`LEADERBOARD_DB`) updates single rows.
+enum EntryStore {
+    Json {
+        entries: RwLock<Vec<LeaderboardEntry>>,
+        path: PathBuf,
+    },
+    Sqlite {
+        db: Arc<StdMutex<Connection>>,
+        path: PathBuf,
+        /// Row count refreshed on every write so `/health` never queries the database.
+        count: AtomicUsize,
+    },
 }
 
-impl LeaderboardState {
-    async fn load(path: PathBuf) -> Result<Self> {
+impl EntryStore {
+    async fn json(path: PathBuf) -> Result<Self> {
         if let Some(parent) = path.parent() {
             if !parent.as_os_str().is_empty() {
                 fs::create_dir_all(parent)
@@ -31,8 +69,9 @@ impl LeaderboardState {
             match fs::read(&path).await {
                 Ok(bytes) => match serde_json::from_slice::<Vec<LeaderboardEntry>>(&bytes) {
                     Ok(mut list) => {
+                        dedupe_entries(&mut list);
                         list.sort_by(|a, b| b.total_minutes.partial_cmp(&a.total_minutes).unwrap());
-                        list.truncate(25);
+                        list.truncate(MAX_STORED_ENTRIES);
                         list
                     }
                     Err(err) => {
@@ -55,32 +94,451 @@ impl LeaderboardState {
             Vec::new()
         };
 
-        let state = Self {
+        let store = EntryStore::Json {
             entries: RwLock::new(entries),
             path,
         };
-        state.persist().await?;
-        Ok(state)
+        store.persist().await?;
+        Ok(store)
     }
 
-    async fn submit(&self, username: String, total_minutes: f64) -> Result<()> {
-        {
-            let mut guard = self.entries.write().await;
-            update_local_entries(&mut guard, &username, total_minutes);
+    async fn sqlite(path: PathBuf) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent)
+                    .await
+                    .with_context(|| format!("Failed to create {}", parent.display()))?;
+            }
         }
-        self.persist().await
+
+        let conn = Connection::open(&path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS leaderboard (
+                username TEXT PRIMARY KEY COLLATE NOCASE,
+                total_minutes REAL NOT NULL,
+                last_submission TEXT,
+                month TEXT,
+                month_minutes REAL
+            );
+            CREATE INDEX IF NOT EXISTS leaderboard_total ON leaderboard (total_minutes DESC);",
+        )
+        .with_context(|| format!("Failed to initialize {}", path.display()))?;
+        ensure_column(&conn, "last_submission", "TEXT")
+            .and_then(|()| ensure_column(&conn, "month", "TEXT"))
+            .and_then(|()| ensure_column(&conn, "month_minutes", "REAL"))
+            .with_context(|| format!("Failed to migrate {}", path.display()))?;
+        let count =
+            count_rows(&conn).with_context(|| format!("Failed to read {}", path.display()))?;
+
+        Ok(EntryStore::Sqlite {
+            db: Arc::new(StdMutex::new(conn)),
+            path,
+            count: AtomicUsize::new(count),
+        })
     }
 
-    async fn top(&self) -> Vec<LeaderboardEntry> {
-        self.entries.read().await.clone()
+    fn describe(&self) -> String {
+        match self {
+            EntryStore::Json { path, .. } => format!("JSON {}", path.display()),
+            EntryStore::Sqlite { path, .. } => format!("SQLite {}", path.display()),
+        }
+    }
+
+    async fn submit(
+        &self,
+        username: String,
+        total_minutes: f64,
+        monthly: Option<MonthlyTotal>,
+    ) -> Result<()> {
+        match self {
+            EntryStore::Json { entries, .. } => {
+                {
+                    let mut guard = entries.write().await;
+                    update_local_entries(&mut guard, &username, total_minutes, monthly, Utc::now());
+                }
+                self.persist().await
+            }
+            EntryStore::Sqlite { db, count, .. } => {
+                let (month, month_minutes) = match monthly {
+                    Some(monthly) => (Some(monthly.month), Some(monthly.minutes)),
+                    None => (None, None),
+                };
+                let rows = with_db(db, move |conn| {
+                    // Submissions without a monthly figure leave the stored one in place.
+                    conn.execute(
+                        "INSERT INTO leaderboard
+                            (username, total_minutes, last_submission, month, month_minutes)
+                         VALUES (?1, ?2, ?3, ?4, ?5)
+                         ON CONFLICT(username) DO UPDATE SET
+                            total_minutes = excluded.total_minutes,
+                            last_submission = excluded.last_submission,
+                            month = COALESCE(excluded.month, leaderboard.month),
+                            month_minutes = COALESCE(excluded.month_minutes, leaderboard.month_minutes)",
+                        params![username, total_minutes, Utc::now(), month, month_minutes],
+                    )?;
+                    count_rows(conn)
+                })
+                .await?;
+                count.store(rows, Ordering::Relaxed);
+                Ok(())
+            }
+        }
+    }
+
+    async fn remove(&self, username: &str) -> Result<bool> {
+        match self {
+            EntryStore::Json { entries, .. } => {
+                let removed = {
+                    let mut guard = entries.write().await;
+                    remove_local_entry(&mut guard, username)
+                };
+                if removed {
+                    self.persist().await?;
+                }
+                Ok(removed)
+            }
+            EntryStore::Sqlite { db, count, .. } => {
+                let username = username.trim().to_string();
+                let (removed, rows) = with_db(db, move |conn| {
+                    let removed = conn.execute(
+                        "DELETE FROM leaderboard WHERE username = ?1",
+                        params![username],
+                    )?;
+                    Ok((removed > 0, count_rows(conn)?))
+                })
+                .await?;
+                count.store(rows, Ordering::Relaxed);
+                Ok(removed)
+            }
+        }
+    }
+
+    async fn top(
+        &self,
+        limit: usize,
+        offset: usize,
+        period: LeaderboardPeriod,
+    ) -> Result<Vec<LeaderboardEntry>> {
+        let month = month_key(Utc::now().date_naive());
+        match self {
+            EntryStore::Json { entries, .. } => {
+                let guard = entries.read().await;
+                let page = match period {
+                    LeaderboardPeriod::All => {
+                        guard.iter().skip(offset).take(limit).cloned().collect()
+                    }
+                    LeaderboardPeriod::Month => monthly_entries(&guard, &month)
+                        .into_iter()
+                        .skip(offset)
+                        .take(limit)
+                        .collect(),
+                };
+                Ok(page)
+            }
+            EntryStore::Sqlite { db, .. } => {
+                with_db(db, move |conn| {
+                    let entry_from_row = |row: &rusqlite::Row<'_>| {
+                        let month: Option<String> = row.get(3)?;
+                        let minutes: Option<f64> = row.get(4)?;
+                        Ok(LeaderboardEntry {
+                            username: row.get(0)?,
+                            total_minutes: row.get(1)?,
+                            last_submission: row.get(2)?,
+                            monthly: month
+                                .zip(minutes)
+                                .map(|(month, minutes)| MonthlyTotal { month, minutes }),
+                        })
+                    };
+                    match period {
+                    LeaderboardPeriod::All => conn
+                        .prepare_cached(
+                            "SELECT username, total_minutes, last_submission, month, month_minutes
+                                 FROM leaderboard
+                                 ORDER BY total_minutes DESC, username LIMIT ?1 OFFSET ?2",
+                        )?
+                        .query_map(params![limit as i64, offset as i64], entry_from_row)?
+                        .collect(),
+                    LeaderboardPeriod::Month => conn
+                        .prepare_cached(
+                            "SELECT username, month_minutes, last_submission, month, month_minutes
+                                 FROM leaderboard
+                                 WHERE month = ?3 AND month_minutes > 0
+                                 ORDER BY month_minutes DESC, username LIMIT ?1 OFFSET ?2",
+                        )?
+                        .query_map(params![limit as i64, offset as i64, month], entry_from_row)?
+                        .collect(),
+                }
+                })
+                .await
+            }
+        }
+    }
+
+    /// Whether an entry exists for `username`, ignoring ASCII case like `remove`.
+    async fn contains(&self, username: &str) -> Result<bool> {
+        match self {
+            EntryStore::Json { entries, .. } => Ok(entries
+                .read()
+                .await
+                .iter()
+                .any(|entry| entry.username.eq_ignore_ascii_case(username.trim()))),
+            EntryStore::Sqlite { db, .. } => {
+                let username = username.trim().to_string();
+                with_db(db, move |conn| {
+                    Ok(conn
+                        .query_row(
+                            "SELECT 1 FROM leaderboard WHERE username = ?1",
+                            params![username],
+                            |_| Ok(()),
+                        )
+                        .optional()?
+                        .is_some())
+                })
+                .await
+            }
+        }
+    }
+
+    async fn usernames(&self) -> Result<Vec<String>> {
+        match self {
+            EntryStore::Json { entries, .. } => Ok(entries
+                .read()
+                .await
+                .iter()
+                .map(|entry| entry.username.clone())
+                .collect()),
+            EntryStore::Sqlite { db, .. } => {
+                with_db(db, |conn| {
+                    conn.prepare("SELECT username FROM leaderboard")?
+                        .query_map([], |row| row.get(0))?
+                        .collect()
+                })
+                .await
+            }
+        }
+    }
+
+    async fn entry_count(&self) -> usize {
+        match self {
+            EntryStore::Json { entries, .. } => entries.read().await.len(),
+            EntryStore::Sqlite { count, .. } => count.load(Ordering::Relaxed),
+        }
+    }
+
+    async fn rank(&self, username: &str) -> Result<Option<LeaderboardRank>> {
+        match self {
+            EntryStore::Json { entries, .. } => Ok(rank_of(&entries.read().await, username)),
+            EntryStore::Sqlite { db, .. } => {
+                let username = username.trim().to_string();
+                with_db(db, move |conn| {
+                    conn.query_row(
+                        "SELECT
+                            (SELECT COUNT(*) FROM leaderboard AS other
+                             WHERE other.total_minutes > entry.total_minutes) + 1,
+                            entry.total_minutes,
+                            (SELECT COUNT(*) FROM leaderboard)
+                         FROM leaderboard AS entry WHERE entry.username = ?1",
+                        params![username],
+                        |row| {
+                            Ok(LeaderboardRank {
+                                rank: row.get::<_, i64>(0)? as usize,
+                                total_minutes: row.get(1)?,
+                                total_players: row.get::<_, i64>(2)? as usize,
+                            })
+                        },
+                    )
+                    .optional()
+                })
+                .await
+            }
+        }
     }
 
     async fn persist(&self) -> Result<()> {
-        let guard = self.entries.read().await;
+        match self {
+            EntryStore::Json { entries, path } => {
+                let guard = entries.read().await;
+                let payload = serde_json::to_vec_pretty(&*guard)?;
+                fs::write(path, payload)
+                    .await
+                    .with_context(|| format!("Failed to write {}", path.display()))
+            }
+            // Every SQLite write is already durable.
+            EntryStore::Sqlite { .. } => Ok(()),
+        }
+    }
+}
+
+/// Adds a column that databases created by older releases lack.
+fn ensure_column(conn: &Connection, name: &str, declaration: &str) -> rusqlite::Result<()> {
+    let mut statement = conn.prepare("PRAGMA table_info(leaderboard)")?;
+    let has_column = statement
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<rusqlite::Result<Vec<_>>>()?
+        .iter()
+        .any(|column| column == name);
+    if !has_column {
+        conn.execute(
+            &format!("ALTER TABLE leaderboard ADD COLUMN {name} {declaration}"),
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+fn count_rows(conn: &Connection) -> rusqlite::Result<usize> {
+    conn.query_row("SELECT COUNT(*) FROM leaderboard", [], |row| {
+        row.get::<_, i64>(0)
+    })
+    .map(|rows| rows as usize)
+}
+
+/// Runs a query on the blocking pool so SQLite I/O never stalls the async workers.
+async fn with_db<T, F>(db: &Arc<StdMutex<Connection>>, query: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&Connection) -> rusqlite::Result<T> + Send + 'static,
+{
+    let db = Arc::clone(db);
+    task::spawn_blocking(move || {
+        let conn = db
+            .lock()
+            .map_err(|_| anyhow!("Leaderboard database lock poisoned"))?;
+        query(&conn).context("Leaderboard database query failed")
+    })
+    .await
+    .context("Leaderboard database task failed")?
+}
+
+struct LeaderboardState {
+    store: EntryStore,
+    /// Claim tokens keyed by `username_key`.
+    tokens: RwLock<HashMap<String, String>>,
+    tokens_path: PathBuf,
+    /// Last accepted submission per client address and `username_key`.
+    recent_submits: Mutex<HashMap<(IpAddr, String), Instant>>,
+}
+
+impl LeaderboardState {
+    async fn load(store: EntryStore, tokens_path: PathBuf) -> Result<Self> {
+        let tokens = match fs::read(&tokens_path).await {
+            Ok(bytes) => serde_json::from_slice(&bytes).with_context(|| {
+                format!("Failed to parse claim tokens {}", tokens_path.display())
+            })?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(err) => {
+                return Err(err)
+                    .with_context(|| format!("Failed to read {}", tokens_path.display()));
+            }
+        };
+
+        let state = Self {
+            store,
+            tokens: RwLock::new(tokens),
+            tokens_path,
+            recent_submits: Mutex::new(HashMap::new()),
+        };
+        state.migrate_token_keys().await?;
+        Ok(state)
+    }
+
+    /// Older releases keyed tokens by the Unicode lowercase name. For names whose non-ASCII
+    /// letters change case that differs from `username_key`, so such keys are moved using the
+    /// stored entry names.
+    async fn migrate_token_keys(&self) -> Result<()> {
+        let usernames = self.store.usernames().await?;
+        let migrated = {
+            let mut tokens = self.tokens.write().await;
+            let mut migrated = false;
+            for username in usernames {
+                let legacy = username.trim().to_lowercase();
+                let key = username_key(&username);
+                if legacy != key
+                    && !tokens.contains_key(&key)
+                    && let Some(token) = tokens.remove(&legacy)
+                {
+                    tokens.insert(key, token);
+                    migrated = true;
+                }
+            }
+            migrated
+        };
+        if migrated {
+            self.persist_tokens().await?;
+        }
+        Ok(())
+    }
+
+    /// Records a validated submission attempt. Returns how long the caller must wait when the previous
+    /// accepted submission for this address and username is too recent.
+    async fn throttle(&self, ip: IpAddr, username: &str) -> Option<Duration> {
+        let now = Instant::now();
+        let mut recent = self.recent_submits.lock().await;
+        if recent.len() > 10_000 {
+            recent.retain(|_, last| now.duration_since(*last) < SUBMIT_INTERVAL);
+        }
+        let key = (ip, username_key(username));
+        if let Some(last) = recent.get(&key) {
+            let elapsed = now.duration_since(*last);
+            if elapsed < SUBMIT_INTERVAL {
+                return Some(SUBMIT_INTERVAL - elapsed);
+            }
+        }
+        recent.insert(key, now);
+        None
+    }
+
+    async fn is_claimed(&self, username: &str) -> bool {
+        self.tokens
+            .read()
+            .await
+            .contains_key(&username_key(username))
+    }
+
+    /// True when `username` is unclaimed or `presented` matches its claim token.
+    async fn verify(&self, username: &str, presented: Option<&str>) -> bool {
+        match self.tokens.read().await.get(&username_key(username)) {
+            Some(expected) => presented == Some(expected.as_str()),
+            None => true,
+        }
+    }
+
+    /// Issues a token for an unclaimed username. Returns `None` if it is already claimed.
+    async fn claim(&self, username: &str) -> Result<Option<String>> {
+        let token = {
+            let mut tokens = self.tokens.write().await;
+            let key = username_key(username);
+            if tokens.contains_key(&key) {
+                return Ok(None);
+            }
+            let token = Uuid::new_v4().simple().to_string();
+            tokens.insert(key, token.clone());
+            token
+        };
+        self.persist_tokens().await?;
+        Ok(Some(token))
+    }
+
+    async fn release(&self, username: &str) -> Result<()> {
+        let removed = self
+            .tokens
+            .write()
+            .await
+            .remove(&username_key(username))
+            .is_some();
+        if removed {
+            self.persist_tokens().await?;
+        }
+        Ok(())
+    }
+
+    async fn persist_tokens(&self) -> Result<()> {
+        let guard = self.tokens.read().await;
         let payload = serde_json::to_vec_pretty(&*guard)?;
-        fs::write(&self.path, payload)
+        fs::write(&self.tokens_path, payload)
             .await
-            .with_context(|| format!("Failed to write {}", self.path.display()))
+            .with_context(|| format!("Failed to write {}", self.tokens_path.display()))
     }
 }
 
@@ -88,6 +546,22 @@ impl LeaderboardState {
 struct SubmitPayload {
     username: String,
     total_minutes: f64,
+    #[serde(default)]
+    monthly: Option<MonthlyTotal>,
+}
+
+#[derive(Serialize)]
+struct HealthResponse {
+    status: &'static str,
+    entries: usize,
+}
+
+#[derive(Deserialize)]
+struct TopQuery {
+    limit: Option<usize>,
+    offset: Option<usize>,
+    #[serde(default)]
+    period: LeaderboardPeriod,
 }
 
 type SharedState = Arc<LeaderboardState>;
@@ -100,61 +574,209 @@ async fn main() -> Result<()> {
         .unwrap_or_else(|_| "0.0.0.0:8080".to_string())
         .parse()
         .context("Invalid LEADERBOARD_ADDR value")?;
-    let data_path = PathBuf::from(
-        env::var("LEADERBOARD_STORE").unwrap_or_else(|_| "leaderboard-data.json".to_string()),
-    );
+    let (store, data_path) = match env::var("LEADERBOARD_DB") {
+        Ok(db_path) if !db_path.trim().is_empty() => {
+            let path = PathBuf::from(db_path.trim());
+            (EntryStore::sqlite(path.clone()).await?, path)
+        }
+        _ => {
+            let path = PathBuf::from(
+                env::var("LEADERBOARD_STORE")
+                    .unwrap_or_else(|_| "leaderboard-data.json".to_string()),
+            );
+            (EntryStore::json(path.clone()).await?, path)
+        }
+    };
+
+    let tokens_path = env::var("LEADERBOARD_TOKENS_STORE")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| data_path.with_extension("tokens.json"));
 
-    let state = Arc::new(LeaderboardState::load(data_path).await?);
+    let state = Arc::new(LeaderboardState::load(store, tokens_path).await?);
 
     let app = Router::new()
         .route("/submit", post(submit_handler))
+        .route("/health", get(health_handler))
         .route("/top", get(top_handler))
+        .route("/entry/:username", delete(delete_handler))
+        .route("/rank/:username", get(rank_handler))
         .with_state(state.clone());
 
     println!(
         "Leaderboard service listening on http://{} (storage: {})",
         addr,
-        state.path.display()
+        state.store.describe()
     );
 
     let listener = TcpListener::bind(addr)
         .await
         .with_context(|| format!("Failed to bind to {addr}"))?;
-    axum::serve(listener, app)
-        .await
-        .context("Leaderboard server crashed")?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .context("Leaderboard server crashed")?;
 
     Ok(())
 }
 
 async fn submit_handler(
     State(state): State<SharedState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(payload): Json<SubmitPayload>,
-) -> AppResult<impl IntoResponse> {
+) -> AppResult<Response> {
     let username = payload.username.trim();
-    if username.is_empty() {
-        return Err((StatusCode::BAD_REQUEST, "Username is required".into()));
-    }
+    validate_username(username).map_err(|err| (StatusCode::BAD_REQUEST, err.to_string()))?;
     if !payload.total_minutes.is_finite() || payload.total_minutes < 0.0 {
         return Err((
             StatusCode::BAD_REQUEST,
             "total_minutes must be a non-negative number".into(),
         ));
     }
+    if let Some(monthly) = &payload.monthly {
+        if !monthly.minutes.is_finite() || monthly.minutes < 0.0 {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "monthly minutes must be a non-negative number".into(),
+            ));
+        }
+        if NaiveDate::parse_from_str(&format!("{}-01", monthly.month), "%Y-%m-%d").is_err() {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "monthly month must be formatted as YYYY-MM".into(),
+            ));
+        }
+    }
+
+    // Claimed usernames must present the matching token. Unclaimed ones (new players, or
+    // entries from before claiming existed) are claimed by this submission, but only once it
+    // is stored: a failed write must not leave a claim whose token the client never received.
+    let claimed = state.is_claimed(username).await;
+    if claimed && !state.verify(username, bearer_token(&headers)).await {
+        return Err(forbidden(username));
+    }
+
+    // Only requests that passed validation count, so rejected or forged submissions cannot
+    // use up the owner's budget.
+    if let Some(wait) = state.throttle(peer.ip(), username).await {
+        let seconds = wait.as_secs().max(1);
+        return Ok((
+            StatusCode::TOO_MANY_REQUESTS,
+            [(RETRY_AFTER, seconds.to_string())],
+            format!("Too many submissions for {username}; retry in {seconds}s"),
+        )
+            .into_response());
+    }
 
     state
-        .submit(username.to_string(), payload.total_minutes)
+        .store
+        .submit(username.to_string(), payload.total_minutes, payload.monthly)
         .await
         .map_err(internal_error)?;
 
+    // `None` when a concurrent first submission claimed the name in the meantime.
+    let issued = if claimed {
+        None
+    } else {
+        state.claim(username).await.map_err(internal_error)?
+    };
+
+    Ok(match issued {
+        Some(token) => (StatusCode::CREATED, Json(ClaimResponse { token })).into_response(),
+        None => StatusCode::NO_CONTENT.into_response(),
+    })
+}
+
+async fn delete_handler(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Path(username): Path<String>,
+) -> AppResult<impl IntoResponse> {
+    let username = username.trim();
+    if username.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Username is required".into()));
+    }
+
+    let not_found = || {
+        (
+            StatusCode::NOT_FOUND,
+            format!("No leaderboard entry for {username}"),
+        )
+    };
+    if !state
+        .store
+        .contains(username)
+        .await
+        .map_err(internal_error)?
+    {
+        return Err(not_found());
+    }
+    // Entries submitted before claim tokens existed have no owner to check against.
+    if !state.verify(username, bearer_token(&headers)).await {
+        return Err(forbidden(username));
+    }
+
+    let removed = state.store.remove(username).await.map_err(internal_error)?;
+    if !removed {
+        return Err(not_found());
+    }
+    state.release(username).await.map_err(internal_error)?;
+
     Ok(StatusCode::NO_CONTENT)
 }
 
-async fn top_handler(State(state): State<SharedState>) -> AppResult<impl IntoResponse> {
-    let entries = state.top().await;
+/// Liveness/readiness probe; answers from memory without touching the store.
+async fn health_handler(State(state): State<SharedState>) -> Json<HealthResponse> {
+    Json(HealthResponse {
+        status: "ok",
+        entries: state.store.entry_count().await,
+    })
+}
+
+async fn top_handler(
+    State(state): State<SharedState>,
+    Query(query): Query<TopQuery>,
+) -> AppResult<impl IntoResponse> {
+    let limit = clamp_top_limit(query.limit);
+    let entries = state
+        .store
+        .top(limit, query.offset.unwrap_or(0), query.period)
+        .await
+        .map_err(internal_error)?;
     Ok(Json(entries))
 }
 
+async fn rank_handler(
+    State(state): State<SharedState>,
+    Path(username): Path<String>,
+) -> AppResult<impl IntoResponse> {
+    match state.store.rank(&username).await.map_err(internal_error)? {
+        Some(rank) => Ok(Json(rank)),
+        None => Err((
+            StatusCode::NOT_FOUND,
+            format!("No leaderboard entry for {}", username.trim()),
+        )),
+    }
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+}
+
+fn forbidden(username: &str) -> (StatusCode, String) {
+    (
+        StatusCode::FORBIDDEN,
+        format!("{username} is claimed; a matching Authorization token is required"),
+    )
+}
+
 fn internal_error(err: anyhow::Error) -> (StatusCode, String) {
     eprintln!("Leaderboard error: {err:?}");
     (StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
@@ -0,0 +1,221 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, anyhow};
+use plotters::coord::Shift;
+use plotters::prelude::*;
+
+use crate::storage::Analytics;
+
+const SHARE_IMAGE_SIZE: (u32, u32) = (1200, 630);
+const SHARE_HEADER_HEIGHT: u32 = 250;
+const CHART_IMAGE_SIZE: (u32, u32) = (1200, 600);
+
+const BACKGROUND: RGBColor = RGBColor(26, 30, 39);
+const CARD_FILL: RGBColor = RGBColor(36, 41, 52);
+const TEXT: RGBColor = RGBColor(224, 228, 235);
+const MUTED: RGBColor = RGBColor(150, 158, 176);
+const TOTAL_ACCENT: RGBColor = RGBColor(86, 156, 214);
+const AVERAGE_ACCENT: RGBColor = RGBColor(170, 120, 255);
+const RECENT_ACCENT: RGBColor = RGBColor(255, 170, 90);
+const DAILY_BAR: RGBColor = RGBColor(114, 181, 244);
+
+type Canvas<'a> = DrawingArea<BitMapBackend<'a>, Shift>;
+
+/// Renders a summary card plus the last 14 days of playtime into a single PNG sized for
+/// social media previews. Returns the path actually written (with a `.png` extension).
+pub fn render_share_image(
+    path: &Path,
+    analytics: &Analytics,
+    commander: Option<&str>,
+) -> Result<PathBuf> {
+    let out_path = with_png_extension(path);
+    let mut daily = analytics.recent_daily.clone();
+    daily.reverse();
+    let labels = daily
+        .iter()
+        .map(|(day, _)| day.format("%m-%d").to_string())
+        .collect::<Vec<_>>();
+    let hours = daily
+        .iter()
+        .map(|(_, minutes)| minutes / 60.0)
+        .collect::<Vec<_>>();
+
+    {
+        let root = BitMapBackend::new(&out_path, SHARE_IMAGE_SIZE).into_drawing_area();
+        root.fill(&BACKGROUND).map_err(draw_error)?;
+        let (header, chart_area) = root.split_vertically(SHARE_HEADER_HEIGHT);
+        draw_summary_card(&header, analytics, commander)?;
+        if hours.is_empty() {
+            chart_area
+                .draw(&Text::new(
+                    "No daily playtime recorded yet.",
+                    (40, 40),
+                    ("sans-serif", 24).into_font().color(&MUTED),
+                ))
+                .map_err(draw_error)?;
+        } else {
+            draw_bar_chart(
+                &chart_area,
+                "Daily playtime (last 14 days)",
+                &labels,
+                &hours,
+                "Hours",
+                DAILY_BAR,
+            )?;
+        }
+        root.present().map_err(draw_error)?;
+    }
+
+    Ok(out_path)
+}
+
+/// Renders a single bar chart from the dashboard (same labels and values) to a PNG at a fixed
+/// resolution. Returns the path actually written (with a `.png` extension).
+pub fn render_chart_image(
+    path: &Path,
+    title: &str,
+    labels: &[String],
+    values: &[f64],
+    y_desc: &str,
+    color: (u8, u8, u8),
+) -> Result<PathBuf> {
+    let out_path = with_png_extension(path);
+    {
+        let root = BitMapBackend::new(&out_path, CHART_IMAGE_SIZE).into_drawing_area();
+        root.fill(&BACKGROUND).map_err(draw_error)?;
+        draw_bar_chart(
+            &root,
+            title,
+            labels,
+            values,
+            y_desc,
+            RGBColor(color.0, color.1, color.2),
+        )?;
+        root.present().map_err(draw_error)?;
+    }
+    Ok(out_path)
+}
+
+fn draw_summary_card(
+    area: &Canvas<'_>,
+    analytics: &Analytics,
+    commander: Option<&str>,
+) -> Result<()> {
+    area.draw(&Text::new(
+        "Star Citizen Playtime",
+        (40, 28),
+        ("sans-serif", 40).into_font().color(&TEXT),
+    ))
+    .map_err(draw_error)?;
+    if let Some(name) = commander {
+        area.draw(&Text::new(
+            format!("CMDR {name}"),
+            (40, 78),
+            ("sans-serif", 26).into_font().color(&TOTAL_ACCENT),
+        ))
+        .map_err(draw_error)?;
+    }
+
+    let stats = [
+        (
+            "Total hours",
+            format!("{:.1} h", analytics.total_minutes / 60.0),
+            TOTAL_ACCENT,
+        ),
+        (
+            "Sessions",
+            analytics.total_sessions.to_string(),
+            AVERAGE_ACCENT,
+        ),
+        (
+            "Last 30 days",
+            format!("{:.1} h", analytics.minutes_last_30 / 60.0),
+            RECENT_ACCENT,
+        ),
+    ];
+
+    let box_width = 350;
+    let gap = 35;
+    for (idx, (title, value, accent)) in stats.iter().enumerate() {
+        let x0 = 40 + idx as i32 * (box_width + gap);
+        let corners = [(x0, 125), (x0 + box_width, 225)];
+        area.draw(&Rectangle::new(corners, CARD_FILL.filled()))
+            .map_err(draw_error)?;
+        area.draw(&Rectangle::new(corners, accent.stroke_width(2)))
+            .map_err(draw_error)?;
+        area.draw(&Text::new(
+            *title,
+            (x0 + 18, 138),
+            ("sans-serif", 22).into_font().color(accent),
+        ))
+        .map_err(draw_error)?;
+        area.draw(&Text::new(
+            value.clone(),
+            (x0 + 18, 170),
+            ("sans-serif", 38).into_font().color(&TEXT),
+        ))
+        .map_err(draw_error)?;
+    }
+    Ok(())
+}
+
+fn draw_bar_chart(
+    area: &Canvas<'_>,
+    title: &str,
+    labels: &[String],
+    values: &[f64],
+    y_desc: &str,
+    color: RGBColor,
+) -> Result<()> {
+    let max_value = values.iter().copied().fold(0.0, f64::max);
+    let upper = if max_value <= 0.0 {
+        1.0
+    } else {
+        (max_value * 1.1).ceil()
+    };
+
+    let mut chart = ChartBuilder::on(area)
+        .margin(30)
+        .caption(title, ("sans-serif", 26).into_font().color(&TEXT))
+        .x_label_area_size(36)
+        .y_label_area_size(56)
+        .build_cartesian_2d((0..labels.len()).into_segmented(), 0f64..upper)
+        .map_err(draw_error)?;
+
+    chart
+        .configure_mesh()
+        .disable_x_mesh()
+        .axis_style(MUTED.stroke_width(1))
+        .light_line_style(CARD_FILL.stroke_width(1))
+        .label_style(("sans-serif", 16).into_font().color(&MUTED))
+        .x_labels(labels.len())
+        .x_label_formatter(&|value| match value {
+            SegmentValue::CenterOf(idx) => labels.get(*idx).cloned().unwrap_or_default(),
+            _ => String::new(),
+        })
+        .y_desc(y_desc)
+        .draw()
+        .map_err(draw_error)?;
+
+    chart
+        .draw_series(
+            Histogram::vertical(&chart)
+                .style(color.filled())
+                .margin(8)
+                .data(values.iter().enumerate().map(|(idx, value)| (idx, *value))),
+        )
+        .map_err(draw_error)?;
+    Ok(())
+}
+
+fn with_png_extension(path: &Path) -> PathBuf {
+    let mut out_path = path.to_path_buf();
+    if out_path.extension().map(|ext| ext != "png").unwrap_or(true) {
+        out_path.set_extension("png");
+    }
+    out_path
+}
+
+fn draw_error<E: std::fmt::Display>(err: E) -> anyhow::Error {
+    anyhow!("Failed to render image: {err}")
+}
@@ -1,13 +1,19 @@
 use std::{
-    env, fs,
+    collections::HashMap,
+    env, fmt, fs,
     path::{Path, PathBuf},
-    sync::Arc,
-    time::Duration,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
 };
 
 use anyhow::{Context, Result, anyhow};
-use reqwest::{Url, blocking::Client};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use reqwest::{StatusCode, Url, blocking::Client, header::RETRY_AFTER};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::storage::write_atomic;
 
 fn normalize_endpoint(value: &str) -> Option<String> {
     let trimmed = value.trim();
@@ -30,6 +36,30 @@ fn baked_in_default_endpoint() -> Option<String> {
 
 const FALLBACK_GLOBAL_ENDPOINT: &str = "https://playtracker.al1e.dev";
 
+/// Longest username, in characters, accepted by the service and kept by the settings.
+pub const MAX_USERNAME_CHARS: usize = 32;
+
+/// Entries returned by `/top` when the client does not ask for a limit.
+pub const DEFAULT_TOP_LIMIT: usize = 25;
+
+/// Largest page `/top` will return in one response.
+pub const MAX_TOP_LIMIT: usize = 100;
+
+/// Upper bound on stored entries so the board cannot grow without limit.
+pub const MAX_STORED_ENTRIES: usize = 10_000;
+
+/// Extra submission attempts after a connection failure or timeout.
+pub const DEFAULT_SUBMIT_RETRIES: u32 = 3;
+
+/// Wait used when a 429 response carries no usable `Retry-After` header.
+const DEFAULT_RATE_LIMIT_BACKOFF: Duration = Duration::from_secs(60);
+
+/// How long `LeaderboardClient::auto_with_fallback` waits for the endpoint to answer.
+pub const DEFAULT_PING_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Delay before the first retry; doubled for each later attempt.
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
 fn fallback_global_endpoint() -> Option<String> {
     Some(FALLBACK_GLOBAL_ENDPOINT.to_string())
 }
@@ -44,12 +74,89 @@ fn global_remote_endpoint() -> Option<String> {
 pub struct LeaderboardEntry {
     pub username: String,
     pub total_minutes: f64,
+    /// When the service last accepted a submission for this username; absent on older data.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_submission: Option<DateTime<Utc>>,
+    /// Latest monthly total reported for this username.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monthly: Option<MonthlyTotal>,
+}
+
+/// Which board `/top` serves. The all-time board is the default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LeaderboardPeriod {
+    #[default]
+    All,
+    Month,
+}
+
+/// Minutes played in one calendar month, submitted alongside the all-time total. Months are
+/// UTC months, matching how the server picks the current month.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonthlyTotal {
+    /// `YYYY-MM`.
+    pub month: String,
+    pub minutes: f64,
+}
+
+impl MonthlyTotal {
+    pub fn new(date: NaiveDate, minutes: f64) -> Self {
+        Self {
+            month: month_key(date),
+            minutes,
+        }
+    }
 }
 
-#[derive(Debug, Clone, Serialize)]
+/// A player's position on the full leaderboard, not just the returned top page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaderboardRank {
+    pub rank: usize,
+    pub total_minutes: f64,
+    pub total_players: usize,
+}
+
+/// Returned by `/submit` when a username is claimed for the first time. Later submissions
+/// for that username must send the token as `Authorization: Bearer <token>`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaimResponse {
+    pub token: String,
+}
+
+/// The service asked the client to slow down (HTTP 429). The submission is queued and can be
+/// retried after `retry_after`.
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimited {
+    pub retry_after: Duration,
+}
+
+impl fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Leaderboard is rate limiting submissions; retry in {}s",
+            self.retry_after.as_secs()
+        )
+    }
+}
+
+impl std::error::Error for RateLimited {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct SubmitPayload {
     username: String,
     total_minutes: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    monthly: Option<MonthlyTotal>,
+}
+
+/// A board page remembered by `fetch_top_entries_cached`.
+pub struct CachedBoard {
+    period: LeaderboardPeriod,
+    limit: usize,
+    fetched_at: Instant,
+    entries: Vec<LeaderboardEntry>,
 }
 
 #[derive(Clone)]
@@ -58,6 +165,13 @@ pub enum LeaderboardClient {
         client: Client,
         endpoint: Arc<str>,
         secondary: Option<Arc<str>>,
+        retries: u32,
+        /// Latest submission that could not reach the service, flushed once it is reachable.
+        pending: Arc<PathBuf>,
+        /// Claim tokens issued by each endpoint, keyed by endpoint and username.
+        tokens: Arc<PathBuf>,
+        /// Last fetched page per board, shared by clones of this client.
+        cache: Arc<Mutex<Vec<CachedBoard>>>,
     },
     Local {
         path: Arc<PathBuf>,
@@ -70,7 +184,7 @@ impl LeaderboardClient {
             let trimmed = raw_override.trim();
             if trimmed.eq_ignore_ascii_case("default") || trimmed.eq_ignore_ascii_case("builtin") {
                 if let Some(endpoint) = global_remote_endpoint() {
-                    return build_remote_client(endpoint, None);
+                    return build_remote_client(data_dir, endpoint, None);
                 }
                 return build_local_client(data_dir);
             }
@@ -86,84 +200,336 @@ impl LeaderboardClient {
                         secondary = Some(global);
                     }
                 }
-                return build_remote_client(endpoint, secondary);
+                return build_remote_client(data_dir, endpoint, secondary);
             }
         }
 
         if let Some(endpoint) = global_remote_endpoint() {
-            return build_remote_client(endpoint, None);
+            return build_remote_client(data_dir, endpoint, None);
         }
 
         build_local_client(data_dir)
     }
 
-    pub fn submit_total_minutes(&self, username: &str, total_minutes: f64) -> Result<()> {
-        if username.trim().is_empty() {
-            return Err(anyhow!("Username required to sync leaderboard"));
+    /// Like `auto`, but pings a remote endpoint first and falls back to the local leaderboard when
+    /// it cannot be reached within `timeout`. The flag is true when the fallback was taken.
+    pub fn auto_with_fallback(
+        data_dir: &Path,
+        override_endpoint: Option<&str>,
+        timeout: Duration,
+    ) -> Result<(Self, bool)> {
+        let client = Self::auto(data_dir, override_endpoint)?;
+        match client.local_fallback(data_dir, timeout)? {
+            Some(local) => Ok((local, true)),
+            None => Ok((client, false)),
+        }
+    }
+
+    /// Pings the endpoint and returns the local client for `data_dir` when it cannot be reached
+    /// within `timeout`, or `None` to keep using this one. Blocks for up to `timeout`.
+    pub fn local_fallback(&self, data_dir: &Path, timeout: Duration) -> Result<Option<Self>> {
+        match self.ping(timeout) {
+            Err(err) if is_unreachable(&err) => Ok(Some(build_local_client(data_dir)?)),
+            _ => Ok(None),
         }
+    }
 
+    /// Checks that a remote endpoint answers `/health` within `timeout`. Any HTTP response counts
+    /// as reachable; local clients always succeed.
+    pub fn ping(&self, timeout: Duration) -> Result<()> {
         match self {
             LeaderboardClient::Remote {
-                client,
-                endpoint,
-                secondary,
+                client, endpoint, ..
             } => {
+                let url = build_endpoint_url(endpoint, "health")?;
+                client
+                    .get(url)
+                    .timeout(timeout)
+                    .send()
+                    .context("Failed to reach leaderboard service")?;
+                Ok(())
+            }
+            LeaderboardClient::Local { .. } => Ok(()),
+        }
+    }
+
+    /// Sets how many times a remote submission is retried on connection errors or timeouts.
+    /// Has no effect on local clients.
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        if let LeaderboardClient::Remote {
+            retries: current, ..
+        } = &mut self
+        {
+            *current = retries;
+        }
+        self
+    }
+
+    /// Submits the all-time total and, when given, the current month's total for the
+    /// monthly board.
+    pub fn submit_total_minutes(
+        &self,
+        username: &str,
+        total_minutes: f64,
+        monthly: Option<MonthlyTotal>,
+    ) -> Result<()> {
+        if username.trim().is_empty() {
+            return Err(anyhow!("Username required to sync leaderboard"));
+        }
+
+        match self {
+            LeaderboardClient::Remote { pending, .. } => {
                 let payload = SubmitPayload {
                     username: username.trim().to_string(),
                     total_minutes,
+                    monthly,
                 };
 
-                let mut errors = Vec::new();
-                if let Err(err) = submit_payload(client, endpoint, &payload) {
-                    errors.push(err);
+                let errors = self.submit_remote(&payload);
+                if errors.is_empty() {
+                    // A newer total supersedes anything still queued.
+                    clear_pending(pending)?;
+                    self.invalidate_cache();
+                    return Ok(());
                 }
 
-                if let Some(secondary) = secondary {
-                    if let Err(err) = submit_payload(client, secondary, &payload) {
-                        errors.push(err);
-                    }
+                let limits = errors
+                    .iter()
+                    .filter_map(|err| err.downcast_ref::<RateLimited>())
+                    .map(|limit| limit.retry_after)
+                    .collect::<Vec<_>>();
+                if limits.len() == errors.len() {
+                    // Only throttled: keep the total for later instead of reporting a failure.
+                    store_pending(pending, &payload)?;
+                    let retry_after = limits.into_iter().max().unwrap_or_default();
+                    return Err(anyhow::Error::new(RateLimited { retry_after }));
                 }
 
-                if !errors.is_empty() {
-                    let combined = errors
-                        .into_iter()
-                        .map(|err| err.to_string())
-                        .collect::<Vec<_>>()
-                        .join(" | ");
-                    return Err(anyhow!(combined));
+                let deferred = !limits.is_empty() || errors.iter().any(is_unreachable);
+                let mut combined = combine_errors(errors);
+                if deferred {
+                    store_pending(pending, &payload)?;
+                    combined.push_str(" (queued until the leaderboard is reachable)");
                 }
-                Ok(())
+                Err(anyhow!(combined))
             }
             LeaderboardClient::Local { path } => {
                 let mut entries = read_local_entries(path)?;
-                update_local_entries(&mut entries, username, total_minutes);
+                update_local_entries(&mut entries, username, total_minutes, monthly, Utc::now());
                 store_local_entries(path, &entries)?;
                 Ok(())
             }
         }
     }
 
-    pub fn fetch_top_entries(&self) -> Result<Vec<LeaderboardEntry>> {
+    /// Removes `username` (case-insensitive) from the leaderboard. Returns false when no
+    /// matching entry exists.
+    pub fn remove_username(&self, username: &str) -> Result<bool> {
+        let username = username.trim();
+        if username.is_empty() {
+            return Err(anyhow!("Username required to leave the leaderboard"));
+        }
+
+        match self {
+            LeaderboardClient::Remote {
+                client,
+                endpoint,
+                secondary,
+                pending,
+                tokens,
+                ..
+            } => {
+                // Don't let a queued submission put the entry straight back.
+                clear_pending(pending)?;
+                let removed = delete_entry(client, endpoint, username, tokens);
+                // The primary endpoint decides the result; a failing mirror is only reported.
+                let mirrored = secondary
+                    .as_ref()
+                    .map(|secondary| delete_entry(client, secondary, username, tokens));
+                self.invalidate_cache();
+                match mirrored {
+                    Some(Ok(mirror_removed)) => Ok(removed? | mirror_removed),
+                    Some(Err(err)) => {
+                        let removed = removed?;
+                        eprintln!(
+                            "Failed to remove {username} from the mirrored leaderboard: {err:#}"
+                        );
+                        Ok(removed)
+                    }
+                    None => removed,
+                }
+            }
+            LeaderboardClient::Local { path } => {
+                let mut entries = read_local_entries(path)?;
+                if !remove_local_entry(&mut entries, username) {
+                    return Ok(false);
+                }
+                store_local_entries(path, &entries)?;
+                Ok(true)
+            }
+        }
+    }
+
+    /// Looks up where `username` stands. Returns `None` when the username is not on the board.
+    pub fn fetch_rank(&self, username: &str) -> Result<Option<LeaderboardRank>> {
+        let username = username.trim();
+        if username.is_empty() {
+            return Ok(None);
+        }
+
         match self {
             LeaderboardClient::Remote {
                 client, endpoint, ..
             } => {
-                let url = build_endpoint_url(endpoint, "top")?;
+                let mut url = build_endpoint_url(endpoint, "rank")?;
+                url.path_segments_mut()
+                    .map_err(|_| {
+                        anyhow!(
+                            "Leaderboard endpoint '{}' cannot accept path segments",
+                            endpoint
+                        )
+                    })?
+                    .push(username);
                 let response = client
                     .get(url)
                     .send()
-                    .context("Failed to query leaderboard service")?
+                    .context("Failed to query leaderboard rank")?;
+                if response.status() == StatusCode::NOT_FOUND {
+                    return Ok(None);
+                }
+                let rank = response
                     .error_for_status()
-                    .context("Leaderboard service returned an error status")?;
-                let payload: LeaderboardResponse = response
+                    .context("Leaderboard service returned an error status")?
                     .json()
-                    .context("Failed to parse leaderboard response")?;
-                Ok(match payload {
-                    LeaderboardResponse::Entries(entries) => entries,
-                    LeaderboardResponse::Wrapped { entries, .. } => entries,
-                })
+                    .context("Failed to parse leaderboard rank")?;
+                Ok(Some(rank))
+            }
+            LeaderboardClient::Local { path } => {
+                let entries = read_local_entries(path)?;
+                Ok(rank_of(&entries, username))
+            }
+        }
+    }
+
+    /// Resubmits a queued offline submission, if any. Returns true when one was delivered.
+    pub fn flush_pending(&self) -> Result<bool> {
+        let LeaderboardClient::Remote { pending, .. } = self else {
+            return Ok(false);
+        };
+        let Some(payload) = read_pending(pending)? else {
+            return Ok(false);
+        };
+        let errors = self.submit_remote(&payload);
+        if !errors.is_empty() {
+            return Err(anyhow!(combine_errors(errors)));
+        }
+        clear_pending(pending)?;
+        self.invalidate_cache();
+        Ok(true)
+    }
+
+    /// Drops a queued offline submission without sending it.
+    pub fn discard_pending(&self) -> Result<()> {
+        match self {
+            LeaderboardClient::Remote { pending, .. } => clear_pending(pending),
+            LeaderboardClient::Local { .. } => Ok(()),
+        }
+    }
+
+    /// Like `fetch_top_entries`, but answers from the last fetch of the same board when it is
+    /// younger than `ttl`. Submissions and removals through this client clear the cache. Local
+    /// clients always read the file.
+    pub fn fetch_top_entries_cached(
+        &self,
+        limit: Option<usize>,
+        period: LeaderboardPeriod,
+        ttl: Duration,
+    ) -> Result<Vec<LeaderboardEntry>> {
+        let LeaderboardClient::Remote { cache, .. } = self else {
+            return self.fetch_top_entries(limit, period);
+        };
+        let limit = clamp_top_limit(limit);
+        if let Ok(cache) = cache.lock()
+            && let Some(hit) = cache.iter().find(|board| {
+                board.period == period && board.limit == limit && board.fetched_at.elapsed() < ttl
+            })
+        {
+            return Ok(hit.entries.clone());
+        }
+
+        let entries = self.fetch_top_entries(Some(limit), period)?;
+        if let Ok(mut cache) = cache.lock() {
+            cache.retain(|board| board.period != period);
+            cache.push(CachedBoard {
+                period,
+                limit,
+                fetched_at: Instant::now(),
+                entries: entries.clone(),
+            });
+        }
+        Ok(entries)
+    }
+
+    fn invalidate_cache(&self) {
+        if let LeaderboardClient::Remote { cache, .. } = self
+            && let Ok(mut cache) = cache.lock()
+        {
+            cache.clear();
+        }
+    }
+
+    fn submit_remote(&self, payload: &SubmitPayload) -> Vec<anyhow::Error> {
+        let LeaderboardClient::Remote {
+            client,
+            endpoint,
+            secondary,
+            retries,
+            tokens,
+            ..
+        } = self
+        else {
+            return Vec::new();
+        };
+
+        let mut errors = Vec::new();
+        if let Err(err) = submit_payload(client, endpoint, payload, *retries, tokens) {
+            errors.push(err);
+        }
+        if let Some(secondary) = secondary
+            && let Err(err) = submit_payload(client, secondary, payload, *retries, tokens)
+        {
+            errors.push(err);
+        }
+        errors
+    }
+
+    /// Fetches the highest entries of `period`, `limit` defaulting to `DEFAULT_TOP_LIMIT` and
+    /// capped at `MAX_TOP_LIMIT`. Monthly entries carry the month's minutes in `total_minutes`.
+    /// The primary endpoint is always preferred; the secondary one is only queried when the
+    /// primary fails, and its board is returned unmerged.
+    pub fn fetch_top_entries(
+        &self,
+        limit: Option<usize>,
+        period: LeaderboardPeriod,
+    ) -> Result<Vec<LeaderboardEntry>> {
+        let limit = clamp_top_limit(limit);
+        match self {
+            LeaderboardClient::Remote {
+                client,
+                endpoint,
+                secondary,
+                ..
+            } => fetch_with_fallback(endpoint, secondary.as_ref(), |endpoint| {
+                fetch_remote_top(client, endpoint, limit, period)
+            }),
+            LeaderboardClient::Local { path } => {
+                let mut entries = read_local_entries(path)?;
+                if period == LeaderboardPeriod::Month {
+                    entries = monthly_entries(&entries, &month_key(Utc::now().date_naive()));
+                }
+                entries.truncate(limit);
+                Ok(entries)
             }
-            LeaderboardClient::Local { path } => read_local_entries(path),
         }
     }
 }
@@ -183,7 +549,55 @@ enum LeaderboardResponse {
     },
 }
 
-fn build_remote_client(endpoint: String, secondary: Option<String>) -> Result<LeaderboardClient> {
+/// Runs `fetch` against `primary`, and against `secondary` only when that fails. When both
+/// fail the error carries both causes.
+fn fetch_with_fallback<T>(
+    primary: &Arc<str>,
+    secondary: Option<&Arc<str>>,
+    mut fetch: impl FnMut(&Arc<str>) -> Result<T>,
+) -> Result<T> {
+    match fetch(primary) {
+        Ok(value) => Ok(value),
+        Err(primary_err) => match secondary {
+            Some(secondary) => fetch(secondary)
+                .map_err(|secondary_err| anyhow!(combine_errors(vec![primary_err, secondary_err]))),
+            None => Err(primary_err),
+        },
+    }
+}
+
+fn fetch_remote_top(
+    client: &Client,
+    endpoint: &Arc<str>,
+    limit: usize,
+    period: LeaderboardPeriod,
+) -> Result<Vec<LeaderboardEntry>> {
+    let mut url = build_endpoint_url(endpoint, "top")?;
+    url.query_pairs_mut()
+        .append_pair("limit", &limit.to_string());
+    if period == LeaderboardPeriod::Month {
+        url.query_pairs_mut().append_pair("period", "month");
+    }
+    let response = client
+        .get(url)
+        .send()
+        .with_context(|| format!("Failed to query leaderboard service at {endpoint}"))?
+        .error_for_status()
+        .context("Leaderboard service returned an error status")?;
+    let payload: LeaderboardResponse = response
+        .json()
+        .context("Failed to parse leaderboard response")?;
+    Ok(match payload {
+        LeaderboardResponse::Entries(entries) => entries,
+        LeaderboardResponse::Wrapped { entries, .. } => entries,
+    })
+}
+
+fn build_remote_client(
+    data_dir: &Path,
+    endpoint: String,
+    secondary: Option<String>,
+) -> Result<LeaderboardClient> {
     let client = Client::builder()
         .timeout(Duration::from_secs(10))
         .build()
@@ -192,6 +606,10 @@ fn build_remote_client(endpoint: String, secondary: Option<String>) -> Result<Le
         client,
         endpoint: Arc::from(endpoint.into_boxed_str()),
         secondary: secondary.map(|s| Arc::from(s.into_boxed_str())),
+        retries: DEFAULT_SUBMIT_RETRIES,
+        pending: Arc::new(data_dir.join("leaderboard_pending.json")),
+        tokens: Arc::new(data_dir.join("leaderboard_tokens.json")),
+        cache: Arc::new(Mutex::new(Vec::new())),
     })
 }
 
@@ -205,22 +623,214 @@ fn build_local_client(data_dir: &Path) -> Result<LeaderboardClient> {
     })
 }
 
-fn submit_payload(client: &Client, endpoint: &Arc<str>, payload: &SubmitPayload) -> Result<()> {
+fn submit_payload(
+    client: &Client,
+    endpoint: &Arc<str>,
+    payload: &SubmitPayload,
+    retries: u32,
+    tokens: &Path,
+) -> Result<()> {
     let url = build_endpoint_url(endpoint, "submit")?;
-    let response = client
-        .post(url)
-        .json(payload)
+    let token = load_token(tokens, endpoint, &payload.username)?;
+    let mut attempt = 0;
+    let response = loop {
+        let mut request = client.post(url.clone()).json(payload);
+        if let Some(token) = &token {
+            request = request.bearer_auth(token);
+        }
+        match request.send() {
+            Ok(response) => break response,
+            Err(err) if attempt < retries && (err.is_connect() || err.is_timeout()) => {
+                thread::sleep(retry_delay(attempt));
+                attempt += 1;
+            }
+            Err(err) => {
+                return Err(anyhow::Error::new(err).context(format!(
+                    "Failed to reach leaderboard service at {} after {} attempt(s)",
+                    endpoint,
+                    attempt + 1
+                )));
+            }
+        }
+    };
+    if response.status() == StatusCode::TOO_MANY_REQUESTS {
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_RATE_LIMIT_BACKOFF);
+        return Err(anyhow::Error::new(RateLimited { retry_after }));
+    }
+    if response.status() == StatusCode::FORBIDDEN {
+        return Err(anyhow!(
+            "Leaderboard rejected the submission: '{}' is claimed by another install",
+            payload.username
+        ));
+    }
+    if !response.status().is_success() {
+        return Err(anyhow!(
+            "Leaderboard sync failed with status {}",
+            response.status()
+        ));
+    }
+    if response.status() == StatusCode::CREATED {
+        let claim: ClaimResponse = response
+            .json()
+            .context("Failed to parse leaderboard claim token")?;
+        save_token(tokens, endpoint, &payload.username, Some(claim.token))?;
+    }
+    Ok(())
+}
+
+fn delete_entry(
+    client: &Client,
+    endpoint: &Arc<str>,
+    username: &str,
+    tokens: &Path,
+) -> Result<bool> {
+    let mut url = build_endpoint_url(endpoint, "entry")?;
+    url.path_segments_mut()
+        .map_err(|_| {
+            anyhow!(
+                "Leaderboard endpoint '{}' cannot accept path segments",
+                endpoint
+            )
+        })?
+        .push(username);
+    let mut request = client.delete(url);
+    if let Some(token) = load_token(tokens, endpoint, username)? {
+        request = request.bearer_auth(token);
+    }
+    let response = request
         .send()
         .context("Failed to reach leaderboard service")?;
+    if response.status() == StatusCode::NOT_FOUND {
+        return Ok(false);
+    }
+    if response.status() == StatusCode::FORBIDDEN {
+        let reason = response.text().unwrap_or_default();
+        let reason = reason.trim();
+        return Err(if reason.is_empty() {
+            anyhow!("Leaderboard refused to remove '{username}'")
+        } else {
+            anyhow!("Leaderboard refused to remove '{username}': {reason}")
+        });
+    }
     if !response.status().is_success() {
         return Err(anyhow!(
-            "Leaderboard sync failed with status {}",
+            "Leaderboard removal failed with status {}",
             response.status()
         ));
     }
+    save_token(tokens, endpoint, username, None)?;
+    Ok(true)
+}
+
+/// Folds case with `username_key`, like the server's token map.
+fn token_key(endpoint: &str, username: &str) -> String {
+    format!(
+        "{}|{}",
+        endpoint.trim_end_matches('/'),
+        username_key(username)
+    )
+}
+
+/// The key older releases used, folding case with Unicode rules. Still read so tokens saved
+/// for non-ASCII names keep working; they move to `token_key` on the next save.
+fn legacy_token_key(endpoint: &str, username: &str) -> String {
+    format!(
+        "{}|{}",
+        endpoint.trim_end_matches('/'),
+        username.trim().to_lowercase()
+    )
+}
+
+fn read_tokens(path: &Path) -> Result<HashMap<String, String>> {
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    let raw =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&raw).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+fn load_token(path: &Path, endpoint: &str, username: &str) -> Result<Option<String>> {
+    let mut tokens = read_tokens(path)?;
+    Ok(tokens
+        .remove(&token_key(endpoint, username))
+        .or_else(|| tokens.remove(&legacy_token_key(endpoint, username))))
+}
+
+/// Stores the claim token for `username` on `endpoint`, or forgets it when `token` is `None`.
+fn save_token(path: &Path, endpoint: &str, username: &str, token: Option<String>) -> Result<()> {
+    let mut tokens = read_tokens(path)?;
+    let key = token_key(endpoint, username);
+    let legacy_key = legacy_token_key(endpoint, username);
+    let legacy = legacy_key != key && tokens.remove(&legacy_key).is_some();
+    match token {
+        Some(token) => {
+            tokens.insert(key, token);
+        }
+        None => {
+            if tokens.remove(&key).is_none() && !legacy {
+                return Ok(());
+            }
+        }
+    }
+    let raw = serde_json::to_string_pretty(&tokens)?;
+    write_atomic(path, raw)
+}
+
+/// True when the service could not be reached at all, as opposed to rejecting the request.
+fn is_unreachable(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<reqwest::Error>().is_some()
+}
+
+fn combine_errors(errors: Vec<anyhow::Error>) -> String {
+    errors
+        .into_iter()
+        .map(|err| format!("{err:#}"))
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+fn read_pending(path: &Path) -> Result<Option<SubmitPayload>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let raw =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let payload = serde_json::from_str(&raw)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    Ok(Some(payload))
+}
+
+fn store_pending(path: &Path, payload: &SubmitPayload) -> Result<()> {
+    let raw = serde_json::to_string_pretty(payload)?;
+    write_atomic(path, raw)
+}
+
+fn clear_pending(path: &Path) -> Result<()> {
+    if path.exists() {
+        fs::remove_file(path).with_context(|| format!("Failed to remove {}", path.display()))?;
+    }
     Ok(())
 }
 
+/// Exponential backoff: 0.5s, 1s, 2s, ... capped at 30 seconds.
+fn retry_delay(attempt: u32) -> Duration {
+    RETRY_BASE_DELAY
+        .saturating_mul(1u32 << attempt.min(16))
+        .min(Duration::from_secs(30))
+}
+
+/// Appends one path segment to the endpoint, keeping any path the base already has. A single
+/// trailing slash on the base is ignored, so `https://host/api` and `https://host/api/` both give
+/// `https://host/api/submit`, and `https://host` gives `https://host/submit`. Slashes around
+/// `segment` are trimmed; an empty segment returns the base unchanged. The segment is
+/// percent-encoded as a single path component, so it must not contain inner slashes.
 fn build_endpoint_url(base: &Arc<str>, segment: &str) -> Result<Url> {
     let mut url =
         Url::parse(base).with_context(|| format!("Invalid leaderboard endpoint '{}'", base))?;
@@ -244,32 +854,318 @@ fn build_endpoint_url(base: &Arc<str>, segment: &str) -> Result<Url> {
 fn read_local_entries(path: &Path) -> Result<Vec<LeaderboardEntry>> {
     let raw =
         fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
-    let entries: Vec<LeaderboardEntry> = serde_json::from_str(&raw)
+    let mut entries: Vec<LeaderboardEntry> = serde_json::from_str(&raw)
         .with_context(|| format!("Failed to parse {}", path.display()))?;
+    dedupe_entries(&mut entries);
     Ok(entries)
 }
 
 fn store_local_entries(path: &Path, entries: &[LeaderboardEntry]) -> Result<()> {
     let payload = serde_json::to_string_pretty(entries)?;
-    fs::write(path, payload).with_context(|| format!("Failed to write {}", path.display()))
+    write_atomic(path, payload)
 }
 
+/// Records a submission. Usernames match case-insensitively and the casing of the first
+/// submission is kept for display: a later "cmdr_alie" only updates the totals of an existing
+/// "CMDR_Alie".
 pub fn update_local_entries(
     entries: &mut Vec<LeaderboardEntry>,
     username: &str,
     total_minutes: f64,
+    monthly: Option<MonthlyTotal>,
+    submitted_at: DateTime<Utc>,
 ) {
+    let username = username.trim();
     if let Some(existing) = entries
         .iter_mut()
         .find(|entry| entry.username.eq_ignore_ascii_case(username))
     {
         existing.total_minutes = total_minutes;
+        existing.last_submission = Some(submitted_at);
+        // Submissions without a monthly figure leave the last one in place.
+        if monthly.is_some() {
+            existing.monthly = monthly;
+        }
     } else {
         entries.push(LeaderboardEntry {
-            username: username.trim().to_string(),
+            username: username.to_string(),
             total_minutes,
+            last_submission: Some(submitted_at),
+            monthly,
         });
     }
     entries.sort_by(|a, b| b.total_minutes.partial_cmp(&a.total_minutes).unwrap());
-    entries.truncate(25);
+    entries.truncate(MAX_STORED_ENTRIES);
+}
+
+/// Collapses entries whose usernames differ only by case, as written by releases that let the
+/// display casing follow the latest submission. The first entry's casing wins; the merged entry
+/// keeps the highest total and the most recent activity. Returns true if anything was merged.
+pub fn dedupe_entries(entries: &mut Vec<LeaderboardEntry>) -> bool {
+    let before = entries.len();
+    let mut merged: Vec<LeaderboardEntry> = Vec::with_capacity(before);
+    for entry in entries.drain(..) {
+        match merged
+            .iter_mut()
+            .find(|kept| kept.username.eq_ignore_ascii_case(&entry.username))
+        {
+            Some(kept) => {
+                if entry.total_minutes > kept.total_minutes {
+                    kept.total_minutes = entry.total_minutes;
+                }
+                if entry.last_submission > kept.last_submission {
+                    kept.last_submission = entry.last_submission;
+                    if entry.monthly.is_some() {
+                        kept.monthly = entry.monthly;
+                    }
+                }
+            }
+            None => merged.push(entry),
+        }
+    }
+    *entries = merged;
+    entries.len() != before
+}
+
+/// `YYYY-MM` key for the month containing `date`.
+pub fn month_key(date: NaiveDate) -> String {
+    format!("{:04}-{:02}", date.year(), date.month())
+}
+
+/// The monthly board for `month`: entries that reported minutes for it, highest first, with
+/// `total_minutes` replaced by the month's minutes.
+pub fn monthly_entries(entries: &[LeaderboardEntry], month: &str) -> Vec<LeaderboardEntry> {
+    let mut board = entries
+        .iter()
+        .filter_map(|entry| {
+            let monthly = entry.monthly.as_ref()?;
+            (monthly.month == month && monthly.minutes > 0.0).then(|| LeaderboardEntry {
+                total_minutes: monthly.minutes,
+                ..entry.clone()
+            })
+        })
+        .collect::<Vec<_>>();
+    board.sort_by(|a, b| b.total_minutes.partial_cmp(&a.total_minutes).unwrap());
+    board
+}
+
+/// Resolves a requested page size to the range `/top` serves.
+pub fn clamp_top_limit(limit: Option<usize>) -> usize {
+    limit.unwrap_or(DEFAULT_TOP_LIMIT).clamp(1, MAX_TOP_LIMIT)
+}
+
+/// Key under which a username is compared case-insensitively. Only ASCII letters are folded,
+/// the same rule as `eq_ignore_ascii_case` here and `COLLATE NOCASE` in the SQLite store.
+pub fn username_key(username: &str) -> String {
+    username.trim().to_ascii_lowercase()
+}
+
+/// Removes the entry matching `username` (case-insensitive). Returns false if none matched.
+pub fn remove_local_entry(entries: &mut Vec<LeaderboardEntry>, username: &str) -> bool {
+    let before = entries.len();
+    let username = username.trim();
+    entries.retain(|entry| !entry.username.eq_ignore_ascii_case(username));
+    entries.len() != before
+}
+
+/// Finds `username` (case-insensitive) in entries sorted highest first.
+pub fn rank_of(entries: &[LeaderboardEntry], username: &str) -> Option<LeaderboardRank> {
+    let username = username.trim();
+    entries
+        .iter()
+        .position(|entry| entry.username.eq_ignore_ascii_case(username))
+        .map(|index| LeaderboardRank {
+            rank: index + 1,
+            total_minutes: entries[index].total_minutes,
+            total_players: entries.len(),
+        })
+}
+
+/// Characters that never belong in a username: control characters plus invisible formatting
+/// (zero-width and bidirectional overrides) that can spoof names or break table layouts.
+fn is_disallowed_username_char(ch: char) -> bool {
+    ch.is_control()
+        || matches!(
+            ch,
+            '\u{00AD}'
+                | '\u{200B}'..='\u{200F}'
+                | '\u{202A}'..='\u{202E}'
+                | '\u{2060}'..='\u{2069}'
+                | '\u{FEFF}'
+        )
+}
+
+/// Checks a username the way the service does before accepting a submission.
+pub fn validate_username(username: &str) -> Result<()> {
+    let trimmed = username.trim();
+    if trimmed.is_empty() {
+        return Err(anyhow!("Username is required"));
+    }
+    if trimmed.chars().count() > MAX_USERNAME_CHARS {
+        return Err(anyhow!(
+            "Username must be at most {MAX_USERNAME_CHARS} characters"
+        ));
+    }
+    if let Some(ch) = trimmed.chars().find(|ch| is_disallowed_username_char(*ch)) {
+        return Err(anyhow!(
+            "Username contains an unsupported character (U+{:04X})",
+            ch as u32
+        ));
+    }
+    Ok(())
+}
+
+/// Stable stand-in for a username: `anon-` plus the first 8 hex digits of the SHA-256 of the
+/// trimmed, lowercased name. This is pseudonymous, not anonymous: anyone who guesses the name can
+/// compute the same ID.
+pub fn pseudonymize_username(username: &str) -> String {
+    let digest = Sha256::digest(username.trim().to_lowercase().as_bytes());
+    let hex: String = digest[..4]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    format!("anon-{hex}")
+}
+
+/// Client-side counterpart of `validate_username`: drops disallowed characters and trims the
+/// result to the length the service accepts.
+pub fn sanitize_username(username: &str) -> String {
+    username
+        .chars()
+        .filter(|ch| !is_disallowed_username_char(*ch))
+        .collect::<String>()
+        .trim()
+        .chars()
+        .take(MAX_USERNAME_CHARS)
+        .collect::<String>()
+        .trim_end()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint(base: &str, segment: &str) -> String {
+        build_endpoint_url(&Arc::from(base), segment)
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn endpoint_url_without_trailing_slash() {
+        assert_eq!(endpoint("https://host", "submit"), "https://host/submit");
+    }
+
+    #[test]
+    fn endpoint_url_with_trailing_slash() {
+        assert_eq!(endpoint("https://host/", "submit"), "https://host/submit");
+    }
+
+    #[test]
+    fn endpoint_url_keeps_base_path() {
+        assert_eq!(
+            endpoint("https://host/api", "submit"),
+            "https://host/api/submit"
+        );
+    }
+
+    #[test]
+    fn endpoint_url_keeps_base_path_with_trailing_slash() {
+        assert_eq!(
+            endpoint("https://host/api/", "submit"),
+            "https://host/api/submit"
+        );
+    }
+
+    #[test]
+    fn endpoint_url_empty_segment_returns_base() {
+        assert_eq!(endpoint("https://host/api", ""), "https://host/api");
+        assert_eq!(endpoint("https://host/api", "/"), "https://host/api");
+    }
+
+    fn entry(username: &str, total_minutes: f64) -> LeaderboardEntry {
+        LeaderboardEntry {
+            username: username.to_string(),
+            total_minutes,
+            last_submission: None,
+            monthly: None,
+        }
+    }
+
+    #[test]
+    fn update_local_entries_folds_case_variants_into_the_first_name() {
+        let mut entries = Vec::new();
+        update_local_entries(&mut entries, "Foo", 60.0, None, Utc::now());
+        update_local_entries(&mut entries, "foo", 90.0, None, Utc::now());
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].username, "Foo");
+        assert_eq!(entries[0].total_minutes, 90.0);
+    }
+
+    #[test]
+    fn dedupe_entries_keeps_the_first_casing() {
+        let mut entries = vec![entry("Foo", 60.0), entry("foo", 90.0), entry("Bar", 30.0)];
+        assert!(dedupe_entries(&mut entries));
+        let names = entries
+            .iter()
+            .map(|entry| (entry.username.as_str(), entry.total_minutes))
+            .collect::<Vec<_>>();
+        assert_eq!(names, [("Foo", 90.0), ("Bar", 30.0)]);
+        assert!(!dedupe_entries(&mut entries));
+    }
+
+    #[test]
+    fn token_key_folds_ascii_case_only() {
+        assert_eq!(
+            token_key("https://host/", " Foo "),
+            token_key("https://host", "foo")
+        );
+        assert_ne!(
+            token_key("https://host", "Ñame"),
+            token_key("https://host", "ñame")
+        );
+        assert_eq!(
+            token_key("https://host", "Ñame"),
+            format!("https://host|{}", username_key("Ñame"))
+        );
+    }
+
+    #[test]
+    fn fallback_queries_secondary_when_primary_fails() {
+        let (primary, secondary): (Arc<str>, Arc<str>) = ("primary".into(), "secondary".into());
+        let mut queried = Vec::new();
+        let result = fetch_with_fallback(&primary, Some(&secondary), |endpoint| {
+            queried.push(endpoint.to_string());
+            if endpoint.as_ref() == "primary" {
+                Err(anyhow!("primary down"))
+            } else {
+                Ok(endpoint.to_string())
+            }
+        });
+        assert_eq!(result.unwrap(), "secondary");
+        assert_eq!(queried, ["primary", "secondary"]);
+    }
+
+    #[test]
+    fn fallback_skips_secondary_when_primary_answers() {
+        let (primary, secondary): (Arc<str>, Arc<str>) = ("primary".into(), "secondary".into());
+        let mut queried = Vec::new();
+        let result = fetch_with_fallback(&primary, Some(&secondary), |endpoint| {
+            queried.push(endpoint.to_string());
+            Ok(endpoint.to_string())
+        });
+        assert_eq!(result.unwrap(), "primary");
+        assert_eq!(queried, ["primary"]);
+    }
+
+    #[test]
+    fn fallback_reports_both_failures() {
+        let (primary, secondary): (Arc<str>, Arc<str>) = ("primary".into(), "secondary".into());
+        let err = fetch_with_fallback::<()>(&primary, Some(&secondary), |endpoint| {
+            Err(anyhow!("{endpoint} down"))
+        })
+        .unwrap_err();
+        assert_eq!(err.to_string(), "primary down | secondary down");
+    }
 }
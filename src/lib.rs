@@ -1,5 +1,9 @@
+pub mod charts;
 pub mod leaderboard;
+pub mod local_api;
 pub mod monitor;
+pub mod notifications;
 pub mod settings;
 pub mod startup;
 pub mod storage;
+pub mod watch;
@@ -0,0 +1,139 @@
+use std::{
+    net::{Ipv4Addr, SocketAddr, TcpListener as StdTcpListener},
+    sync::{Arc, Mutex},
+    thread::{self, JoinHandle},
+};
+
+use anyhow::{Context, Result};
+use axum::{
+    Json, Router,
+    extract::State,
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use chrono::{FixedOffset, Local};
+use tokio::{net::TcpListener, sync::oneshot, task};
+
+use crate::monitor::{MonitorSnapshot, StatusReport};
+use crate::storage::{SessionStore, compute_analytics_at};
+
+/// Port used when the setting has never been changed.
+pub const DEFAULT_LOCAL_API_PORT: u16 = 4756;
+
+#[derive(Clone)]
+struct ApiState {
+    snapshot: Arc<Mutex<MonitorSnapshot>>,
+    store: Arc<SessionStore>,
+    utc_offset: Option<FixedOffset>,
+}
+
+/// A small JSON server on `127.0.0.1` for browser-based overlays. `GET /status` mirrors the
+/// overlay status file and `GET /analytics` returns the same analytics as `report --json`,
+/// with days and hours taken at `utc_offset` when one is set.
+/// The server runs on its own thread and stops when the handle is dropped.
+pub struct LocalApi {
+    addr: SocketAddr,
+    shutdown: Option<oneshot::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl LocalApi {
+    /// Binds the port up front so a port already in use is reported to the caller.
+    pub fn start(
+        port: u16,
+        snapshot: Arc<Mutex<MonitorSnapshot>>,
+        store: Arc<SessionStore>,
+        utc_offset: Option<FixedOffset>,
+    ) -> Result<Self> {
+        let listener = StdTcpListener::bind((Ipv4Addr::LOCALHOST, port))
+            .with_context(|| format!("Failed to bind local API to 127.0.0.1:{port}"))?;
+        listener
+            .set_nonblocking(true)
+            .context("Failed to configure local API socket")?;
+        let addr = listener
+            .local_addr()
+            .context("Failed to read local API address")?;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .context("Failed to start local API runtime")?;
+
+        let app = Router::new()
+            .route("/status", get(status_handler))
+            .route("/analytics", get(analytics_handler))
+            .with_state(ApiState {
+                snapshot,
+                store,
+                utc_offset,
+            });
+        let (shutdown, stopped) = oneshot::channel::<()>();
+        let thread = thread::spawn(move || {
+            let result = runtime.block_on(async move {
+                let listener = TcpListener::from_std(listener)?;
+                axum::serve(listener, app)
+                    .with_graceful_shutdown(async {
+                        stopped.await.ok();
+                    })
+                    .await
+            });
+            if let Err(err) = result {
+                eprintln!("Local API stopped: {err}");
+            }
+        });
+
+        Ok(Self {
+            addr,
+            shutdown: Some(shutdown),
+            thread: Some(thread),
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for LocalApi {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+async fn status_handler(State(state): State<ApiState>) -> Response {
+    let active = state
+        .snapshot
+        .lock()
+        .ok()
+        .and_then(|snapshot| snapshot.active_session.clone());
+    let status = StatusReport::new(active.as_ref(), Local::now());
+    with_cors(Json(status).into_response())
+}
+
+async fn analytics_handler(State(state): State<ApiState>) -> Response {
+    let store = Arc::clone(&state.store);
+    let response = match task::spawn_blocking(move || store.load_sessions()).await {
+        Ok(Ok(sessions)) => Json(compute_analytics_at(&sessions, state.utc_offset)).into_response(),
+        Ok(Err(err)) => (StatusCode::INTERNAL_SERVER_ERROR, format!("{err:#}")).into_response(),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    };
+    with_cors(response)
+}
+
+/// Overlays are usually served from another origin (or a local file), so allow any.
+fn with_cors(mut response: Response) -> Response {
+    response.headers_mut().insert(
+        header::ACCESS_CONTROL_ALLOW_ORIGIN,
+        header::HeaderValue::from_static("*"),
+    );
+    response
+}
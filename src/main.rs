@@ -1,87 +1,558 @@
-use std::{path::PathBuf, sync::Arc, sync::atomic::AtomicBool, time::Duration};
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
 
-use anyhow::Result;
-use clap::{Parser, Subcommand};
-use star_citizen_playtime::monitor::Monitor;
-use star_citizen_playtime::startup;
-use star_citizen_playtime::storage::{SessionStore, compute_analytics, format_duration};
+use anyhow::{Context, Result, bail};
+use chrono::{FixedOffset, Local, NaiveDate};
+use clap::{Args, Parser, Subcommand};
+use star_citizen_playtime::monitor::{
+    DEFAULT_GRACE_PERIOD, DEFAULT_IDLE_BACKOFF_FACTOR, DEFAULT_MAX_POLL_INTERVAL, MatchMode,
+    Monitor, MonitorSnapshot,
+};
+use star_citizen_playtime::startup::{self, StartupMethod};
+use star_citizen_playtime::storage::{
+    CsvExportOptions, Session, SessionStore, active_minutes_on_day, active_session_is_stale,
+    active_session_minutes, compute_analytics_at, date_at, format_duration,
+    format_duration_precise, minutes_on_day, parse_local_datetime, read_active_status,
+    sessions_in_range,
+};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about = "Star Citizen playtime tracker", long_about = None)]
 struct Cli {
+    /// Store data in DIR instead of the platform data directory (also read from SC_PLAYTIME_DATA_DIR)
+    #[arg(long, global = true, value_name = "DIR")]
+    data_dir: Option<PathBuf>,
     #[command(subcommand)]
     command: Option<Command>,
 }
 
+#[derive(Args, Debug)]
+struct RunArgs {
+    /// Polling interval in seconds
+    #[arg(long, default_value_t = 15)]
+    poll_seconds: u64,
+    /// Poll N times less often while the game is not running (1 disables the backoff)
+    #[arg(long, value_name = "N", default_value_t = DEFAULT_IDLE_BACKOFF_FACTOR)]
+    idle_backoff: u32,
+    /// Longest idle polling interval in seconds
+    #[arg(long, value_name = "SECONDS", default_value_t = DEFAULT_MAX_POLL_INTERVAL.as_secs())]
+    max_poll_seconds: u64,
+    /// Minimum session length in minutes before logging
+    #[arg(long, default_value_t = 3)]
+    min_session_minutes: u64,
+    /// Keep sessions shorter than the minimum (tagged "short") instead of discarding them
+    #[arg(long)]
+    keep_short_sessions: bool,
+    /// Minimum length in minutes for sessions recovered after a crash (0 keeps all of them)
+    #[arg(long, value_name = "MINUTES")]
+    recovered_min_session_minutes: Option<u64>,
+    /// Keep the session open if the game comes back within this many seconds (0 disables)
+    #[arg(long, value_name = "SECONDS", default_value_t = DEFAULT_GRACE_PERIOD.as_secs())]
+    grace_seconds: u64,
+    /// Merge a session into the previous one when it starts within N minutes of its end (0 disables)
+    #[arg(long, value_name = "MINUTES", default_value_t = 0)]
+    merge_gap_minutes: u64,
+    /// End the session after N minutes without keyboard/mouse input (Windows only)
+    #[arg(long, value_name = "MINUTES")]
+    idle_timeout_minutes: Option<u64>,
+    /// Close a restored active session last seen more than N minutes ago instead of resuming it (0 disables)
+    #[arg(long, value_name = "MINUTES", default_value_t = 720)]
+    auto_reset_stale_minutes: u64,
+    /// Suppress console output, including the summary printed on exit
+    #[arg(long)]
+    quiet: bool,
+    /// Process name fragment to track instead of Star Citizen (repeatable)
+    #[arg(long = "process-token", value_name = "NAME")]
+    process_tokens: Vec<String>,
+    /// How process names are compared with the tokens: contains, exact or regex
+    #[arg(long, value_name = "MODE", default_value = "contains")]
+    match_mode: MatchMode,
+    /// Also count a visible window whose title contains TEXT as the game running, for when
+    /// the process name is not recognized (Windows only)
+    #[arg(long, value_name = "TEXT")]
+    window_title: Option<String>,
+    /// Show a desktop notification when a session starts and when it is saved (Windows only)
+    #[arg(long)]
+    notify: bool,
+    /// Keep status.txt and status.json in the data directory updated for stream overlays
+    #[arg(long)]
+    status_file: bool,
+    /// Append session start/continue/recover/finalize events to events.jsonl in the data directory
+    #[arg(long)]
+    event_log: bool,
+    /// Run a single detection pass and exit, leaving the cadence to an external scheduler
+    #[arg(long)]
+    once: bool,
+}
+
+impl Default for RunArgs {
+    fn default() -> Self {
+        Self {
+            poll_seconds: 15,
+            idle_backoff: DEFAULT_IDLE_BACKOFF_FACTOR,
+            max_poll_seconds: DEFAULT_MAX_POLL_INTERVAL.as_secs(),
+            min_session_minutes: 3,
+            keep_short_sessions: false,
+            recovered_min_session_minutes: None,
+            grace_seconds: DEFAULT_GRACE_PERIOD.as_secs(),
+            merge_gap_minutes: 0,
+            idle_timeout_minutes: None,
+            auto_reset_stale_minutes: 720,
+            quiet: false,
+            process_tokens: Vec::new(),
+            match_mode: MatchMode::Contains,
+            window_title: None,
+            notify: false,
+            status_file: false,
+            event_log: false,
+            once: false,
+        }
+    }
+}
+
 #[derive(Subcommand, Debug)]
 enum Command {
     /// Run the background detector loop (default command)
-    Run {
+    Run(RunArgs),
+    /// Run the monitor and keep a one-line live status updated in the terminal
+    Watch {
         /// Polling interval in seconds
         #[arg(long, default_value_t = 15)]
         poll_seconds: u64,
         /// Minimum session length in minutes before logging
         #[arg(long, default_value_t = 3)]
         min_session_minutes: u64,
+        /// Process name fragment to track instead of Star Citizen (repeatable)
+        #[arg(long = "process-token", value_name = "NAME")]
+        process_tokens: Vec<String>,
     },
+    /// Print whether a session is being tracked right now
+    Status,
     /// Print a quick analytics summary to stdout
-    Report,
+    Report {
+        /// Emit the analytics as JSON instead of the human-readable summary
+        #[arg(long)]
+        json: bool,
+        /// Only include sessions started on or after this date (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        since: Option<NaiveDate>,
+        /// Only include sessions started on or before this date (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        until: Option<NaiveDate>,
+        /// Also report the playtime over the last N days
+        #[arg(long, value_name = "DAYS")]
+        window: Option<u32>,
+        /// Fold the session in progress into the total, daily and rolling 7/30-day/--window figures
+        #[arg(long)]
+        include_active: bool,
+        /// Bucket days, weekdays and hours at this UTC offset (e.g. +02:00) instead of local time
+        #[arg(long, value_name = "OFFSET", allow_hyphen_values = true)]
+        utc_offset: Option<FixedOffset>,
+        /// Print a single line with today's and total playtime, for shell prompts and status bars
+        #[arg(
+            long,
+            conflicts_with_all = ["json", "since", "until", "window", "include_active", "utc_offset"]
+        )]
+        oneline: bool,
+    },
     /// Export session history to CSV
     ExportCsv {
         #[arg(value_name = "PATH")]
         path: PathBuf,
+        /// Add a column with the local start date
+        #[arg(long)]
+        date: bool,
+        /// Add a column with the weekday the session started on
+        #[arg(long)]
+        weekday: bool,
+        /// Add a column with the duration in hours
+        #[arg(long)]
+        hours: bool,
+        /// Only export sessions started on or after this date (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        since: Option<NaiveDate>,
+        /// Only export sessions started on or before this date (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        until: Option<NaiveDate>,
     },
-    /// Register this executable to run on Windows login
+    /// Export session history to JSON
+    ExportJson {
+        #[arg(value_name = "PATH")]
+        path: PathBuf,
+    },
+    /// Import sessions from a CSV file produced by export-csv
+    ImportCsv {
+        #[arg(value_name = "PATH")]
+        path: PathBuf,
+    },
+    /// Import sessions from a JSON file produced by export-json
+    ImportJson {
+        #[arg(value_name = "PATH")]
+        path: PathBuf,
+    },
+    /// Backfill a session that was not tracked
+    AddSession {
+        /// Start time (YYYY-MM-DD HH:MM or RFC 3339)
+        #[arg(long)]
+        start: String,
+        /// End time (YYYY-MM-DD HH:MM or RFC 3339)
+        #[arg(long)]
+        end: String,
+        #[arg(long)]
+        note: Option<String>,
+        /// Add the session even if it overlaps one already recorded
+        #[arg(long)]
+        allow_overlap: bool,
+    },
+    /// Copy the data files into a timestamped backup folder
+    Backup {
+        /// Folder to place the backup in (defaults to the data directory's backups folder)
+        #[arg(value_name = "DEST")]
+        dest: Option<PathBuf>,
+    },
+    /// Register this executable to run at login (Run key on Windows, LaunchAgent on macOS,
+    /// XDG autostart entry on Linux)
     InstallStartup {
         #[arg(long, value_name = "EXE", default_value = "")]
         exe: String,
         #[arg(long, value_name = "ARGS", default_value = "")]
         args: String,
+        /// Print the command that would be registered without touching the registry
+        #[arg(long)]
+        dry_run: bool,
+        /// Register a Task Scheduler logon task instead of a Run registry entry
+        #[arg(long)]
+        task_scheduler: bool,
+        /// Seconds to wait after login before starting (Task Scheduler only)
+        #[arg(
+            long,
+            value_name = "SECONDS",
+            default_value_t = 0,
+            requires = "task_scheduler"
+        )]
+        delay: u32,
+    },
+    /// Remove the login startup registration
+    UninstallStartup {
+        /// Print what would be removed without touching the registry
+        #[arg(long)]
+        dry_run: bool,
+        /// Remove the Task Scheduler logon task instead of the Run registry entry
+        #[arg(long)]
+        task_scheduler: bool,
     },
-    /// Remove the Windows startup registration
-    UninstallStartup,
     /// Clear any in-progress session marker
-    ResetActive,
+    ResetActive {
+        /// Only clear the marker if it was last seen more than N minutes ago
+        #[arg(long, value_name = "MINUTES")]
+        if_older_than: Option<u64>,
+    },
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
-    match cli.command.unwrap_or(Command::Run {
-        poll_seconds: 15,
-        min_session_minutes: 3,
-    }) {
-        Command::Run {
+    let data_dir = cli.data_dir.as_deref();
+    match cli
+        .command
+        .unwrap_or_else(|| Command::Run(RunArgs::default()))
+    {
+        Command::Run(args) => run_monitor(data_dir, args),
+        Command::Watch {
             poll_seconds,
             min_session_minutes,
-        } => run_monitor(poll_seconds, min_session_minutes),
-        Command::Report => run_report(),
-        Command::ExportCsv { path } => export_csv(path),
-        Command::InstallStartup { exe, args } => install_startup(exe, args),
-        Command::UninstallStartup => uninstall_startup(),
-        Command::ResetActive => reset_active(),
+            process_tokens,
+        } => run_watch(data_dir, poll_seconds, min_session_minutes, process_tokens),
+        Command::Status => print_status(data_dir),
+        Command::Report { oneline: true, .. } => print_report_oneline(data_dir),
+        Command::Report {
+            json,
+            since,
+            until,
+            window,
+            include_active,
+            utc_offset,
+            oneline: false,
+        } => run_report(
+            data_dir,
+            json,
+            since,
+            until,
+            window,
+            include_active,
+            utc_offset,
+        ),
+        Command::ExportCsv {
+            path,
+            date,
+            weekday,
+            hours,
+            since,
+            until,
+        } => {
+            let options = CsvExportOptions {
+                local_date: date,
+                weekday,
+                hours,
+                since,
+                until,
+            };
+            export_csv(data_dir, path, &options)
+        }
+        Command::ExportJson { path } => export_json(data_dir, path),
+        Command::ImportCsv { path } => import_sessions(data_dir, path, ImportFormat::Csv),
+        Command::ImportJson { path } => import_sessions(data_dir, path, ImportFormat::Json),
+        Command::AddSession {
+            start,
+            end,
+            note,
+            allow_overlap,
+        } => add_session(data_dir, &start, &end, note, allow_overlap),
+        Command::Backup { dest } => backup(data_dir, dest),
+        Command::InstallStartup {
+            exe,
+            args,
+            dry_run,
+            task_scheduler,
+            delay,
+        } => install_startup(exe, args, dry_run, startup_method(task_scheduler), delay),
+        Command::UninstallStartup {
+            dry_run,
+            task_scheduler,
+        } => uninstall_startup(dry_run, startup_method(task_scheduler)),
+        Command::ResetActive { if_older_than } => reset_active(data_dir, if_older_than),
     }
 }
 
-fn run_monitor(poll_seconds: u64, min_session_minutes: u64) -> Result<()> {
-    let stop_flag = Arc::new(AtomicBool::new(false));
+/// Opens the session store in `--data-dir` when given, otherwise the default location.
+fn open_store(data_dir: Option<&Path>) -> Result<SessionStore> {
+    match data_dir {
+        Some(dir) => SessionStore::with_dir(dir),
+        None => SessionStore::new(),
+    }
+}
+
+fn run_monitor(data_dir: Option<&Path>, args: RunArgs) -> Result<()> {
+    let mut monitor = Monitor::new(
+        Duration::from_secs(args.poll_seconds),
+        args.min_session_minutes,
+    )
+    .with_data_dir(data_dir.map(Path::to_path_buf))
+    .with_idle_backoff(
+        args.idle_backoff,
+        Duration::from_secs(args.max_poll_seconds),
+    )
+    .with_discard_short_sessions(!args.keep_short_sessions)
+    .with_recovered_min_session(args.recovered_min_session_minutes)
+    .with_grace_period(Duration::from_secs(args.grace_seconds))
+    .with_merge_gap(args.merge_gap_minutes)
+    .with_idle_timeout(args.idle_timeout_minutes)
+    .with_stale_active_reset(Some(args.auto_reset_stale_minutes))
+    .with_process_tokens(args.process_tokens)
+    .with_match_mode(args.match_mode)
+    .with_window_title(args.window_title)
+    .with_notifications(args.notify, args.notify)
+    .with_status_file(args.status_file)
+    .with_event_log(args.event_log)
+    .with_quiet(args.quiet);
+
+    if args.once {
+        if let Some(active) = monitor.run_once()?
+            && !args.quiet
+        {
+            println!(
+                "Session in progress since {} ({})",
+                active.start.format("%Y-%m-%d %H:%M:%S"),
+                format_duration(active_session_minutes(&active))
+            );
+        }
+        return Ok(());
+    }
 
-    let mut monitor = Monitor::new(Duration::from_secs(poll_seconds), min_session_minutes);
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let handler_flag = Arc::clone(&stop_flag);
+    ctrlc::set_handler(move || handler_flag.store(true, Ordering::SeqCst))
+        .context("Failed to install Ctrl+C handler")?;
     monitor.run(stop_flag)
 }
 
-fn run_report() -> Result<()> {
-    let store = SessionStore::new()?;
+fn run_watch(
+    data_dir: Option<&Path>,
+    poll_seconds: u64,
+    min_session_minutes: u64,
+    process_tokens: Vec<String>,
+) -> Result<()> {
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let handler_flag = Arc::clone(&stop_flag);
+    ctrlc::set_handler(move || handler_flag.store(true, Ordering::SeqCst))
+        .context("Failed to install Ctrl+C handler")?;
+
+    let poll_interval = Duration::from_secs(poll_seconds.max(1));
+    let snapshot = Arc::new(Mutex::new(MonitorSnapshot::default()));
+    let mut monitor = Monitor::new(poll_interval, min_session_minutes)
+        .with_data_dir(data_dir.map(Path::to_path_buf))
+        .with_process_tokens(process_tokens)
+        .with_quiet(true)
+        .with_status_sink(Arc::clone(&snapshot));
+    let monitor_stop = Arc::clone(&stop_flag);
+    let handle = thread::spawn(move || monitor.run(monitor_stop));
+
+    println!("Watching for game sessions (Ctrl+C to stop)...");
+    let mut stdout = std::io::stdout();
+    let mut last_width = 0;
+    let mut next_print = Instant::now();
+    while !stop_flag.load(Ordering::SeqCst) && !handle.is_finished() {
+        if Instant::now() >= next_print {
+            let line = snapshot
+                .lock()
+                .map(|snapshot| describe_watch_status(&snapshot))
+                .unwrap_or_else(|_| "Status unavailable".to_string());
+            let width = line.chars().count();
+            print!("\r{line:<last_width$}");
+            stdout.flush().ok();
+            last_width = width;
+            next_print = Instant::now() + poll_interval;
+        }
+        thread::sleep(Duration::from_millis(250));
+    }
+    println!();
+
+    stop_flag.store(true, Ordering::SeqCst);
+    match handle.join() {
+        Ok(result) => result,
+        Err(_) => bail!("Monitor thread panicked"),
+    }
+}
+
+fn describe_watch_status(snapshot: &MonitorSnapshot) -> String {
+    let now = Local::now().format("%H:%M:%S");
+    if let Some(active) = &snapshot.active_session {
+        format!(
+            "[{now}] {} {} — {} elapsed (since {})",
+            snapshot.status_text,
+            active.game.as_deref().unwrap_or("game"),
+            format_duration(active_session_minutes(active)),
+            active.start.format("%H:%M")
+        )
+    } else if let Some(last) = &snapshot.last_session {
+        format!(
+            "[{now}] Idle — last session {} for {}",
+            last.start.format("%Y-%m-%d %H:%M"),
+            format_duration(last.duration_minutes)
+        )
+    } else {
+        format!("[{now}] Idle — waiting for the game to start")
+    }
+}
+
+fn print_status(data_dir: Option<&Path>) -> Result<()> {
+    let store = open_store(data_dir)?;
+    match read_active_status(&store)? {
+        Some(active) => println!(
+            "Tracking {} for {} (since {}, last seen {})",
+            active.game.as_deref().unwrap_or("game"),
+            format_duration(active_session_minutes(&active)),
+            active.start.format("%Y-%m-%d %H:%M"),
+            active.last_seen.format("%H:%M:%S")
+        ),
+        None => println!("Not tracking."),
+    }
+    Ok(())
+}
+
+/// `SC: 1h 42m today | 312h total`. Skips the analytics pass since this runs on every prompt;
+/// a session in progress counts towards both figures, towards today's only from midnight.
+fn print_report_oneline(data_dir: Option<&Path>) -> Result<()> {
+    let store = open_store(data_dir)?;
     let sessions = store.load_sessions()?;
-    if sessions.is_empty() {
-        println!("No Sessions recorded yet.");
+    let today = Local::now().date_naive();
+    let mut today_minutes = minutes_on_day(&sessions, today, None);
+    let mut total_minutes: f64 = sessions
+        .iter()
+        .map(|session| session.duration_minutes)
+        .sum();
+    if let Some(active) = read_active_status(&store)? {
+        total_minutes += active_session_minutes(&active);
+        today_minutes += active_minutes_on_day(&active, today, None);
+    }
+    println!(
+        "SC: {} today | {:.0}h total",
+        format_duration(today_minutes),
+        total_minutes / 60.0
+    );
+    Ok(())
+}
+
+fn run_report(
+    data_dir: Option<&Path>,
+    json: bool,
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+    window: Option<u32>,
+    include_active: bool,
+    utc_offset: Option<FixedOffset>,
+) -> Result<()> {
+    if let (Some(since), Some(until)) = (since, until)
+        && since > until
+    {
+        bail!("--since ({since}) must not be after --until ({until})");
+    }
+    let store = open_store(data_dir)?;
+    let sessions = sessions_in_range(&store.load_sessions()?, since, until, utc_offset);
+    // Like saved sessions, the session in progress only counts when it started in the range.
+    let active = if include_active {
+        read_active_status(&store)?.filter(|active| {
+            let day = date_at(active.start, utc_offset);
+            since.is_none_or(|since| day >= since) && until.is_none_or(|until| day <= until)
+        })
+    } else {
+        None
+    };
+    let analyze = |sessions: &[Session]| {
+        let mut analytics = compute_analytics_at(sessions, utc_offset);
+        if let Some(active) = &active {
+            analytics.add_active_session(active);
+        }
+        analytics
+    };
+    if json {
+        let analytics = analyze(&sessions);
+        let mut value =
+            serde_json::to_value(&analytics).context("Failed to serialize analytics")?;
+        if let Some(days) = window {
+            value["window_days"] = days.into();
+            value["minutes_in_window"] = analytics.minutes_in_last(days).into();
+        }
+        let output =
+            serde_json::to_string_pretty(&value).context("Failed to serialize analytics")?;
+        println!("{output}");
+        return Ok(());
+    }
+    if sessions.is_empty() && active.is_none() {
+        if since.is_some() || until.is_some() {
+            println!("No sessions recorded in the selected date range.");
+        } else {
+            println!("No Sessions recorded yet.");
+        }
         return Ok(());
     }
-    let analytics = compute_analytics(&sessions);
+    let analytics = analyze(&sessions);
     println!(
         "Playtime Summary\n\nTotal playtime: {:.2} hours across {} sessions",
         analytics.total_minutes / 60.0,
         analytics.total_sessions
     );
+    if let Some(minutes) = analytics.active_minutes {
+        println!(
+            "Including the session in progress: {}",
+            format_duration(minutes)
+        );
+    }
     println!(
         "Average session: {} | Median session: {}",
         format_duration(analytics.average_session_minutes),
@@ -95,6 +566,18 @@ fn run_report() -> Result<()> {
         analytics.minutes_last_7 / 60.0,
         analytics.minutes_last_30 / 60.0
     );
+    if let Some(days) = window {
+        println!(
+            "Last {days} days: {:.2} h",
+            analytics.minutes_in_last(days) / 60.0
+        );
+    }
+    if analytics.game_totals.len() > 1 {
+        println!("\nPlaytime by game:");
+        for (game, minutes) in &analytics.game_totals {
+            println!(" - {game} — {}", format_duration(*minutes));
+        }
+    }
     println!("\nTop play days:");
     for (idx, entry) in analytics.top_days.iter().enumerate() {
         println!(" {}. {} — {}", idx + 1, entry.0, format_duration(entry.1));
@@ -104,54 +587,193 @@ fn run_report() -> Result<()> {
         println!(
             " - {} | {}",
             session.start.format("%Y-%m-%d %H:%M"),
-            format_duration(session.duration_minutes)
+            format_duration_precise(session.duration_minutes)
         );
     }
     Ok(())
 }
 
-fn export_csv(path: PathBuf) -> Result<()> {
-    let store = SessionStore::new()?;
+fn export_csv(data_dir: Option<&Path>, path: PathBuf, options: &CsvExportOptions) -> Result<()> {
+    if let (Some(since), Some(until)) = (options.since, options.until)
+        && since > until
+    {
+        bail!("--since ({since}) must not be after --until ({until})");
+    }
+    let store = open_store(data_dir)?;
     let sessions = store.load_sessions()?;
-    let (written, actual_path) = store.export_csv(&path, &sessions)?;
+    let (written, actual_path) = store.export_csv_with(&path, &sessions, options)?;
     println!("Exported {written} sessions to {}", actual_path.display());
     Ok(())
 }
 
-fn install_startup(exe: String, args: String) -> Result<()> {
-    #[cfg(windows)]
-    {
-        use startup::install;
-        use std::env;
+fn export_json(data_dir: Option<&Path>, path: PathBuf) -> Result<()> {
+    let store = open_store(data_dir)?;
+    let sessions = store.load_sessions()?;
+    let (written, actual_path) = store.export_json(&path, &sessions)?;
+    println!("Exported {written} sessions to {}", actual_path.display());
+    Ok(())
+}
 
-        let exe_path = if exe.trim().is_empty() {
-            env::current_exe()?
-        } else {
-            PathBuf::from(exe)
-        };
-        install(&exe_path, args.trim())?
+enum ImportFormat {
+    Csv,
+    Json,
+}
+
+fn import_sessions(data_dir: Option<&Path>, path: PathBuf, format: ImportFormat) -> Result<()> {
+    let store = open_store(data_dir)?;
+    let summary = match format {
+        ImportFormat::Csv => store.import_csv(&path)?,
+        ImportFormat::Json => store.import_json(&path)?,
+    };
+    println!(
+        "Imported {} sessions from {} ({} already present, {} malformed)",
+        summary.added,
+        path.display(),
+        summary.skipped,
+        summary.errors.len()
+    );
+    for error in &summary.errors {
+        println!(" - {error}");
+    }
+    Ok(())
+}
+
+fn add_session(
+    data_dir: Option<&Path>,
+    start: &str,
+    end: &str,
+    note: Option<String>,
+    allow_overlap: bool,
+) -> Result<()> {
+    let start = parse_local_datetime(start)?;
+    let end = parse_local_datetime(end)?;
+    let store = open_store(data_dir)?;
+    let session = store.add_manual_session(start, end, note.unwrap_or_default(), allow_overlap)?;
+    println!(
+        "Added session {} for {}",
+        session.start.format("%Y-%m-%d %H:%M"),
+        format_duration(session.duration_minutes)
+    );
+    Ok(())
+}
+
+fn backup(data_dir: Option<&Path>, dest: Option<PathBuf>) -> Result<()> {
+    let store = open_store(data_dir)?;
+    let (folder, copied) = store.backup_to(dest.as_deref())?;
+    println!("Backed up {copied} files to {}", folder.display());
+    Ok(())
+}
+
+fn startup_method(task_scheduler: bool) -> StartupMethod {
+    if task_scheduler {
+        StartupMethod::TaskScheduler
+    } else {
+        StartupMethod::RunKey
     }
-    #[cfg(not(windows))]
+}
+
+fn install_startup(
+    exe: String,
+    args: String,
+    dry_run: bool,
+    method: StartupMethod,
+    delay: u32,
+) -> Result<()> {
+    let exe_path = if exe.trim().is_empty() {
+        std::env::current_exe()?
+    } else {
+        PathBuf::from(exe)
+    };
+    if dry_run {
+        let command = startup::startup_command(&exe_path, args.trim());
+        match method {
+            StartupMethod::RunKey => {
+                println!("Would write to {}:", startup::entry_location());
+                println!("{command}");
+            }
+            StartupMethod::TaskScheduler => {
+                println!("Would run:");
+                println!(
+                    "schtasks {}",
+                    startup::task_create_args(&command, delay)
+                        .iter()
+                        .map(|arg| if arg.contains(' ') {
+                            format!("\"{}\"", arg.replace('"', "\\\""))
+                        } else {
+                            arg.clone()
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                );
+            }
+        }
+        if !exe_path.exists() {
+            println!("Warning: {} does not exist.", exe_path.display());
+        }
+        return Ok(());
+    }
+
+    #[cfg(any(windows, target_os = "macos", target_os = "linux"))]
+    {
+        startup::install_with(method, &exe_path, args.trim(), delay)?
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
     {
-        println!("Startup registration is only available on Windows.");
+        println!("Startup registration is not available on this platform.");
     }
     Ok(())
 }
 
-fn uninstall_startup() -> Result<()> {
-    #[cfg(windows)]
+fn uninstall_startup(dry_run: bool, method: StartupMethod) -> Result<()> {
+    if dry_run && method == StartupMethod::TaskScheduler {
+        if startup::is_installed_with(method)? {
+            println!("Would delete scheduled task {}.", startup::TASK_NAME);
+        } else {
+            println!("Scheduled task is not present; nothing would be removed.");
+        }
+        return Ok(());
+    }
+    if dry_run {
+        if let Some(command) = startup::installed_command()? {
+            println!("Would remove {}:", startup::entry_location());
+            println!("{command}");
+        } else if startup::is_installed()? {
+            println!("Would remove {}.", startup::entry_location());
+        } else {
+            println!("Auto-start entry is not present; nothing would be removed.");
+        }
+        return Ok(());
+    }
+
+    #[cfg(any(windows, target_os = "macos", target_os = "linux"))]
     {
-        startup::uninstall()?;
+        startup::uninstall_with(method)?;
     }
-    #[cfg(not(windows))]
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
     {
-        println!("Startup registration is only available on Windows.");
+        println!("Startup registration is not available on this platform.");
     }
     Ok(())
 }
 
-fn reset_active() -> Result<()> {
-    let store = SessionStore::new()?;
+fn reset_active(data_dir: Option<&Path>, if_older_than: Option<u64>) -> Result<()> {
+    let store = open_store(data_dir)?;
+    if let Some(minutes) = if_older_than {
+        match store.load_active()? {
+            None => {
+                println!("No active session marker present.");
+                return Ok(());
+            }
+            Some(active) if !active_session_is_stale(&active, Local::now(), minutes) => {
+                println!(
+                    "Active session marker was last seen {} (within {minutes}m); leaving it in place.",
+                    active.last_seen.format("%Y-%m-%d %H:%M:%S")
+                );
+                return Ok(());
+            }
+            Some(_) => {}
+        }
+    }
     store.clear_active()?;
     println!("Cleared active session marker.");
     Ok(())
@@ -1,5 +1,9 @@
 use std::{
     env,
+    fmt::Display,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
     sync::{
         Arc, Mutex,
         atomic::{AtomicBool, Ordering},
@@ -8,75 +12,454 @@ use std::{
     time::Duration,
 };
 
-use anyhow::{Context, Result};
-use chrono::Local;
-use sysinfo::{System, get_current_pid};
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Local};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use sysinfo::{ProcessRefreshKind, RefreshKind, System, UpdateKind, get_current_pid};
 
+use crate::notifications;
 use crate::storage::{
-    ActiveSession, Session, SessionStore, active_session_minutes, format_duration,
+    ActiveSession, DEFAULT_GAME_LABEL, LOG_ROTATE_BYTES, LOG_ROTATE_KEEP, Session, SessionStore,
+    active_session_is_stale, active_session_minutes, format_duration, rotate_log, write_atomic,
 };
 
-const PROCESS_TOKENS: [&str; 4] = [
-    "starcitizen",
-    "star citizen",
-    "starcitizen64",
-    "robertsspaceindustries",
+/// Process name fragments tracked when no custom list is configured (the Star Citizen client).
+pub const DEFAULT_PROCESS_TOKENS: [&str; 3] = ["starcitizen", "star citizen", "starcitizen64"];
+const LAUNCHER_TOKENS: [&str; 2] = ["rsi launcher", "robertsspaceindustries"];
+/// Processes that come and go around the client during launch (anti-cheat bootstrapper, crash
+/// reporter). They never start a session, even when they match a tracked token.
+const HELPER_TOKENS: [&str; 5] = [
+    "easyanticheat",
+    "start_protected_game",
+    "crashhandler",
+    "crashreport",
+    "bugsplat",
 ];
 
+/// Window title searched for by the optional title fallback (Windows only).
+pub const DEFAULT_WINDOW_TITLE: &str = "Star Citizen";
+
+/// How process names are compared with the tracked tokens. Matching is case-insensitive in
+/// every mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchMode {
+    /// The process name contains a token anywhere.
+    #[default]
+    Contains,
+    /// The process name is a token, with or without a trailing `.exe`.
+    Exact,
+    /// A token is a regular expression found somewhere in the process name.
+    Regex,
+}
+
+impl MatchMode {
+    pub fn label(self) -> &'static str {
+        match self {
+            MatchMode::Contains => "contains",
+            MatchMode::Exact => "exact",
+            MatchMode::Regex => "regex",
+        }
+    }
+}
+
+impl std::str::FromStr for MatchMode {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "contains" => Ok(MatchMode::Contains),
+            "exact" => Ok(MatchMode::Exact),
+            "regex" => Ok(MatchMode::Regex),
+            other => Err(anyhow!(
+                "Unknown match mode \"{other}\" (expected contains, exact or regex)"
+            )),
+        }
+    }
+}
+
+/// Tracked process tokens prepared for a `MatchMode`.
+#[derive(Debug, Clone)]
+pub struct ProcessMatcher {
+    mode: MatchMode,
+    tokens: Vec<String>,
+    patterns: Vec<Regex>,
+}
+
+impl ProcessMatcher {
+    /// Fails when a token is not a valid regular expression in `MatchMode::Regex`.
+    pub fn new(mode: MatchMode, tokens: &[String]) -> Result<Self> {
+        let patterns = match mode {
+            MatchMode::Regex => tokens
+                .iter()
+                .map(|token| {
+                    RegexBuilder::new(token)
+                        .case_insensitive(true)
+                        .build()
+                        .with_context(|| format!("Invalid process pattern \"{token}\""))
+                })
+                .collect::<Result<Vec<_>>>()?,
+            MatchMode::Contains | MatchMode::Exact => Vec::new(),
+        };
+        Ok(Self {
+            mode,
+            tokens: tokens.iter().map(|token| token.to_lowercase()).collect(),
+            patterns,
+        })
+    }
+
+    /// `name` is expected in lowercase, as `detect_game_process` passes it.
+    pub fn matches(&self, name: &str) -> bool {
+        match self.mode {
+            MatchMode::Contains => self
+                .tokens
+                .iter()
+                .any(|token| name.contains(token.as_str())),
+            MatchMode::Exact => {
+                let stem = name.strip_suffix(".exe").unwrap_or(name);
+                self.tokens
+                    .iter()
+                    .any(|token| name == token || stem == token)
+            }
+            MatchMode::Regex => self.patterns.iter().any(|pattern| pattern.is_match(name)),
+        }
+    }
+}
+
+/// Which kind of game process was found. Only the game client counts as playtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameProcessKind {
+    Client,
+    Launcher,
+    /// Anti-cheat bootstrapper or crash reporter matching a token.
+    Helper,
+}
+
+/// A matching process found by `detect_game_process`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetectedProcess {
+    pub kind: GameProcessKind,
+    /// Process name without its extension, e.g. `StarCitizen`.
+    pub name: String,
+    /// `None` when the game was found by its window title rather than a process.
+    pub pid: Option<u32>,
+    /// Number of matching processes of this kind; the lowest pid is reported.
+    pub instances: usize,
+}
+
+impl DetectedProcess {
+    /// What triggered detection, for the log.
+    pub fn describe(&self) -> String {
+        match (self.pid, self.instances) {
+            (None, _) => format!("window title \"{}\"", self.name),
+            (Some(pid), 0 | 1) => format!("{} (pid {pid})", self.name),
+            (Some(pid), instances) => {
+                format!("{} (pid {pid}, {instances} instances)", self.name)
+            }
+        }
+    }
+}
+
+/// Note attached to sessions kept despite being shorter than the minimum.
+pub const SHORT_SESSION_NOTE: &str = "short";
+
+/// Note attached to recovered sessions whose marker had not been refreshed for several polls,
+/// so they were closed at `last_seen` rather than when the tracker came back.
+pub const RECOVERED_CAPPED_NOTE: &str = "recovered (capped)";
+
+/// Plain-text status written to the data directory for OBS text sources.
+pub const STATUS_TEXT_FILE: &str = "status.txt";
+/// Machine-readable twin of `STATUS_TEXT_FILE`.
+pub const STATUS_JSON_FILE: &str = "status.json";
+
+/// Newline-delimited JSON log of session lifecycle events, kept in the data directory.
+pub const EVENT_LOG_FILE: &str = "events.jsonl";
+
+const STOP_CHECK_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How much the poll interval is stretched while no game is running.
+pub const DEFAULT_IDLE_BACKOFF_FACTOR: u32 = 4;
+/// Upper bound for the stretched idle interval.
+pub const DEFAULT_MAX_POLL_INTERVAL: Duration = Duration::from_secs(120);
+
+/// How long the game may be missing before its session ends, so a quick restart continues it.
+pub const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(30);
+
 pub struct Monitor {
     poll_interval: Duration,
+    max_poll_interval: Duration,
+    idle_backoff_factor: u32,
     min_session_minutes: u64,
+    recovered_min_session_minutes: Option<u64>,
+    grace_period: Duration,
+    merge_gap_minutes: u64,
     snapshot: Option<Arc<Mutex<MonitorSnapshot>>>,
+    quiet: bool,
+    stale_active_minutes: Option<u64>,
+    discard_short_sessions: bool,
+    idle_timeout_minutes: Option<u64>,
+    process_tokens: Vec<String>,
+    match_mode: MatchMode,
+    /// `None` while the tokens are not valid for `match_mode`; `run` reports why.
+    matcher: Option<ProcessMatcher>,
+    window_title: Option<String>,
+    data_dir: Option<PathBuf>,
+    notify_on_start: bool,
+    notify_on_end: bool,
+    pause: Option<Arc<AtomicBool>>,
+    write_status_file: bool,
+    write_event_log: bool,
 }
 
 impl Monitor {
     pub fn new(poll_interval: Duration, min_session_minutes: u64) -> Self {
         Self {
             poll_interval,
+            max_poll_interval: DEFAULT_MAX_POLL_INTERVAL,
+            idle_backoff_factor: DEFAULT_IDLE_BACKOFF_FACTOR,
             min_session_minutes,
+            recovered_min_session_minutes: None,
+            grace_period: DEFAULT_GRACE_PERIOD,
+            merge_gap_minutes: 0,
             snapshot: None,
+            quiet: false,
+            stale_active_minutes: None,
+            discard_short_sessions: true,
+            idle_timeout_minutes: None,
+            process_tokens: default_process_tokens(),
+            match_mode: MatchMode::Contains,
+            matcher: ProcessMatcher::new(MatchMode::Contains, &default_process_tokens()).ok(),
+            window_title: None,
+            data_dir: None,
+            notify_on_start: false,
+            notify_on_end: false,
+            pause: None,
+            write_status_file: false,
+            write_event_log: false,
+        }
+    }
+
+    /// Append every start, continue, recover and finalize to `events.jsonl` in the data
+    /// directory. The file is rotated by `rotate_log` once it reaches `LOG_ROTATE_BYTES`.
+    pub fn with_event_log(mut self, enabled: bool) -> Self {
+        self.write_event_log = enabled;
+        self
+    }
+
+    /// Rewrite `status.txt` and `status.json` in the data directory after every poll so
+    /// overlays can show whether a session is running and for how long.
+    pub fn with_status_file(mut self, enabled: bool) -> Self {
+        self.write_status_file = enabled;
+        self
+    }
+
+    /// Poll `factor` times less often while no session is active, never slower than
+    /// `max_interval`. The configured interval is used again as soon as tracking starts.
+    /// A factor of 1 keeps polling at a constant rate.
+    pub fn with_idle_backoff(mut self, factor: u32, max_interval: Duration) -> Self {
+        self.idle_backoff_factor = factor.max(1);
+        self.max_poll_interval = max_interval;
+        self
+    }
+
+    /// While `pause` is set no new sessions are started and any in-progress session is
+    /// finalized; tracking picks up again once it is cleared.
+    pub fn with_pause_flag(mut self, pause: Arc<AtomicBool>) -> Self {
+        self.pause = Some(pause);
+        self
+    }
+
+    /// Show a desktop notification when a session starts and/or is saved (Windows only).
+    pub fn with_notifications(mut self, on_start: bool, on_end: bool) -> Self {
+        self.notify_on_start = on_start;
+        self.notify_on_end = on_end;
+        self
+    }
+
+    /// Store sessions in `dir` instead of the default data directory.
+    pub fn with_data_dir(mut self, dir: Option<PathBuf>) -> Self {
+        self.data_dir = dir;
+        self
+    }
+
+    /// Track processes matching any of `tokens` (case-insensitive, see `with_match_mode`)
+    /// instead of the Star Citizen defaults. An empty list keeps the defaults.
+    pub fn with_process_tokens(mut self, tokens: Vec<String>) -> Self {
+        let tokens = normalize_process_tokens(tokens);
+        if !tokens.is_empty() {
+            self.process_tokens = tokens;
+        }
+        self.matcher = ProcessMatcher::new(self.match_mode, &self.process_tokens).ok();
+        self
+    }
+
+    /// How process names are compared with the tokens. An invalid regular expression makes
+    /// `run` and `run_once` fail before the first poll.
+    pub fn with_match_mode(mut self, mode: MatchMode) -> Self {
+        self.match_mode = mode;
+        self.matcher = ProcessMatcher::new(self.match_mode, &self.process_tokens).ok();
+        self
+    }
+
+    /// Fails with the reason when the tokens are not valid for the match mode.
+    fn check_matcher(&self) -> Result<()> {
+        if self.matcher.is_none() {
+            ProcessMatcher::new(self.match_mode, &self.process_tokens)?;
+        }
+        Ok(())
+    }
+
+    /// Also treat the game as running when a visible top-level window's title contains
+    /// `title` (case-insensitive), for systems where the process name is not recognizable.
+    /// Enumerating windows costs more than the process check, so it only runs when no
+    /// process matched. Only supported on Windows.
+    pub fn with_window_title(mut self, title: Option<String>) -> Self {
+        self.window_title = title
+            .map(|title| title.trim().to_string())
+            .filter(|title| !title.is_empty());
+        self
+    }
+
+    /// End the active session at the last input time once the machine has had no
+    /// keyboard/mouse input for `minutes`. Only supported on Windows.
+    pub fn with_idle_timeout(mut self, minutes: Option<u64>) -> Self {
+        self.idle_timeout_minutes = minutes.filter(|value| *value > 0);
+        self
+    }
+
+    /// Minimum length for sessions recovered from a marker left by an earlier run (after a
+    /// crash or an unclean shutdown) instead of `min_session_minutes`. `None` applies the
+    /// regular minimum; `Some(0)` keeps every recovered session.
+    pub fn with_recovered_min_session(mut self, minutes: Option<u64>) -> Self {
+        self.recovered_min_session_minutes = minutes;
+        self
+    }
+
+    /// Keep a session open while the game has been gone for less than `grace`, so a process
+    /// restart (shader recompile, relaunch) continues it instead of starting a new one. Idle
+    /// timeouts and pausing still end the session immediately. `Duration::ZERO` disables it.
+    pub fn with_grace_period(mut self, grace: Duration) -> Self {
+        self.grace_period = grace;
+        self
+    }
+
+    /// Merge a finished session into the previous one when it started within `minutes` of
+    /// that session's end, so "quit to menu, relaunch" is stored as one session. Unlike the
+    /// grace period this applies after the fact, however long the game was gone. 0 disables it.
+    pub fn with_merge_gap(mut self, minutes: u64) -> Self {
+        self.merge_gap_minutes = minutes;
+        self
+    }
+
+    /// Minimum session length applied when finalizing a live or a recovered session.
+    pub fn min_session_minutes_for(&self, recovered: bool) -> u64 {
+        match self.recovered_min_session_minutes {
+            Some(minutes) if recovered => minutes,
+            _ => self.min_session_minutes,
         }
     }
 
+    /// When false, sessions shorter than the minimum are saved with a "short" note
+    /// instead of being dropped.
+    pub fn with_discard_short_sessions(mut self, discard: bool) -> Self {
+        self.discard_short_sessions = discard;
+        self
+    }
+
+    /// Close out a restored active marker instead of resuming it when its `last_seen`
+    /// is older than `minutes`. `None` or `Some(0)` disables the guard.
+    pub fn with_stale_active_reset(mut self, minutes: Option<u64>) -> Self {
+        self.stale_active_minutes = minutes.filter(|value| *value > 0);
+        self
+    }
+
+    /// Suppress informational console output, including the end-of-run summary.
+    pub fn with_quiet(mut self, quiet: bool) -> Self {
+        self.quiet = quiet;
+        self
+    }
+
     pub fn with_status_sink(mut self, snapshot: Arc<Mutex<MonitorSnapshot>>) -> Self {
         self.snapshot = Some(snapshot);
         self
     }
 
+    /// Polls until `stop` is set. Fails straight away when another monitor holds the data
+    /// directory; any error is also recorded in the status sink.
     pub fn run(&mut self, stop: Arc<AtomicBool>) -> Result<()> {
-        let mut system = System::new();
-        let store = SessionStore::new()?;
+        let result = self.run_loop(stop);
+        if let Err(err) = &result {
+            let message = format!("{err:#}");
+            self.update_snapshot(|snapshot| {
+                snapshot.status_text = "Stopped".to_string();
+                snapshot.error = Some(message);
+            });
+        }
+        result
+    }
+
+    fn run_loop(&mut self, stop: Arc<AtomicBool>) -> Result<()> {
+        self.check_matcher()?;
+        let mut system =
+            System::new_with_specifics(RefreshKind::new().with_processes(process_refresh_kind()));
+        let store = self.open_store()?;
+        let _lock = store.lock_monitor()?;
         let mut active = store
             .load_active()
             .context("Failed to restore active session state")?;
 
-        println!(
-            "Star Citizen monitor running (poll every {}s, min session {}m)",
-            self.poll_interval.as_secs(),
-            self.min_session_minutes
+        let mut summary = RunSummary::default();
+
+        if let Some(session) = &active {
+            self.record_event(&store, SessionEventKind::Recover, session, None, true);
+        }
+
+        let idle_interval = idle_poll_interval(
+            self.poll_interval,
+            self.idle_backoff_factor,
+            self.max_poll_interval,
         );
+        self.log(format!(
+            "Playtime monitor running (poll every {}s, {}s while idle, min session {}m, tracking {} [{}])",
+            self.poll_interval.as_secs(),
+            idle_interval.as_secs(),
+            self.min_session_minutes,
+            self.process_tokens.join(", "),
+            self.match_mode.label()
+        ));
 
         self.update_snapshot(|snapshot| {
             snapshot.status_text = "Idle".to_string();
             snapshot.active_session = active.clone();
+            snapshot.error = None;
         });
 
-        system.refresh_processes();
+        refresh_processes(&mut system);
+
+        self.close_stale_active(&store, &system, &mut active, &mut summary)?;
 
         if let Some(ref session) = active {
-            println!(
+            self.log(format!(
                 "Resumed active session from {}",
                 session.start.format("%Y-%m-%d %H:%M:%S")
-            );
-            if !is_game_running(&system) {
-                if let Some(saved) =
-                    finalize_session(&store, session.clone(), self.min_session_minutes)?
-                {
-                    println!(
+            ));
+            if self.running_game(&system).is_none() {
+                let capped = recovery_gap_exceeded(session, Local::now(), self.poll_interval);
+                if capped {
+                    self.log(format!(
+                        "Marker was last refreshed {}; closing the session there instead of now.",
+                        session.last_seen.format("%Y-%m-%d %H:%M:%S")
+                    ));
+                }
+                let note = capped.then_some(RECOVERED_CAPPED_NOTE);
+                if let Some(saved) = self.finalize_session(&store, session.clone(), note, true)? {
+                    self.log(format!(
                         "Recovered session saved: {} for {}",
                         saved.start.format("%Y-%m-%d %H:%M:%S"),
                         format_duration(saved.duration_minutes)
-                    );
+                    ));
+                    summary.record(&saved);
                     active = None;
                     self.update_snapshot(|snapshot| {
                         snapshot.status_text = "Idle".to_string();
@@ -92,80 +475,359 @@ impl Monitor {
             }
         }
 
+        let mut poll_failed = false;
         loop {
             if stop.load(Ordering::SeqCst) {
                 if let Some(active) = active {
                     store.save_active(&active)?;
+                    summary.pending = Some(active_session_minutes(&active));
                     self.update_snapshot(|snapshot| {
                         snapshot.active_session = Some(active);
                         snapshot.status_text = "Pending resume".to_string();
                     });
                 }
-                println!("Stop flag set, shutting down monitor loop.");
+                // The monitor no longer tracks anything, even if the marker resumes later.
+                self.write_status(&store, None, Local::now());
+                self.log("Stop flag set, shutting down monitor loop.");
                 break;
             }
 
-            system.refresh_processes();
-            let running = is_game_running(&system);
-            let now = Local::now();
+            // Past this point errors are treated as transient (a file briefly locked by an
+            // antivirus scan, say): they are reported and the next poll tries again.
+            match self.poll(&store, &mut system, &mut active, &mut summary) {
+                Ok(()) if poll_failed => {
+                    poll_failed = false;
+                    self.log("Poll succeeded again.");
+                    self.update_snapshot(|snapshot| snapshot.error = None);
+                }
+                Ok(()) => {}
+                Err(err) => {
+                    poll_failed = true;
+                    let message = format!("{err:#}");
+                    self.log(format!("Poll failed, retrying on the next poll: {message}"));
+                    self.update_snapshot(|snapshot| snapshot.error = Some(message));
+                }
+            }
 
-            if running {
-                match active {
-                    Some(ref mut session) => {
-                        session.last_seen = now;
-                        store.save_active(session)?;
-                        let snapshot_session = session.clone();
-                        self.update_snapshot(|snapshot| {
-                            snapshot.status_text = "Tracking".to_string();
-                            snapshot.active_session = Some(snapshot_session);
-                        });
+            let interval = if active.is_some() {
+                self.poll_interval
+            } else {
+                idle_interval
+            };
+            wait_for_next_poll(&stop, interval);
+        }
+
+        self.log(summary.describe());
+        self.log("Monitor loop exited normally.");
+        Ok(())
+    }
+
+    /// Runs a single detection pass against the persisted active marker and returns the session
+    /// still in progress afterwards, if any. Meant for an external scheduler that owns the cadence.
+    pub fn run_once(&self) -> Result<Option<ActiveSession>> {
+        self.check_matcher()?;
+        let mut system =
+            System::new_with_specifics(RefreshKind::new().with_processes(process_refresh_kind()));
+        let store = self.open_store()?;
+        let _lock = store.lock_monitor()?;
+        let mut active = store
+            .load_active()
+            .context("Failed to restore active session state")?;
+        let mut summary = RunSummary::default();
+
+        refresh_processes(&mut system);
+        self.close_stale_active(&store, &system, &mut active, &mut summary)?;
+        self.poll(&store, &mut system, &mut active, &mut summary)?;
+        Ok(active)
+    }
+
+    fn open_store(&self) -> Result<SessionStore> {
+        match &self.data_dir {
+            Some(dir) => SessionStore::with_dir(dir.clone()),
+            None => SessionStore::new(),
+        }
+    }
+
+    /// One detection pass: starts, continues or finalizes `active` depending on whether the game
+    /// is running right now, persisting the marker the same way the loop does.
+    fn poll(
+        &self,
+        store: &SessionStore,
+        system: &mut System,
+        active: &mut Option<ActiveSession>,
+        summary: &mut RunSummary,
+    ) -> Result<()> {
+        refresh_processes(system);
+        let now = Local::now();
+        let idle_for = self.idle_timeout_minutes.and_then(|minutes| {
+            system_idle_duration()
+                .filter(|idle| *idle >= Duration::from_secs(minutes.saturating_mul(60)))
+        });
+        let paused = self.is_paused();
+        let idle_status = if paused { "Paused" } else { "Idle" };
+        let running = self
+            .running_game(system)
+            .filter(|_| idle_for.is_none() && !paused);
+        let grace =
+            chrono::Duration::from_std(self.grace_period).unwrap_or(chrono::Duration::zero());
+
+        if let Some(detected) = running {
+            let game = detected.name.clone();
+            match active {
+                Some(session) => {
+                    session.last_seen = now;
+                    session.game.get_or_insert(game);
+                    store.save_active(session)?;
+                    self.record_event(store, SessionEventKind::Continue, session, None, false);
+                    let snapshot_session = session.clone();
+                    self.update_snapshot(|snapshot| {
+                        snapshot.status_text = "Tracking".to_string();
+                        snapshot.active_session = Some(snapshot_session);
+                    });
+                }
+                None => {
+                    self.log(format!(
+                        "Detected {game} start at {} via {}",
+                        now.format("%Y-%m-%d %H:%M:%S"),
+                        detected.describe()
+                    ));
+                    if self.notify_on_start {
+                        self.notify("Session started", &format!("Tracking {game}."));
                     }
-                    None => {
-                        let session = ActiveSession::new(now);
-                        store.save_active(&session)?;
-                        println!(
-                            "Detected Star Citizen start at {}",
-                            session.start.format("%Y-%m-%d %H:%M:%S")
-                        );
-                        self.update_snapshot(|snapshot| {
-                            snapshot.status_text = "Tracking".to_string();
-                            snapshot.active_session = Some(session.clone());
-                        });
-                        active = Some(session);
+                    let mut session = ActiveSession::new(now);
+                    session.game = Some(game);
+                    // Kept in memory even if the marker cannot be written, so a retry continues
+                    // this session instead of starting a new one.
+                    *active = Some(session.clone());
+                    store.save_active(&session)?;
+                    self.record_event(store, SessionEventKind::Start, &session, None, false);
+                    self.update_snapshot(|snapshot| {
+                        snapshot.status_text = "Tracking".to_string();
+                        snapshot.active_session = Some(session);
+                    });
+                }
+            }
+        } else if let Some(session) = active.as_ref()
+            && holds_through_grace(session, idle_for.is_some() || paused, now, grace)
+        {
+            // The game may only be restarting; the next poll either continues the session or
+            // ends it at the `last_seen` from before the gap.
+            let snapshot_session = session.clone();
+            self.update_snapshot(|snapshot| {
+                snapshot.status_text = "Tracking".to_string();
+                snapshot.active_session = Some(snapshot_session);
+            });
+        } else if let Some(mut session) = active.take() {
+            if let Some(idle) = idle_for {
+                let idle = chrono::Duration::from_std(idle).unwrap_or(chrono::Duration::zero());
+                let last_input = now - idle;
+                session.last_seen = last_input.clamp(session.start, session.last_seen);
+                self.log(format!(
+                    "No input for {}; ending session at last activity {}",
+                    format_duration(idle.num_seconds() as f64 / 60.0),
+                    session.last_seen.format("%Y-%m-%d %H:%M:%S")
+                ));
+            }
+            if paused {
+                self.log("Tracking paused; ending the current session.");
+            }
+            let saved = match self.finalize_session(store, session.clone(), None, false) {
+                Ok(saved) => saved,
+                Err(err) => {
+                    // Keep the session so the next poll retries, unless it already reached the
+                    // history (possibly merged into an earlier one) and only clearing the
+                    // marker failed.
+                    let appended = store.load_sessions().is_ok_and(|sessions| {
+                        sessions.iter().any(|saved| {
+                            saved.start <= session.start && saved.end >= session.last_seen
+                        })
+                    });
+                    if !appended {
+                        *active = Some(session);
                     }
+                    return Err(err);
+                }
+            };
+            if let Some(saved) = saved {
+                self.log(format!(
+                    "Session saved: {} lasting {}",
+                    saved.start.format("%Y-%m-%d %H:%M:%S"),
+                    format_duration(saved.duration_minutes)
+                ));
+                summary.record(&saved);
+                self.update_snapshot(|snapshot| {
+                    snapshot.status_text = idle_status.to_string();
+                    snapshot.active_session = None;
+                    snapshot.last_session = Some(saved);
+                });
+            } else {
+                self.update_snapshot(|snapshot| {
+                    snapshot.status_text = idle_status.to_string();
+                    snapshot.active_session = None;
+                });
+            }
+        } else {
+            self.update_snapshot(|snapshot| {
+                if snapshot.active_session.is_some() {
+                    snapshot.active_session = None;
                 }
-            } else if let Some(session) = active.take() {
-                if let Some(saved) = finalize_session(&store, session, self.min_session_minutes)? {
-                    println!(
-                        "Session saved: {} lasting {}",
+                snapshot.status_text = idle_status.to_string();
+            });
+        }
+        self.write_status(store, active.as_ref(), now);
+        Ok(())
+    }
+
+    /// The running game client, falling back to the window title check when configured.
+    /// Title matches are recorded under the default game label.
+    fn running_game(&self, system: &System) -> Option<DetectedProcess> {
+        let matched = self
+            .matcher
+            .as_ref()
+            .and_then(|matcher| running_game(system, matcher));
+        matched.or_else(|| {
+            self.window_title
+                .as_deref()
+                .filter(|title| window_title_present(title))
+                .map(|_| DetectedProcess {
+                    kind: GameProcessKind::Client,
+                    name: DEFAULT_GAME_LABEL.to_string(),
+                    pid: None,
+                    instances: 1,
+                })
+        })
+    }
+
+    fn write_status(
+        &self,
+        store: &SessionStore,
+        active: Option<&ActiveSession>,
+        now: DateTime<Local>,
+    ) {
+        if !self.write_status_file {
+            return;
+        }
+        let status = StatusReport::new(active, now);
+        if let Err(err) = status.write_to(store.data_dir()) {
+            self.log(format!("{err:#}"));
+        }
+    }
+
+    /// Closes a restored marker whose `last_seen` is older than the stale threshold instead of
+    /// letting it resume. A marker is never stale while the game is still running, since the
+    /// gap then only means the monitor itself was stopped; `system` must be freshly refreshed.
+    fn close_stale_active(
+        &self,
+        store: &SessionStore,
+        system: &System,
+        active: &mut Option<ActiveSession>,
+        summary: &mut RunSummary,
+    ) -> Result<()> {
+        if let (Some(session), Some(threshold)) = (active.clone(), self.stale_active_minutes)
+            && active_session_is_stale(&session, Local::now(), threshold)
+            && self.running_game(system).is_none()
+        {
+            self.log(format!(
+                "Active session from {} was last seen {} and is older than {}m; closing it instead of resuming.",
+                session.start.format("%Y-%m-%d %H:%M:%S"),
+                session.last_seen.format("%Y-%m-%d %H:%M:%S"),
+                threshold
+            ));
+            *active = None;
+            match self.finalize_session(store, session, Some(RECOVERED_CAPPED_NOTE), true)? {
+                Some(saved) => {
+                    self.log(format!(
+                        "Stale session saved: {} for {}",
                         saved.start.format("%Y-%m-%d %H:%M:%S"),
                         format_duration(saved.duration_minutes)
-                    );
+                    ));
+                    summary.record(&saved);
                     self.update_snapshot(|snapshot| {
-                        snapshot.status_text = "Idle".to_string();
                         snapshot.active_session = None;
                         snapshot.last_session = Some(saved);
                     });
-                } else {
-                    self.update_snapshot(|snapshot| {
-                        snapshot.status_text = "Idle".to_string();
-                        snapshot.active_session = None;
-                    });
                 }
-            } else {
-                self.update_snapshot(|snapshot| {
-                    if snapshot.active_session.is_some() {
-                        snapshot.active_session = None;
-                    }
-                    snapshot.status_text = "Idle".to_string();
-                });
+                None => {
+                    self.log("Stale session was shorter than the minimum and was discarded.");
+                    self.update_snapshot(|snapshot| snapshot.active_session = None);
+                }
             }
+        }
+        Ok(())
+    }
+
+    fn finalize_session(
+        &self,
+        store: &SessionStore,
+        active: ActiveSession,
+        note: Option<&str>,
+        recovered: bool,
+    ) -> Result<Option<Session>> {
+        let saved = finalize_session(
+            store,
+            active.clone(),
+            self.min_session_minutes_for(recovered),
+            self.discard_short_sessions,
+            self.merge_gap_minutes,
+            note,
+        )?;
+        match &saved {
+            Some(session) => self.record_event(
+                store,
+                SessionEventKind::Finalize,
+                &active,
+                Some(session.note.as_str()).filter(|note| !note.is_empty()),
+                recovered,
+            ),
+            None => self.record_event(store, SessionEventKind::Discard, &active, None, recovered),
+        }
+        if let Some(session) = &saved
+            && self.notify_on_end
+        {
+            self.notify(
+                "Session saved",
+                &format!(
+                    "Session saved: {}",
+                    format_duration(session.duration_minutes)
+                ),
+            );
+        }
+        Ok(saved)
+    }
 
-            thread::sleep(self.poll_interval);
+    fn record_event(
+        &self,
+        store: &SessionStore,
+        kind: SessionEventKind,
+        session: &ActiveSession,
+        note: Option<&str>,
+        recovered: bool,
+    ) {
+        if !self.write_event_log {
+            return;
+        }
+        let event = SessionEvent::new(kind, session, note, recovered, Local::now());
+        if let Err(err) = event.append_to(store.data_dir()) {
+            self.log(format!("{err:#}"));
         }
+    }
 
-        println!("Monitor loop exited normally.");
-        Ok(())
+    fn notify(&self, title: &str, body: &str) {
+        if let Err(err) = notifications::notify(title, body) {
+            self.log(format!("{err:#}"));
+        }
+    }
+
+    fn is_paused(&self) -> bool {
+        self.pause
+            .as_ref()
+            .is_some_and(|pause| pause.load(Ordering::SeqCst))
+    }
+
+    fn log<S: Display>(&self, message: S) {
+        if !self.quiet {
+            println!("{message}");
+        }
     }
 
     fn update_snapshot<F>(&self, update: F)
@@ -180,28 +842,283 @@ impl Monitor {
     }
 }
 
+/// Counters collected over a single `Monitor::run` invocation.
+#[derive(Debug, Clone, Default)]
+struct RunSummary {
+    sessions_recorded: usize,
+    total_minutes: f64,
+    longest_minutes: f64,
+    pending: Option<f64>,
+}
+
+impl RunSummary {
+    fn record(&mut self, session: &Session) {
+        self.sessions_recorded += 1;
+        self.total_minutes += session.duration_minutes;
+        self.longest_minutes = self.longest_minutes.max(session.duration_minutes);
+    }
+
+    fn describe(&self) -> String {
+        let mut text = if self.sessions_recorded == 0 {
+            "Run summary: no sessions recorded.".to_string()
+        } else {
+            format!(
+                "Run summary: {} session{} recorded, {} tracked, longest {}.",
+                self.sessions_recorded,
+                if self.sessions_recorded == 1 { "" } else { "s" },
+                format_duration(self.total_minutes),
+                format_duration(self.longest_minutes)
+            )
+        };
+        if let Some(minutes) = self.pending {
+            text.push_str(&format!(
+                " Active session ({}) will resume on next start.",
+                format_duration(minutes)
+            ));
+        }
+        text
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct MonitorSnapshot {
     pub status_text: String,
     pub active_session: Option<ActiveSession>,
     pub last_session: Option<Session>,
+    /// Why the monitor stopped, when it exited with an error, or the last failed poll while
+    /// it keeps retrying.
+    pub error: Option<String>,
+}
+
+/// Contents of the status files written by `Monitor::with_status_file`.
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusReport {
+    pub tracking: bool,
+    pub elapsed_minutes: f64,
+    pub started_at: Option<DateTime<Local>>,
+    pub game: Option<String>,
+    pub updated_at: DateTime<Local>,
+}
+
+impl StatusReport {
+    pub fn new(active: Option<&ActiveSession>, now: DateTime<Local>) -> Self {
+        Self {
+            tracking: active.is_some(),
+            elapsed_minutes: active.map(active_session_minutes).unwrap_or(0.0),
+            started_at: active.map(|session| session.start),
+            game: active.and_then(|session| session.game.clone()),
+            updated_at: now,
+        }
+    }
+
+    /// One line suitable for an OBS text source, e.g. `StarCitizen — 1h 05m`.
+    pub fn text(&self) -> String {
+        if self.tracking {
+            format!(
+                "{} — {}",
+                self.game.as_deref().unwrap_or("Playing"),
+                format_duration(self.elapsed_minutes)
+            )
+        } else {
+            "Not playing".to_string()
+        }
+    }
+
+    /// Writes `status.txt` and `status.json` into `dir`.
+    pub fn write_to(&self, dir: &Path) -> Result<()> {
+        write_atomic(&dir.join(STATUS_TEXT_FILE), self.text())?;
+        let payload =
+            serde_json::to_string_pretty(self).context("Failed to serialize status file")?;
+        write_atomic(&dir.join(STATUS_JSON_FILE), payload)
+    }
+}
+
+/// What happened to a session, as recorded in `EVENT_LOG_FILE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionEventKind {
+    /// The game was detected and a new session began.
+    Start,
+    /// A poll still saw the game running and extended the session.
+    Continue,
+    /// An in-progress marker left by an earlier run was restored.
+    Recover,
+    /// The session ended and was saved.
+    Finalize,
+    /// The session ended but was shorter than the minimum and dropped.
+    Discard,
+}
+
+/// One line of `EVENT_LOG_FILE`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionEvent {
+    pub event: SessionEventKind,
+    pub at: DateTime<Local>,
+    pub start: DateTime<Local>,
+    pub last_seen: DateTime<Local>,
+    pub duration_minutes: f64,
+    pub game: Option<String>,
+    /// Whether the session came from a marker restored at startup.
+    pub recovered: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+impl SessionEvent {
+    pub fn new(
+        event: SessionEventKind,
+        session: &ActiveSession,
+        note: Option<&str>,
+        recovered: bool,
+        at: DateTime<Local>,
+    ) -> Self {
+        Self {
+            event,
+            at,
+            start: session.start,
+            last_seen: session.last_seen,
+            duration_minutes: active_session_minutes(session),
+            game: session.game.clone(),
+            recovered,
+            note: note.map(str::to_string),
+        }
+    }
+
+    /// Appends this event to `EVENT_LOG_FILE` in `dir`, rotating a full log first.
+    pub fn append_to(&self, dir: &Path) -> Result<()> {
+        let path = dir.join(EVENT_LOG_FILE);
+        rotate_log(&path, LOG_ROTATE_BYTES, LOG_ROTATE_KEEP)?;
+        let line = serde_json::to_string(self).context("Failed to serialize session event")?;
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        writeln!(file, "{line}").with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+fn wait_for_next_poll(stop: &AtomicBool, interval: Duration) {
+    let mut remaining = interval;
+    while !remaining.is_zero() && !stop.load(Ordering::SeqCst) {
+        let step = remaining.min(STOP_CHECK_INTERVAL);
+        thread::sleep(step);
+        remaining -= step;
+    }
+}
+
+/// Poll interval used while no game is running: `base` stretched by `factor`, capped at
+/// `max` but never faster than `base` itself.
+pub fn idle_poll_interval(base: Duration, factor: u32, max: Duration) -> Duration {
+    base.saturating_mul(factor.max(1)).min(max).max(base)
+}
+
+/// Time since the last keyboard or mouse input anywhere on the system.
+#[cfg(windows)]
+fn system_idle_duration() -> Option<Duration> {
+    use windows_sys::Win32::System::SystemInformation::GetTickCount;
+    use windows_sys::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+    let mut info = LASTINPUTINFO {
+        cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+        dwTime: 0,
+    };
+    if unsafe { GetLastInputInfo(&mut info) } == 0 {
+        return None;
+    }
+    let now = unsafe { GetTickCount() };
+    Some(Duration::from_millis(now.wrapping_sub(info.dwTime) as u64))
+}
+
+#[cfg(not(windows))]
+fn system_idle_duration() -> Option<Duration> {
+    None
 }
 
-fn is_game_running(system: &System) -> bool {
+/// Whether any visible top-level window has a title containing `needle` (case-insensitive).
+#[cfg(windows)]
+fn window_title_present(needle: &str) -> bool {
+    use windows_sys::Win32::Foundation::{BOOL, HWND, LPARAM};
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        EnumWindows, GetWindowTextLengthW, GetWindowTextW, IsWindowVisible,
+    };
+
+    struct Search {
+        needle: String,
+        found: bool,
+    }
+
+    unsafe extern "system" fn visit(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        // SAFETY: `lparam` is the `Search` passed to `EnumWindows` below, which outlives the call.
+        let search = unsafe { &mut *(lparam as *mut Search) };
+        if unsafe { IsWindowVisible(hwnd) } == 0 {
+            return 1;
+        }
+        let len = unsafe { GetWindowTextLengthW(hwnd) };
+        if len <= 0 {
+            return 1;
+        }
+        let mut buf = vec![0u16; len as usize + 1];
+        let copied = unsafe { GetWindowTextW(hwnd, buf.as_mut_ptr(), buf.len() as i32) };
+        let title = String::from_utf16_lossy(&buf[..copied.max(0) as usize]);
+        if title.to_lowercase().contains(&search.needle) {
+            search.found = true;
+            return 0;
+        }
+        1
+    }
+
+    let mut search = Search {
+        needle: needle.to_lowercase(),
+        found: false,
+    };
+    unsafe {
+        EnumWindows(Some(visit), &mut search as *mut Search as LPARAM);
+    }
+    search.found
+}
+
+#[cfg(not(windows))]
+fn window_title_present(_needle: &str) -> bool {
+    false
+}
+
+/// What detection needs from each process: its name, plus the executable path the first time
+/// the process is seen. CPU, memory, disk, user and environment data are never collected, which
+/// keeps each poll cheap on machines running hundreds of processes.
+pub fn process_refresh_kind() -> ProcessRefreshKind {
+    ProcessRefreshKind::new().with_exe(UpdateKind::OnlyIfNotSet)
+}
+
+/// Updates the process list (adding new and dropping exited processes) with `process_refresh_kind`.
+pub fn refresh_processes(system: &mut System) {
+    system.refresh_processes_specifics(process_refresh_kind());
+}
+
+/// Returns the running game client, if any. Launchers and helpers alone never count.
+fn running_game(system: &System, matcher: &ProcessMatcher) -> Option<DetectedProcess> {
+    detect_game_process(system, matcher).filter(|detected| detected.kind == GameProcessKind::Client)
+}
+
+/// Scans running processes and reports the most relevant process accepted by `matcher`.
+/// A running game client takes precedence over a launcher, and a launcher over a helper.
+pub fn detect_game_process(system: &System, matcher: &ProcessMatcher) -> Option<DetectedProcess> {
     let current_pid = get_current_pid().ok();
     let self_exe_name = env::current_exe().ok().and_then(|path| {
         path.file_name()
             .and_then(|name| name.to_str().map(|s| s.to_ascii_lowercase()))
     });
 
-    system.processes().values().any(|process| {
+    let mut client: Option<DetectedProcess> = None;
+    let mut other: Option<DetectedProcess> = None;
+    for process in system.processes().values() {
         if current_pid.map(|pid| pid == process.pid()).unwrap_or(false) {
-            return false;
+            continue;
         }
 
         let process_name = process.name().to_ascii_lowercase();
         if process_name.contains("playtime") {
-            return false;
+            continue;
         }
 
         let exe_name_lower = process
@@ -212,36 +1129,313 @@ fn is_game_running(system: &System) -> bool {
 
         if let (Some(self_name), Some(exe_name)) = (&self_exe_name, &exe_name_lower) {
             if exe_name == self_name {
-                return false;
+                continue;
             }
         }
 
-        if PROCESS_TOKENS
-            .iter()
-            .any(|token| process_name.contains(token))
+        let kind = classify_process_name(&process_name, matcher).or_else(|| {
+            exe_name_lower
+                .as_deref()
+                .and_then(|name| classify_process_name(name, matcher))
+        });
+        let Some(kind) = kind else {
+            continue;
+        };
+        let pid = process.pid().as_u32();
+        let found = DetectedProcess {
+            kind,
+            name: process_display_name(process.name()),
+            pid: Some(pid),
+            instances: 1,
+        };
+        let slot = match kind {
+            GameProcessKind::Client => &mut client,
+            GameProcessKind::Launcher | GameProcessKind::Helper => &mut other,
+        };
+        match slot {
+            Some(existing) if existing.kind == kind => {
+                existing.instances += 1;
+                if existing.pid.is_none_or(|existing_pid| pid < existing_pid) {
+                    existing.name = found.name;
+                    existing.pid = found.pid;
+                }
+            }
+            // A launcher replaces a helper, never the other way round.
+            Some(existing) if existing.kind == GameProcessKind::Launcher => {}
+            _ => *slot = Some(found),
+        }
+    }
+    client.or(other)
+}
+
+fn classify_process_name(name: &str, matcher: &ProcessMatcher) -> Option<GameProcessKind> {
+    if LAUNCHER_TOKENS.iter().any(|token| name.contains(token)) {
+        return Some(GameProcessKind::Launcher);
+    }
+    if matcher.matches(name) {
+        if name.contains("launcher") {
+            return Some(GameProcessKind::Launcher);
+        }
+        if HELPER_TOKENS.iter().any(|token| name.contains(token)) {
+            return Some(GameProcessKind::Helper);
+        }
+        return Some(GameProcessKind::Client);
+    }
+    None
+}
+
+/// Strips a trailing `.exe` so sessions read `StarCitizen` rather than `StarCitizen.exe`.
+fn process_display_name(name: &str) -> String {
+    let trimmed = name.trim();
+    match trimmed.len().checked_sub(4) {
+        Some(cut) if trimmed[cut..].eq_ignore_ascii_case(".exe") => trimmed[..cut].to_string(),
+        _ => trimmed.to_string(),
+    }
+}
+
+pub fn default_process_tokens() -> Vec<String> {
+    DEFAULT_PROCESS_TOKENS
+        .iter()
+        .map(|token| token.to_string())
+        .collect()
+}
+
+/// Trims and de-duplicates (case-insensitively) process tokens, dropping empty entries. Case is
+/// kept because a regular expression can depend on it (`\S` versus `\s`).
+pub fn normalize_process_tokens(tokens: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for token in tokens {
+        let token = token.trim().to_string();
+        if !token.is_empty()
+            && !normalized
+                .iter()
+                .any(|existing| existing.to_lowercase() == token.to_lowercase())
         {
-            return true;
+            normalized.push(token);
         }
+    }
+    normalized
+}
 
-        exe_name_lower
-            .as_ref()
-            .map(|exe| PROCESS_TOKENS.iter().any(|token| exe.contains(token)))
-            .unwrap_or(false)
-    })
+/// True when a session whose game is no longer detected should stay open: the game was last
+/// seen less than `grace` before `now` and tracking was not `interrupted` by idle or pause.
+fn holds_through_grace(
+    session: &ActiveSession,
+    interrupted: bool,
+    now: DateTime<Local>,
+    grace: chrono::Duration,
+) -> bool {
+    !interrupted && now - session.last_seen < grace
+}
+
+/// True when a restored marker's `last_seen` is more than two poll intervals behind `now`,
+/// meaning the tracker was not running for part of the time the marker would otherwise cover.
+pub fn recovery_gap_exceeded(
+    active: &ActiveSession,
+    now: DateTime<Local>,
+    poll_interval: Duration,
+) -> bool {
+    let gap = (now - active.last_seen).to_std().unwrap_or(Duration::ZERO);
+    gap > poll_interval.saturating_mul(2)
 }
 
 fn finalize_session(
     store: &SessionStore,
     active: ActiveSession,
     min_session_minutes: u64,
+    discard_short_sessions: bool,
+    merge_gap_minutes: u64,
+    extra_note: Option<&str>,
 ) -> Result<Option<Session>> {
     let minutes = active_session_minutes(&active);
+    let mut notes = Vec::new();
     if minutes < min_session_minutes as f64 {
-        store.clear_active()?;
-        return Ok(None);
+        if discard_short_sessions {
+            store.clear_active()?;
+            return Ok(None);
+        }
+        notes.push(SHORT_SESSION_NOTE);
     }
-    let session = Session::new(active.start, active.last_seen, String::new());
-    store.append_session(session.clone())?;
+    notes.extend(extra_note);
+    let note = notes.join(", ");
+    let mut session = Session::new(active.start, active.last_seen, note);
+    session.game = active.game;
+    let session = if merge_gap_minutes > 0 {
+        let max_gap = i64::try_from(merge_gap_minutes)
+            .ok()
+            .and_then(chrono::Duration::try_minutes)
+            .unwrap_or(chrono::Duration::MAX);
+        store.append_or_merge_session(session, max_gap)?.0
+    } else {
+        store.append_session(session.clone())?;
+        session
+    };
     store.clear_active()?;
     Ok(Some(session))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::test_support::{TempStore, local};
+
+    #[test]
+    fn session_survives_a_blip_within_the_grace_period() {
+        let start = local("2024-03-10 12:00");
+        let at = |seconds| start + chrono::Duration::seconds(seconds);
+        let grace = chrono::Duration::seconds(30);
+        let mut session = ActiveSession::new(start);
+        // Polls every 5 seconds; the game vanishes between 10s and 20s while it restarts.
+        for (seconds, running) in [(5, true), (10, false), (15, false), (20, false), (25, true)] {
+            if running {
+                session.last_seen = at(seconds);
+            } else {
+                assert!(holds_through_grace(&session, false, at(seconds), grace));
+            }
+        }
+        assert_eq!(session.start, start);
+        assert_eq!(session.last_seen, at(25));
+        // Gone for the full grace period, the session ends at its last sighting.
+        assert!(!holds_through_grace(&session, false, at(55), grace));
+    }
+
+    #[test]
+    fn idle_or_pause_ends_a_session_despite_grace() {
+        let session = ActiveSession::new(local("2024-03-10 12:00"));
+        let now = session.last_seen + chrono::Duration::seconds(5);
+        assert!(!holds_through_grace(
+            &session,
+            true,
+            now,
+            chrono::Duration::seconds(30)
+        ));
+    }
+
+    /// Compares one poll's refresh with `process_refresh_kind` against refreshing every process
+    /// detail. Timing depends on the machine, so it only reports; run it with
+    /// `cargo test --release process_refresh_cost -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn process_refresh_cost() {
+        let time = |kind: ProcessRefreshKind| {
+            let mut system = System::new_with_specifics(RefreshKind::new().with_processes(kind));
+            let rounds = 20;
+            let started = std::time::Instant::now();
+            for _ in 0..rounds {
+                system.refresh_processes_specifics(kind);
+            }
+            (started.elapsed() / rounds, system.processes().len())
+        };
+        let (narrow, processes) = time(process_refresh_kind());
+        let (full, _) = time(ProcessRefreshKind::everything());
+        println!("{processes} processes: {narrow:?} per poll, {full:?} refreshing everything");
+    }
+
+    #[test]
+    fn old_active_marker_is_closed_at_last_seen() {
+        let store = TempStore::new();
+        let now = Local::now();
+        let mut stale = ActiveSession::new(now - chrono::Duration::hours(3));
+        stale.last_seen = now - chrono::Duration::hours(2);
+        store.save_active(&stale).unwrap();
+
+        let monitor = Monitor::new(Duration::from_secs(5), 1)
+            .with_data_dir(Some(store.data_dir().to_path_buf()))
+            .with_process_tokens(vec!["sc-playtime-test-no-such-game".to_string()])
+            .with_stale_active_reset(Some(60))
+            .with_quiet(true);
+        assert!(monitor.run_once().unwrap().is_none());
+
+        let sessions = store.load_sessions().unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].start, stale.start);
+        assert_eq!(sessions[0].end, stale.last_seen);
+        assert_eq!(sessions[0].note, RECOVERED_CAPPED_NOTE);
+        assert!(store.load_active().unwrap().is_none());
+    }
+
+    #[test]
+    fn recovery_gap_is_exceeded_only_past_two_polls() {
+        let poll = Duration::from_secs(5);
+        let session = ActiveSession::new(local("2024-03-10 12:00"));
+        let just_under = session.last_seen + chrono::Duration::seconds(9);
+        let just_over = session.last_seen + chrono::Duration::seconds(11);
+        assert!(!recovery_gap_exceeded(&session, just_under, poll));
+        assert!(recovery_gap_exceeded(&session, just_over, poll));
+    }
+
+    /// A marker `seconds` long starting at noon.
+    fn active_for(seconds: i64) -> ActiveSession {
+        let mut active = ActiveSession::new(local("2024-03-10 12:00"));
+        active.last_seen = active.start + chrono::Duration::seconds(seconds);
+        active
+    }
+
+    #[test]
+    fn recovered_minimum_keeps_short_recovered_sessions() {
+        let store = TempStore::new();
+        let monitor = Monitor::new(Duration::from_secs(5), 10)
+            .with_data_dir(Some(store.data_dir().to_path_buf()))
+            .with_recovered_min_session(Some(2))
+            .with_quiet(true);
+        // Five minutes: over the recovered minimum, under the normal one.
+        assert!(
+            monitor
+                .finalize_session(&store, active_for(300), None, false)
+                .unwrap()
+                .is_none()
+        );
+        let saved = monitor
+            .finalize_session(&store, active_for(300), None, true)
+            .unwrap()
+            .expect("recovered session kept");
+        assert_eq!(saved.duration_minutes, 5.0);
+        assert_eq!(store.load_sessions().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn discard_mode_drops_sessions_below_the_threshold() {
+        let store = TempStore::new();
+        assert!(
+            finalize_session(&store, active_for(599), 10, true, 0, None)
+                .unwrap()
+                .is_none()
+        );
+        assert!(store.load_sessions().unwrap().is_empty());
+        finalize_session(&store, active_for(600), 10, true, 0, None).unwrap();
+        let sessions = store.load_sessions().unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].duration_minutes, 10.0);
+        assert_eq!(sessions[0].note, "");
+    }
+
+    #[test]
+    fn keep_mode_stores_short_sessions_with_a_note() {
+        let store = TempStore::new();
+        finalize_session(&store, active_for(599), 10, false, 0, None).unwrap();
+        finalize_session(&store, active_for(600), 10, false, 0, None).unwrap();
+        let notes = store
+            .load_sessions()
+            .unwrap()
+            .into_iter()
+            .map(|session| session.note)
+            .collect::<Vec<_>>();
+        assert_eq!(notes, [SHORT_SESSION_NOTE, ""]);
+    }
+
+    #[test]
+    fn finalize_session_never_merges_with_a_zero_gap() {
+        let store = TempStore::new();
+        store
+            .append_session(Session::new(
+                local("2024-03-10 10:00"),
+                local("2024-03-10 11:00"),
+                String::new(),
+            ))
+            .unwrap();
+        let mut active = ActiveSession::new(local("2024-03-10 11:00"));
+        active.last_seen = local("2024-03-10 11:30");
+        finalize_session(&store, active, 0, false, 0, None).unwrap();
+        assert_eq!(store.load_sessions().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,22 @@
+use anyhow::Result;
+
+#[cfg(windows)]
+use anyhow::Context;
+#[cfg(windows)]
+use winrt_notification::{Duration, Toast};
+
+/// Shows a desktop toast notification. Only implemented on Windows; elsewhere this is a no-op.
+#[cfg(windows)]
+pub fn notify(title: &str, body: &str) -> Result<()> {
+    Toast::new(Toast::POWERSHELL_APP_ID)
+        .title(title)
+        .text1(body)
+        .duration(Duration::Short)
+        .show()
+        .context("Failed to show desktop notification")
+}
+
+#[cfg(not(windows))]
+pub fn notify(_title: &str, _body: &str) -> Result<()> {
+    Ok(())
+}
@@ -1,20 +1,116 @@
 use std::{fs, path::PathBuf};
 
 use anyhow::{Context, Result};
+use chrono::{FixedOffset, NaiveDate};
 use serde::{Deserialize, Serialize};
 
+use crate::leaderboard::{DEFAULT_SUBMIT_RETRIES, sanitize_username};
+use crate::local_api::DEFAULT_LOCAL_API_PORT;
+use crate::monitor::{
+    DEFAULT_GRACE_PERIOD, MatchMode, default_process_tokens, normalize_process_tokens,
+};
+use crate::startup::StartupMethod;
+use crate::storage::{PatchRelease, write_atomic};
+
+/// Largest analytics UTC offset accepted, in minutes (UTC±14:00).
+pub const MAX_UTC_OFFSET_MINUTES: i32 = 14 * 60;
+
+/// Upper bound for the Task Scheduler start-up delay (one hour).
+pub const MAX_STARTUP_DELAY_SECONDS: u32 = 3600;
+
+/// Dashboard color scheme. `System` follows the OS light/dark preference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+    System,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct AppSettings {
     pub poll_seconds: u64,
     pub min_session_minutes: u64,
+    /// Drop sessions shorter than `min_session_minutes`; when false they are kept with a "short" note.
+    pub discard_short_sessions: bool,
+    /// Minimum length for sessions recovered after a crash; `None` uses `min_session_minutes`.
+    pub recovered_min_session_minutes: Option<u64>,
+    /// Seconds the game may be gone before its session ends; a restart within this continues it.
+    pub grace_seconds: u64,
+    /// Merge a session into the previous one when it starts within this many minutes of its
+    /// end (0 disables).
+    pub merge_gap_minutes: u64,
+    /// End the active session after this many minutes without keyboard/mouse input (0 disables).
+    pub idle_timeout_minutes: u64,
+    /// Close a restored active session older than this instead of resuming it (0 disables).
+    pub auto_reset_stale_active_minutes: u64,
+    /// Case-insensitive process names, fragments or patterns (see `process_match_mode`) that
+    /// count as a running game.
+    pub process_tokens: Vec<String>,
+    pub process_match_mode: MatchMode,
+    /// Also detect the game by a "Star Citizen" window title (Windows only).
+    pub detect_window_title: bool,
     pub refresh_seconds: u64,
+    /// Show a desktop notification when tracking starts (Windows only).
+    pub notify_on_session_start: bool,
+    /// Show a desktop notification when a session is saved (Windows only).
+    pub notify_on_session_end: bool,
+    /// Daily playtime target shown on the Overview tab (0 disables).
+    pub daily_goal_minutes: u64,
+    /// Total playtime thresholds, in hours, that trigger a one-time congratulation.
+    pub milestone_hours: Vec<u64>,
+    /// Patch release dates used to break playtime down per patch on the Insights tab.
+    pub patches: Vec<PatchRelease>,
+    /// Milestones already congratulated, so they are not announced again after a restart.
+    pub announced_milestones: Vec<u64>,
+    /// Longest run of consecutive play days seen so far, kept even if those sessions are deleted.
+    pub best_streak_days: u32,
+    /// Last day of the `best_streak_days` run.
+    pub best_streak_end_date: Option<NaiveDate>,
     pub run_on_login: bool,
+    /// How "run on login" is registered (Windows only).
+    pub startup_method: StartupMethod,
+    /// Seconds to wait after login before starting, for the Task Scheduler method.
+    pub startup_delay_seconds: u32,
+    /// The first-run introduction has been shown. Only a fresh install (no `settings.json`)
+    /// starts with this unset, so existing users never see it.
+    pub has_onboarded: bool,
+    /// Keep `status.txt`/`status.json` in the data directory up to date for stream overlays.
+    pub write_status_file: bool,
+    /// Append session lifecycle events to `events.jsonl` in the data directory.
+    pub write_event_log: bool,
+    /// Serve status and analytics as JSON on `127.0.0.1:local_api_port` while the dashboard runs.
+    pub enable_local_api: bool,
+    pub local_api_port: u16,
+    /// Colour of the tray icon while idle (Windows only); it turns green while a session is tracked.
+    pub tray_icon_color: [u8; 3],
+    /// Write every tray event to the tray log, not just failures (Windows only).
+    pub tray_debug_logging: bool,
+    /// Tracking paused from the dashboard; kept across restarts.
+    pub tracking_paused: bool,
+    pub theme: Theme,
     pub show_daily_chart: bool,
     pub show_weekly_chart: bool,
+    pub show_hour_chart: bool,
+    pub show_weekday_chart: bool,
+    pub show_duration_chart: bool,
+    /// Fold the session in progress into the Overview totals so they tick up while playing.
+    pub include_active_session: bool,
+    /// Fixed UTC offset, in minutes, for analytics days, weekdays and hours; `None` follows
+    /// the system time zone.
+    pub analytics_utc_offset_minutes: Option<i32>,
+    pub share_include_username: bool,
     pub sync_leaderboard: bool,
     pub leaderboard_username: String,
+    /// Submit a hash of the username instead of the name itself.
+    pub leaderboard_anonymize: bool,
     pub leaderboard_endpoint: String,
+    /// Extra attempts for a leaderboard submission that hits a connection error or timeout.
+    pub leaderboard_retries: u32,
+    /// Use the local leaderboard when the endpoint does not answer at startup.
+    pub leaderboard_local_fallback: bool,
 }
 
 impl Default for AppSettings {
@@ -22,13 +118,50 @@ impl Default for AppSettings {
         Self {
             poll_seconds: 15,
             min_session_minutes: 3,
+            discard_short_sessions: true,
+            recovered_min_session_minutes: None,
+            grace_seconds: DEFAULT_GRACE_PERIOD.as_secs(),
+            merge_gap_minutes: 0,
+            idle_timeout_minutes: 0,
+            auto_reset_stale_active_minutes: 720,
+            process_tokens: default_process_tokens(),
+            process_match_mode: MatchMode::Contains,
+            detect_window_title: false,
             refresh_seconds: 5,
+            notify_on_session_start: false,
+            notify_on_session_end: false,
+            daily_goal_minutes: 0,
+            milestone_hours: vec![100, 500, 1000],
+            announced_milestones: Vec::new(),
+            best_streak_days: 0,
+            best_streak_end_date: None,
+            patches: Vec::new(),
             run_on_login: false,
+            startup_method: StartupMethod::default(),
+            startup_delay_seconds: 0,
+            has_onboarded: true,
+            write_status_file: false,
+            write_event_log: false,
+            enable_local_api: false,
+            local_api_port: DEFAULT_LOCAL_API_PORT,
+            tray_icon_color: [0x42, 0x7A, 0xC4],
+            tray_debug_logging: false,
+            tracking_paused: false,
+            theme: Theme::default(),
             show_daily_chart: true,
             show_weekly_chart: true,
+            show_hour_chart: true,
+            show_weekday_chart: true,
+            show_duration_chart: true,
+            include_active_session: true,
+            analytics_utc_offset_minutes: None,
+            share_include_username: true,
             sync_leaderboard: true,
             leaderboard_username: String::new(),
+            leaderboard_anonymize: false,
             leaderboard_endpoint: String::new(),
+            leaderboard_retries: DEFAULT_SUBMIT_RETRIES,
+            leaderboard_local_fallback: true,
         }
     }
 }
@@ -37,18 +170,77 @@ impl AppSettings {
     pub fn sanitize(&mut self) {
         self.poll_seconds = self.poll_seconds.clamp(1, 3600);
         self.min_session_minutes = self.min_session_minutes.clamp(1, 1440);
+        self.recovered_min_session_minutes = self
+            .recovered_min_session_minutes
+            .map(|minutes| minutes.min(1440));
+        self.grace_seconds = self.grace_seconds.min(600);
+        self.merge_gap_minutes = self.merge_gap_minutes.min(240);
+        self.idle_timeout_minutes = self.idle_timeout_minutes.min(1440);
+        self.auto_reset_stale_active_minutes = self.auto_reset_stale_active_minutes.min(10080);
+        self.process_tokens = normalize_process_tokens(std::mem::take(&mut self.process_tokens));
+        if self.process_tokens.is_empty() {
+            self.process_tokens = default_process_tokens();
+        }
         self.refresh_seconds = self.refresh_seconds.clamp(1, 60);
-        if !self.show_daily_chart && !self.show_weekly_chart {
-            self.show_daily_chart = true;
+        self.daily_goal_minutes = self.daily_goal_minutes.min(1440);
+        self.milestone_hours
+            .retain(|hours| (1..=100_000).contains(hours));
+        self.milestone_hours.sort_unstable();
+        self.milestone_hours.dedup();
+        self.announced_milestones.sort_unstable();
+        self.announced_milestones.dedup();
+        for patch in &mut self.patches {
+            patch.label = patch.label.trim().to_string();
         }
-        self.leaderboard_username = self.leaderboard_username.trim().to_string();
-        if self.leaderboard_username.len() > 32 {
-            self.leaderboard_username.truncate(32);
+        self.patches.retain(|patch| !patch.label.is_empty());
+        self.patches.sort_by_key(|patch| patch.start);
+        self.patches.dedup_by_key(|patch| patch.start);
+        self.local_api_port = self.local_api_port.max(1024);
+        self.startup_delay_seconds = self.startup_delay_seconds.min(MAX_STARTUP_DELAY_SECONDS);
+        if !self.show_daily_chart
+            && !self.show_weekly_chart
+            && !self.show_hour_chart
+            && !self.show_weekday_chart
+            && !self.show_duration_chart
+        {
+            self.show_daily_chart = true;
         }
+        self.leaderboard_username = sanitize_username(&self.leaderboard_username);
         self.leaderboard_endpoint = self.leaderboard_endpoint.trim().to_string();
         if self.leaderboard_endpoint.len() > 2048 {
             self.leaderboard_endpoint.truncate(2048);
         }
+        self.leaderboard_retries = self.leaderboard_retries.min(10);
+        self.analytics_utc_offset_minutes = self
+            .analytics_utc_offset_minutes
+            .map(|minutes| minutes.clamp(-MAX_UTC_OFFSET_MINUTES, MAX_UTC_OFFSET_MINUTES));
+    }
+
+    /// `analytics_utc_offset_minutes` as a chrono offset.
+    pub fn analytics_utc_offset(&self) -> Option<FixedOffset> {
+        self.analytics_utc_offset_minutes
+            .and_then(|minutes| FixedOffset::east_opt(minutes * 60))
+    }
+
+    /// Keeps a streak of `days` ending on `end` as the best one when it is longer. Returns true
+    /// when the stored best changed.
+    pub fn record_streak(&mut self, days: u32, end: NaiveDate) -> bool {
+        if days <= self.best_streak_days {
+            return false;
+        }
+        self.best_streak_days = days;
+        self.best_streak_end_date = Some(end);
+        true
+    }
+
+    /// Milestones (in hours) reached by `total_minutes` that have not been announced yet.
+    pub fn unannounced_milestones(&self, total_minutes: f64) -> Vec<u64> {
+        self.milestone_hours
+            .iter()
+            .copied()
+            .filter(|hours| total_minutes >= (*hours * 60) as f64)
+            .filter(|hours| !self.announced_milestones.contains(hours))
+            .collect()
     }
 }
 
@@ -65,7 +257,10 @@ impl SettingsStore {
 
     pub fn load(&self) -> Result<AppSettings> {
         if !self.path.exists() {
-            return Ok(AppSettings::default());
+            return Ok(AppSettings {
+                has_onboarded: false,
+                ..AppSettings::default()
+            });
         }
         let raw = fs::read_to_string(&self.path)
             .with_context(|| format!("Failed to read {}", self.path.display()))?;
@@ -79,7 +274,6 @@ impl SettingsStore {
         let mut normalized = settings.clone();
         normalized.sanitize();
         let payload = serde_json::to_string_pretty(&normalized)?;
-        fs::write(&self.path, payload)
-            .with_context(|| format!("Failed to write {}", self.path.display()))
+        write_atomic(&self.path, payload)
     }
 }
@@ -1,8 +1,12 @@
+use serde::{Deserialize, Serialize};
+
 #[cfg(windows)]
 use anyhow::{Context, Result};
 #[cfg(windows)]
 use std::path::Path;
 #[cfg(windows)]
+use std::process::Command;
+#[cfg(windows)]
 use winreg::RegKey;
 #[cfg(windows)]
 use winreg::enums::{HKEY_CURRENT_USER, KEY_ALL_ACCESS, KEY_READ};
@@ -12,6 +16,119 @@ const RUN_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Run";
 #[cfg(windows)]
 const VALUE_NAME: &str = "StarCitizenPlaytime";
 
+/// Name of the scheduled task used by [`StartupMethod::TaskScheduler`].
+pub const TASK_NAME: &str = "StarCitizenPlaytime";
+
+/// How the app is launched at login. The Run key is the default (on macOS and Linux it stands
+/// for the LaunchAgent or autostart entry); Task Scheduler supports a delayed start so the
+/// tracker does not compete with everything else launched at login.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupMethod {
+    #[default]
+    RunKey,
+    TaskScheduler,
+}
+
+/// Registers `executable` with `method`. `delay_seconds` only applies to Task Scheduler.
+pub fn install_with(
+    method: StartupMethod,
+    executable: &std::path::Path,
+    args: &str,
+    delay_seconds: u32,
+) -> anyhow::Result<()> {
+    match method {
+        StartupMethod::RunKey => install(executable, args),
+        StartupMethod::TaskScheduler => install_task(executable, args, delay_seconds),
+    }
+}
+
+pub fn uninstall_with(method: StartupMethod) -> anyhow::Result<()> {
+    match method {
+        StartupMethod::RunKey => uninstall(),
+        StartupMethod::TaskScheduler => uninstall_task(),
+    }
+}
+
+pub fn is_installed_with(method: StartupMethod) -> anyhow::Result<bool> {
+    match method {
+        StartupMethod::RunKey => is_installed(),
+        StartupMethod::TaskScheduler => is_task_installed(),
+    }
+}
+
+/// Arguments passed to `schtasks` to create the logon task, exposed for `--dry-run`.
+pub fn task_create_args(command: &str, delay_seconds: u32) -> Vec<String> {
+    let mut args = vec![
+        "/Create".to_string(),
+        "/TN".to_string(),
+        TASK_NAME.to_string(),
+        "/TR".to_string(),
+        command.to_string(),
+        "/SC".to_string(),
+        "ONLOGON".to_string(),
+        "/RL".to_string(),
+        "LIMITED".to_string(),
+        "/F".to_string(),
+    ];
+    if delay_seconds > 0 {
+        // schtasks expects mmmm:ss.
+        args.push("/DELAY".to_string());
+        args.push(format!(
+            "{:04}:{:02}",
+            delay_seconds / 60,
+            delay_seconds % 60
+        ));
+    }
+    args
+}
+
+/// Registry location of the auto-start entry, as shown to users.
+pub const RUN_ENTRY_DISPLAY: &str =
+    r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run\StarCitizenPlaytime";
+
+/// Where the default method registers the app on this platform, as shown to users.
+pub fn entry_location() -> String {
+    #[cfg(target_os = "macos")]
+    if let Ok(path) = launch_agent_path() {
+        return path.display().to_string();
+    }
+    #[cfg(target_os = "linux")]
+    if let Ok(path) = autostart_path() {
+        return path.display().to_string();
+    }
+    RUN_ENTRY_DISPLAY.to_string()
+}
+
+/// The command line written to the Run entry: the quoted executable path followed by `args`.
+pub fn startup_command(executable: &std::path::Path, args: &str) -> String {
+    let mut command = format!("\"{}\"", executable.display());
+    if !args.is_empty() {
+        command.push(' ');
+        command.push_str(args);
+    }
+    command
+}
+
+/// Splits a Run entry command into the executable path and its arguments; the reverse of
+/// [`startup_command`]. Unquoted paths end at the first space.
+pub fn split_command(command: &str) -> (std::path::PathBuf, &str) {
+    let command = command.trim();
+    let (exe, args) = match command.strip_prefix('"') {
+        Some(rest) => rest.split_once('"').unwrap_or((rest, "")),
+        None => command.split_once(' ').unwrap_or((command, "")),
+    };
+    (std::path::PathBuf::from(exe), args.trim())
+}
+
+/// Whether a registered command launches `executable`. Windows paths are case-insensitive.
+pub fn command_targets(command: &str, executable: &std::path::Path) -> bool {
+    let (registered, _) = split_command(command);
+    registered
+        .to_string_lossy()
+        .eq_ignore_ascii_case(&executable.to_string_lossy())
+}
+
 #[cfg(windows)]
 pub fn install(executable: &Path, args: &str) -> Result<()> {
     if !executable.exists() {
@@ -21,11 +138,7 @@ pub fn install(executable: &Path, args: &str) -> Result<()> {
     let key = hkcu
         .open_subkey_with_flags(RUN_KEY, KEY_ALL_ACCESS)
         .context("Failed to open HKCU Run registry key")?;
-    let mut command = format!("\"{}\"", executable.display());
-    if !args.is_empty() {
-        command.push(' ');
-        command.push_str(args);
-    }
+    let command = startup_command(executable, args);
     key.set_value(VALUE_NAME, &command)
         .context("Failed to set Run entry")?;
     println!("Registered auto-start entry at login.");
@@ -50,28 +163,396 @@ pub fn uninstall() -> Result<()> {
 
 #[cfg(windows)]
 pub fn is_installed() -> Result<bool> {
+    Ok(installed_command()?.is_some())
+}
+
+/// The command line currently registered in the Run entry, if any.
+#[cfg(windows)]
+pub fn installed_command() -> Result<Option<String>> {
     let hkcu = RegKey::predef(HKEY_CURRENT_USER);
     let key = hkcu
         .open_subkey_with_flags(RUN_KEY, KEY_READ)
         .context("Failed to open HKCU Run registry key")?;
     match key.get_value::<String, _>(VALUE_NAME) {
-        Ok(_) => Ok(true),
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Ok(command) => Ok(Some(command)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
         Err(e) => Err(e).context("Failed to read Run entry"),
     }
 }
 
+#[cfg(windows)]
+fn install_task(executable: &Path, args: &str, delay_seconds: u32) -> Result<()> {
+    if !executable.exists() {
+        anyhow::bail!("Executable {} does not exist", executable.display());
+    }
+    let command = startup_command(executable, args);
+    run_schtasks(&task_create_args(&command, delay_seconds))
+        .context("Failed to create scheduled task")?;
+    println!("Registered scheduled task {TASK_NAME} at login.");
+    Ok(())
+}
+
+#[cfg(windows)]
+fn uninstall_task() -> Result<()> {
+    if !is_task_installed()? {
+        println!("Scheduled task was not present.");
+        return Ok(());
+    }
+    run_schtasks(&["/Delete", "/TN", TASK_NAME, "/F"])
+        .context("Failed to delete scheduled task")?;
+    println!("Removed scheduled task.");
+    Ok(())
+}
+
+#[cfg(windows)]
+fn is_task_installed() -> Result<bool> {
+    let status = schtasks()
+        .args(["/Query", "/TN", TASK_NAME])
+        .output()
+        .context("Failed to run schtasks")?
+        .status;
+    Ok(status.success())
+}
+
+#[cfg(windows)]
+fn schtasks() -> Command {
+    use std::os::windows::process::CommandExt;
+    // Keep a console window from flashing up when called from the dashboard.
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    let mut command = Command::new("schtasks");
+    command.creation_flags(CREATE_NO_WINDOW);
+    command
+}
+
+#[cfg(windows)]
+fn run_schtasks<S: AsRef<std::ffi::OsStr>>(args: &[S]) -> Result<()> {
+    let output = schtasks()
+        .args(args)
+        .output()
+        .context("Failed to run schtasks")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "schtasks exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
 #[cfg(not(windows))]
-pub fn install(_executable: &std::path::Path, _args: &str) -> anyhow::Result<()> {
-    anyhow::bail!("Startup registration is only available on Windows.")
+fn install_task(_executable: &std::path::Path, _args: &str, _delay: u32) -> anyhow::Result<()> {
+    anyhow::bail!("Task Scheduler is only available on Windows.")
 }
 
 #[cfg(not(windows))]
-pub fn uninstall() -> anyhow::Result<()> {
-    anyhow::bail!("Startup registration is only available on Windows.")
+fn uninstall_task() -> anyhow::Result<()> {
+    anyhow::bail!("Task Scheduler is only available on Windows.")
 }
 
 #[cfg(not(windows))]
+fn is_task_installed() -> anyhow::Result<bool> {
+    Ok(false)
+}
+
+/// Identifier of the macOS LaunchAgent; also the plist file name.
+#[cfg(target_os = "macos")]
+const LAUNCH_AGENT_LABEL: &str = "com.sudo-spectral.star-citizen-playtime";
+
+#[cfg(target_os = "macos")]
+fn launch_agent_path() -> anyhow::Result<std::path::PathBuf> {
+    let dirs = directories::BaseDirs::new()
+        .ok_or_else(|| anyhow::anyhow!("Unable to determine the home directory"))?;
+    Ok(dirs
+        .home_dir()
+        .join("Library/LaunchAgents")
+        .join(format!("{LAUNCH_AGENT_LABEL}.plist")))
+}
+
+/// Writes a LaunchAgent that starts `executable` at login and loads it with `launchctl`.
+/// `args` is split on whitespace, so arguments cannot contain spaces.
+#[cfg(target_os = "macos")]
+pub fn install(executable: &std::path::Path, args: &str) -> anyhow::Result<()> {
+    use anyhow::Context;
+
+    if !executable.exists() {
+        anyhow::bail!("Executable {} does not exist", executable.display());
+    }
+    let path = launch_agent_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    if path.exists() {
+        // Reloading picks up a changed executable path or arguments.
+        launchctl(&["unload", "-w"], &path).ok();
+    }
+    let program_arguments = std::iter::once(executable.to_string_lossy().into_owned())
+        .chain(args.split_whitespace().map(str::to_string))
+        .map(|arg| format!("        <string>{}</string>\n", xml_escape(&arg)))
+        .collect::<String>();
+    let plist = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{LAUNCH_AGENT_LABEL}</string>
+    <key>ProgramArguments</key>
+    <array>
+{program_arguments}    </array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#
+    );
+    crate::storage::write_atomic(&path, plist)?;
+    launchctl(&["load", "-w"], &path).context("Failed to load LaunchAgent")?;
+    println!("Registered LaunchAgent at {}.", path.display());
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+pub fn uninstall() -> anyhow::Result<()> {
+    use anyhow::Context;
+
+    let path = launch_agent_path()?;
+    if !path.exists() {
+        println!("LaunchAgent was not present.");
+        return Ok(());
+    }
+    // The agent may already be unloaded; removing the plist is what matters.
+    launchctl(&["unload", "-w"], &path).ok();
+    std::fs::remove_file(&path).with_context(|| format!("Failed to remove {}", path.display()))?;
+    println!("Removed LaunchAgent.");
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+pub fn is_installed() -> anyhow::Result<bool> {
+    Ok(launch_agent_path()?.exists())
+}
+
+#[cfg(target_os = "macos")]
+fn launchctl(args: &[&str], plist: &std::path::Path) -> anyhow::Result<()> {
+    let output = std::process::Command::new("launchctl")
+        .args(args)
+        .arg(plist)
+        .output()
+        .map_err(|err| anyhow::anyhow!("Failed to run launchctl: {err}"))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "launchctl exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// The LaunchAgent's `ProgramArguments` joined into one command line, if it is registered.
+#[cfg(target_os = "macos")]
+pub fn installed_command() -> anyhow::Result<Option<String>> {
+    let path = launch_agent_path()?;
+    match std::fs::read_to_string(&path) {
+        Ok(raw) => Ok(program_arguments(&raw).map(|args| {
+            args.iter()
+                .map(|arg| {
+                    if arg.contains(char::is_whitespace) {
+                        format!("\"{arg}\"")
+                    } else {
+                        arg.clone()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(anyhow::anyhow!("Failed to read {}: {e}", path.display())),
+    }
+}
+
+/// The `<string>` entries of the `ProgramArguments` array in a plist written by `install`.
+/// Not a general plist parser: it expects the array to follow its key directly.
+#[cfg(any(target_os = "macos", test))]
+fn program_arguments(plist: &str) -> Option<Vec<String>> {
+    let after_key = &plist[plist.find("<key>ProgramArguments</key>")?..];
+    let array = &after_key[after_key.find("<array>")? + "<array>".len()..];
+    let array = &array[..array.find("</array>")?];
+    let mut args = Vec::new();
+    let mut rest = array;
+    while let Some(start) = rest.find("<string>") {
+        rest = &rest[start + "<string>".len()..];
+        let end = rest.find("</string>")?;
+        args.push(xml_unescape(&rest[..end]));
+        rest = &rest[end + "</string>".len()..];
+    }
+    Some(args)
+}
+
+#[cfg(any(target_os = "macos", test))]
+fn xml_unescape(value: &str) -> String {
+    value
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&gt;", ">")
+        .replace("&lt;", "<")
+        .replace("&amp;", "&")
+}
+
+#[cfg(target_os = "macos")]
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// XDG autostart entry, started by the desktop session at login.
+#[cfg(target_os = "linux")]
+const AUTOSTART_FILE: &str = "star-citizen-playtime.desktop";
+
+#[cfg(target_os = "linux")]
+fn autostart_path() -> anyhow::Result<std::path::PathBuf> {
+    let dirs = directories::BaseDirs::new()
+        .ok_or_else(|| anyhow::anyhow!("Unable to determine the config directory"))?;
+    Ok(dirs.config_dir().join("autostart").join(AUTOSTART_FILE))
+}
+
+/// Writes an XDG autostart `.desktop` file that runs `executable` with `args` at login.
+#[cfg(target_os = "linux")]
+pub fn install(executable: &std::path::Path, args: &str) -> anyhow::Result<()> {
+    use anyhow::Context;
+
+    if !executable.exists() {
+        anyhow::bail!("Executable {} does not exist", executable.display());
+    }
+    let path = autostart_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let mut exec = desktop_quote(&executable.to_string_lossy());
+    if !args.is_empty() {
+        exec.push(' ');
+        exec.push_str(args);
+    }
+    let entry = format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=Star Citizen Playtime\n\
+         Comment=Track Star Citizen playtime\n\
+         Exec={exec}\n\
+         Terminal=false\n\
+         X-GNOME-Autostart-enabled=true\n"
+    );
+    crate::storage::write_atomic(&path, entry)?;
+    println!("Registered autostart entry at {}.", path.display());
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+pub fn uninstall() -> anyhow::Result<()> {
+    use anyhow::Context;
+
+    let path = autostart_path()?;
+    match std::fs::remove_file(&path) {
+        Ok(()) => println!("Removed autostart entry."),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            println!("Autostart entry was not present.");
+        }
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to remove {}", path.display()));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+pub fn is_installed() -> anyhow::Result<bool> {
+    Ok(autostart_path()?.exists())
+}
+
+/// The `Exec=` line of the autostart entry, if present.
+#[cfg(target_os = "linux")]
+pub fn installed_command() -> anyhow::Result<Option<String>> {
+    let path = autostart_path()?;
+    match std::fs::read_to_string(&path) {
+        Ok(raw) => Ok(raw
+            .lines()
+            .find_map(|line| line.strip_prefix("Exec="))
+            .map(str::to_string)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(anyhow::anyhow!("Failed to read {}: {e}", path.display())),
+    }
+}
+
+/// Quotes a path for an `Exec=` key as the desktop entry spec requires.
+#[cfg(target_os = "linux")]
+fn desktop_quote(value: &str) -> String {
+    let mut quoted = String::from("\"");
+    for ch in value.chars() {
+        if matches!(ch, '"' | '`' | '$' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(ch);
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+pub fn install(_executable: &std::path::Path, _args: &str) -> anyhow::Result<()> {
+    anyhow::bail!("Startup registration is not available on this platform.")
+}
+
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+pub fn uninstall() -> anyhow::Result<()> {
+    anyhow::bail!("Startup registration is not available on this platform.")
+}
+
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
 pub fn is_installed() -> anyhow::Result<bool> {
     Ok(false)
 }
+
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+pub fn installed_command() -> anyhow::Result<Option<String>> {
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn program_arguments_reads_the_launch_agent_array() {
+        let plist = r#"<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>com.sudo-spectral.star-citizen-playtime</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>/Applications/SC Playtime/star_citizen_playtime</string>
+        <string>run</string>
+        <string>--note=a&amp;b</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#;
+        assert_eq!(
+            program_arguments(plist).unwrap(),
+            [
+                "/Applications/SC Playtime/star_citizen_playtime",
+                "run",
+                "--note=a&b"
+            ]
+        );
+        assert_eq!(program_arguments("<plist></plist>"), None);
+    }
+}
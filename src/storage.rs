@@ -1,11 +1,16 @@
 use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
     fs,
     io::Write,
     path::{Path, PathBuf},
+    time::SystemTime,
 };
 
-use anyhow::{Context, Result};
-use chrono::{DateTime, Datelike, Duration, Local, NaiveDate};
+use anyhow::{Context, Result, bail};
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime,
+    TimeZone, Timelike, Utc,
+};
 use directories::BaseDirs;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -18,6 +23,11 @@ pub struct Session {
     pub duration_minutes: f64,
     #[serde(default)]
     pub note: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Name of the tracked game process; `None` for sessions recorded before multi-game support.
+    #[serde(default)]
+    pub game: Option<String>,
 }
 
 impl Session {
@@ -30,6 +40,8 @@ impl Session {
             end,
             duration_minutes,
             note,
+            tags: Vec::new(),
+            game: None,
         }
     }
 }
@@ -38,6 +50,8 @@ impl Session {
 pub struct ActiveSession {
     pub start: DateTime<Local>,
     pub last_seen: DateTime<Local>,
+    #[serde(default)]
+    pub game: Option<String>,
 }
 
 impl ActiveSession {
@@ -45,16 +59,90 @@ impl ActiveSession {
         Self {
             start,
             last_seen: start,
+            game: None,
         }
     }
 }
 
+/// A game patch and the local date it went live. A patch covers every day up to the next
+/// patch's start.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PatchRelease {
+    pub label: String,
+    pub start: NaiveDate,
+}
+
+/// Extra columns and a date filter for `export_csv_with`. The default keeps the original
+/// `id,start,end,duration_minutes,note` layout. Extra columns always follow `note`, in the
+/// order below, so files stay importable with `import_csv`.
+#[derive(Debug, Clone, Default)]
+pub struct CsvExportOptions {
+    /// Add a `date` column with the local start date (`YYYY-MM-DD`).
+    pub local_date: bool,
+    /// Add a `weekday` column (`Mon`..`Sun`) for the local start date.
+    pub weekday: bool,
+    /// Add a `duration_hours` column.
+    pub hours: bool,
+    /// Only export sessions started on or after this date.
+    pub since: Option<NaiveDate>,
+    /// Only export sessions started on or before this date.
+    pub until: Option<NaiveDate>,
+}
+
+/// Outcome of merging sessions from an external file into the store.
+#[derive(Debug, Default)]
+pub struct ImportSummary {
+    pub added: usize,
+    pub skipped: usize,
+    pub errors: Vec<String>,
+}
+
+/// Outcome of repairing a malformed `sessions.json`.
+#[derive(Debug)]
+pub struct RecoveryReport {
+    pub salvaged: usize,
+    pub dropped: usize,
+    pub backup_path: PathBuf,
+    pub error: String,
+}
+
+impl RecoveryReport {
+    pub fn describe(&self) -> String {
+        format!(
+            "sessions.json was corrupt ({}); salvaged {} session{}, dropped {} unreadable entr{}. Original saved as {}.",
+            self.error,
+            self.salvaged,
+            if self.salvaged == 1 { "" } else { "s" },
+            self.dropped,
+            if self.dropped == 1 { "y" } else { "ies" },
+            self.backup_path.display()
+        )
+    }
+}
+
+/// Files copied by `SessionStore::backup_to`, relative to the data directory.
+const BACKUP_FILES: [&str; 4] = [
+    "sessions.json",
+    "active_session.json",
+    "settings.json",
+    "leaderboard.json",
+];
+
+/// Exclusive claim on a data directory's active-session marker, held by a running monitor.
+/// The OS releases it when this is dropped or the process exits, so a crash never leaves a
+/// stale lock behind.
+#[derive(Debug)]
+pub struct MonitorLock {
+    _file: fs::File,
+}
+
 pub struct SessionStore {
     data_dir: PathBuf,
     sessions_file: PathBuf,
     active_file: PathBuf,
 }
 
+#[derive(Debug, Clone, Serialize)]
 pub struct Analytics {
     pub total_sessions: usize,
     pub total_minutes: f64,
@@ -62,20 +150,120 @@ pub struct Analytics {
     pub median_session_minutes: f64,
     pub minutes_last_7: f64,
     pub minutes_last_30: f64,
+    /// Minutes in the 7 days before the `minutes_last_7` window, for week-over-week comparison.
+    pub minutes_prev_7: f64,
+    /// Minutes in the 30 days before the `minutes_last_30` window.
+    pub minutes_prev_30: f64,
     pub top_days: Vec<(NaiveDate, f64)>,
     pub recent_sessions: Vec<Session>,
+    pub longest_sessions: Vec<Session>,
     pub recent_daily: Vec<(NaiveDate, f64)>,
     pub recent_weekly: Vec<((i32, u32), f64)>,
+    /// Average session length in minutes for the last 8 ISO weeks in which a session started,
+    /// most recent first. Sessions count towards the week they started in.
+    pub avg_session_by_week: Vec<((i32, u32), f64)>,
+    pub tag_totals: Vec<(String, f64)>,
+    pub game_totals: Vec<(String, f64)>,
+    /// Minutes played in each local hour of the day (index 0 = midnight to 1am), with each
+    /// session spread across the hours it spans.
+    pub play_by_hour: [f64; 24],
+    /// Minutes played per weekday, with sessions split at midnight. Monday first to match ISO
+    /// weeks.
+    pub weekday_totals: [f64; 7],
+    /// Number of sessions per length bucket (see [`DURATION_BUCKETS`]), shortest first.
+    pub duration_histogram: Vec<(String, usize)>,
     pub first_day: Option<NaiveDate>,
     pub last_day: Option<NaiveDate>,
+    /// Playtime per calendar day, kept so arbitrary windows can be answered without
+    /// re-scanning sessions.
+    #[serde(skip)]
+    pub daily_totals: BTreeMap<NaiveDate, f64>,
+    /// Start, end and minutes of every session, so rolling windows measure the exact overlap.
+    #[serde(skip)]
+    pub spans: Vec<(DateTime<Local>, DateTime<Local>, f64)>,
+    /// Minutes of the session in progress folded in by `add_active_session`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_minutes: Option<f64>,
+    /// Offset the day, weekday and hour buckets were taken at; `None` for system local time.
+    #[serde(skip)]
+    pub utc_offset: Option<FixedOffset>,
 }
 
+impl Analytics {
+    /// Minutes played in the `days` × 24 hours up to now, counting only the part of each
+    /// session inside that window.
+    pub fn minutes_in_last(&self, days: u32) -> f64 {
+        minutes_in_window(&self.spans, Local::now(), days)
+    }
+
+    /// Folds the session in progress into `total_minutes`, the days it spans (`daily_totals`,
+    /// `recent_daily`) and the rolling windows, recording its length in `active_minutes`.
+    /// Session counts, averages and the other breakdowns still cover saved sessions only.
+    pub fn add_active_session(&mut self, active: &ActiveSession) {
+        let minutes = active_session_minutes(active);
+        self.total_minutes += minutes;
+        match self.utc_offset {
+            Some(offset) => spread_over_days(
+                &mut self.daily_totals,
+                &offset,
+                active.start,
+                active.last_seen,
+                minutes,
+            ),
+            None => spread_over_days(
+                &mut self.daily_totals,
+                &Local,
+                active.start,
+                active.last_seen,
+                minutes,
+            ),
+        }
+        self.recent_daily = recent_days(&self.daily_totals);
+        self.first_day = self.daily_totals.keys().next().copied();
+        self.last_day = self.daily_totals.keys().next_back().copied();
+        self.spans.push((active.start, active.last_seen, minutes));
+        self.minutes_last_7 = self.minutes_in_last(7);
+        self.minutes_last_30 = self.minutes_in_last(30);
+        let now = Local::now();
+        self.minutes_prev_7 = minutes_in_previous_window(&self.spans, now, 7);
+        self.minutes_prev_30 = minutes_in_previous_window(&self.spans, now, 30);
+        *self.active_minutes.get_or_insert(0.0) += minutes;
+    }
+
+    /// Minutes played from the start of the UTC calendar month containing `now` up to `now`,
+    /// counting only the part of each session inside it. The leaderboard server ranks months
+    /// in UTC, so this ignores the local time zone.
+    pub fn minutes_in_utc_month(&self, now: DateTime<Utc>) -> f64 {
+        let month_start = utc_month_start(now);
+        minutes_between(
+            &self.spans,
+            month_start.with_timezone(&Local),
+            now.with_timezone(&Local),
+        )
+    }
+}
+
+/// Environment variable that overrides the platform data directory.
+pub const DATA_DIR_ENV: &str = "SC_PLAYTIME_DATA_DIR";
+
 impl SessionStore {
+    /// Opens the store in `SC_PLAYTIME_DATA_DIR` when set, otherwise in the platform
+    /// data-local directory.
     pub fn new() -> Result<Self> {
+        if let Some(dir) = std::env::var_os(DATA_DIR_ENV).filter(|dir| !dir.is_empty()) {
+            return Self::with_dir(dir);
+        }
         let base_dirs = BaseDirs::new().context("Unable to determine platform data directory")?;
         let mut data_dir = base_dirs.data_local_dir().to_path_buf();
         data_dir.push("StarCitizenPlaytime");
-        fs::create_dir_all(&data_dir).context("Failed to create data directory")?;
+        Self::with_dir(data_dir)
+    }
+
+    /// Opens the store in an explicit directory, creating it if needed.
+    pub fn with_dir(data_dir: impl Into<PathBuf>) -> Result<Self> {
+        let data_dir = data_dir.into();
+        fs::create_dir_all(&data_dir)
+            .with_context(|| format!("Failed to create data directory {}", data_dir.display()))?;
         let sessions_file = data_dir.join("sessions.json");
         let active_file = data_dir.join("active_session.json");
         Ok(Self {
@@ -86,22 +274,60 @@ impl SessionStore {
     }
 
     pub fn load_sessions(&self) -> Result<Vec<Session>> {
+        let (sessions, recovery) = self.load_sessions_with_recovery()?;
+        if let Some(report) = recovery {
+            eprintln!("{}", report.describe());
+        }
+        Ok(sessions)
+    }
+
+    /// Loads sessions, repairing a malformed `sessions.json` instead of failing: the bad file
+    /// is moved aside to `sessions.corrupt.<timestamp>.json`, every session object that still
+    /// parses is salvaged and written back, and a report of what happened is returned.
+    pub fn load_sessions_with_recovery(&self) -> Result<(Vec<Session>, Option<RecoveryReport>)> {
         if !self.sessions_file.exists() {
-            return Ok(vec![]);
+            return Ok((vec![], None));
         }
         let content = fs::read_to_string(&self.sessions_file)
             .with_context(|| format!("Failed to read {}", self.sessions_file.display()))?;
-        let sessions: Vec<Session> = serde_json::from_str(&content)
-            .with_context(|| format!("Failed to parse {}", self.sessions_file.display()))?;
-        Ok(sessions)
+        let parse_error = match serde_json::from_str::<Vec<Session>>(&content) {
+            Ok(sessions) => return Ok((sessions, None)),
+            Err(err) => err,
+        };
+
+        let (sessions, dropped) = salvage_sessions(&content);
+        let backup_path = self.data_dir.join(format!(
+            "sessions.corrupt.{}.json",
+            Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        fs::rename(&self.sessions_file, &backup_path).with_context(|| {
+            format!(
+                "Failed to move corrupt {} aside (parse error: {parse_error})",
+                self.sessions_file.display()
+            )
+        })?;
+        self.save_sessions(&sessions)?;
+        let report = RecoveryReport {
+            salvaged: sessions.len(),
+            dropped,
+            backup_path,
+            error: parse_error.to_string(),
+        };
+        Ok((sessions, Some(report)))
+    }
+
+    /// Modification time and size of `sessions.json`, or `None` when it does not exist yet.
+    /// Cheap enough to poll for changes before reloading.
+    pub fn sessions_stamp(&self) -> Option<(SystemTime, u64)> {
+        let metadata = fs::metadata(&self.sessions_file).ok()?;
+        Some((metadata.modified().ok()?, metadata.len()))
     }
 
     pub fn save_sessions(&self, sessions: &[Session]) -> Result<()> {
         let mut ordered = sessions.to_vec();
         ordered.sort_by_key(|s| s.start);
         let payload = serde_json::to_string_pretty(&ordered)?;
-        fs::write(&self.sessions_file, payload)
-            .with_context(|| format!("Failed to write {}", self.sessions_file.display()))
+        write_atomic(&self.sessions_file, payload)
     }
 
     pub fn append_session(&self, session: Session) -> Result<()> {
@@ -110,6 +336,210 @@ impl SessionStore {
         self.save_sessions(&sessions)
     }
 
+    /// Appends `session`, or folds it into the latest session that ended no more than
+    /// `max_gap` before it started and recorded the same game (or no game). The gap itself is
+    /// not counted as playtime. Returns the stored session and whether it was merged.
+    pub fn append_or_merge_session(
+        &self,
+        session: Session,
+        max_gap: Duration,
+    ) -> Result<(Session, bool)> {
+        let mut sessions = self.load_sessions()?;
+        let previous = sessions
+            .iter_mut()
+            .filter(|previous| previous.start <= session.start)
+            .max_by_key(|previous| previous.end)
+            .filter(|previous| {
+                session.start - previous.end <= max_gap
+                    && (previous.game.is_none()
+                        || session.game.is_none()
+                        || previous.game == session.game)
+            });
+        let Some(previous) = previous else {
+            sessions.push(session.clone());
+            self.save_sessions(&sessions)?;
+            return Ok((session, false));
+        };
+
+        // Overlapping time is only counted once.
+        let overlap = (previous.end.min(session.end) - session.start)
+            .num_seconds()
+            .max(0) as f64
+            / 60.0;
+        previous.duration_minutes += (session.duration_minutes - overlap).max(0.0);
+        previous.end = previous.end.max(session.end);
+        let note = session.note.trim();
+        if !note.is_empty() && !previous.note.split("; ").any(|existing| existing == note) {
+            if !previous.note.is_empty() {
+                previous.note.push_str("; ");
+            }
+            previous.note.push_str(note);
+        }
+        for tag in session.tags {
+            if !previous.tags.contains(&tag) {
+                previous.tags.push(tag);
+            }
+        }
+        if previous.game.is_none() {
+            previous.game = session.game;
+        }
+        let merged = previous.clone();
+        self.save_sessions(&sessions)?;
+        Ok((merged, true))
+    }
+
+    /// Replaces the note on the session with `id`. Returns false if no such session exists.
+    pub fn update_note(&self, id: Uuid, note: String) -> Result<bool> {
+        self.update_session(id, |session| session.note = note)
+    }
+
+    /// Replaces the tags on the session with `id`. Returns false if no such session exists.
+    pub fn update_tags(&self, id: Uuid, tags: Vec<String>) -> Result<bool> {
+        self.update_session(id, |session| session.tags = tags)
+    }
+
+    /// Removes the session with `id`. Returns false if no such session exists.
+    pub fn delete_session(&self, id: Uuid) -> Result<bool> {
+        let mut sessions = self.load_sessions()?;
+        let before = sessions.len();
+        sessions.retain(|session| session.id != id);
+        if sessions.len() == before {
+            return Ok(false);
+        }
+        self.save_sessions(&sessions)?;
+        Ok(true)
+    }
+
+    /// Appends a manually entered session. Rejects an end that is not after the start and,
+    /// unless `allow_overlap` is set, any overlap with sessions already recorded.
+    pub fn add_manual_session(
+        &self,
+        start: DateTime<Local>,
+        end: DateTime<Local>,
+        note: String,
+        allow_overlap: bool,
+    ) -> Result<Session> {
+        if end <= start {
+            bail!("End time must be after the start time");
+        }
+        let mut sessions = self.load_sessions()?;
+        if !allow_overlap
+            && let Some(existing) = sessions
+                .iter()
+                .find(|session| session.start < end && start < session.end)
+        {
+            bail!(
+                "Overlaps the session from {} to {}",
+                existing.start.format("%Y-%m-%d %H:%M"),
+                existing.end.format("%Y-%m-%d %H:%M")
+            );
+        }
+        let session = Session::new(start, end, note);
+        sessions.push(session.clone());
+        self.save_sessions(&sessions)?;
+        Ok(session)
+    }
+
+    /// Combines the sessions with `ids` into one spanning the earliest start to the latest end.
+    /// The duration is the sum of the originals so gaps between them are not counted, and the
+    /// note records the merged ids. Fails if fewer than two of the ids exist.
+    pub fn merge_sessions(&self, ids: &[Uuid]) -> Result<Session> {
+        let sessions = self.load_sessions()?;
+        let (mut selected, mut remaining): (Vec<Session>, Vec<Session>) = sessions
+            .into_iter()
+            .partition(|session| ids.contains(&session.id));
+        if selected.len() < 2 {
+            bail!("Select at least two existing sessions to merge");
+        }
+        selected.sort_by_key(|session| session.start);
+
+        let start = selected[0].start;
+        let end = selected
+            .iter()
+            .map(|session| session.end)
+            .max()
+            .unwrap_or(start);
+        let mut notes: Vec<&str> = Vec::new();
+        for session in &selected {
+            let note = session.note.trim();
+            if !note.is_empty() && !notes.contains(&note) {
+                notes.push(note);
+            }
+        }
+        let merged_ids = selected
+            .iter()
+            .map(|session| session.id.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let merged_note = format!("merged from {merged_ids}");
+        notes.push(&merged_note);
+
+        let mut merged = Session::new(start, end, notes.join("; "));
+        merged.duration_minutes = selected
+            .iter()
+            .map(|session| session.duration_minutes)
+            .sum();
+        for session in &selected {
+            for tag in &session.tags {
+                if !merged.tags.contains(tag) {
+                    merged.tags.push(tag.clone());
+                }
+            }
+        }
+        merged.game = selected.iter().find_map(|session| session.game.clone());
+
+        remaining.push(merged.clone());
+        self.save_sessions(&remaining)?;
+        Ok(merged)
+    }
+
+    fn update_session<F>(&self, id: Uuid, update: F) -> Result<bool>
+    where
+        F: FnOnce(&mut Session),
+    {
+        let mut sessions = self.load_sessions()?;
+        let Some(session) = sessions.iter_mut().find(|session| session.id == id) else {
+            return Ok(false);
+        };
+        update(session);
+        self.save_sessions(&sessions)?;
+        Ok(true)
+    }
+
+    /// Claims the data directory for one monitor so two trackers (e.g. the CLI `run` loop and
+    /// the dashboard) do not overwrite each other's active-session marker.
+    pub fn lock_monitor(&self) -> Result<MonitorLock> {
+        let path = self.data_dir.join("monitor.lock");
+        let mut file = fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(fs::TryLockError::WouldBlock) => {
+                let owner = fs::read_to_string(&path).unwrap_or_default();
+                let owner = match owner.trim() {
+                    "" => String::new(),
+                    pid => format!(" (PID {pid})"),
+                };
+                bail!(
+                    "Another playtime monitor{owner} is already tracking sessions in {}. Stop it (or close the other dashboard) before starting a new one.",
+                    self.data_dir.display()
+                );
+            }
+            Err(fs::TryLockError::Error(err)) => {
+                return Err(err).with_context(|| format!("Failed to lock {}", path.display()));
+            }
+        }
+        file.set_len(0)
+            .and_then(|()| write!(file, "{}", std::process::id()))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(MonitorLock { _file: file })
+    }
+
     pub fn load_active(&self) -> Result<Option<ActiveSession>> {
         if !self.active_file.exists() {
             return Ok(None);
@@ -123,8 +553,7 @@ impl SessionStore {
 
     pub fn save_active(&self, active: &ActiveSession) -> Result<()> {
         let payload = serde_json::to_string_pretty(active)?;
-        fs::write(&self.active_file, payload)
-            .with_context(|| format!("Failed to write {}", self.active_file.display()))
+        write_atomic(&self.active_file, payload)
     }
 
     pub fn clear_active(&self) -> Result<()> {
@@ -136,33 +565,386 @@ impl SessionStore {
     }
 
     pub fn export_csv(&self, path: &Path, sessions: &[Session]) -> Result<(usize, PathBuf)> {
+        self.export_csv_with(path, sessions, &CsvExportOptions::default())
+    }
+
+    /// Like `export_csv`, with optional computed columns and a start-date range.
+    pub fn export_csv_with(
+        &self,
+        path: &Path,
+        sessions: &[Session],
+        options: &CsvExportOptions,
+    ) -> Result<(usize, PathBuf)> {
+        let sessions = sessions_in_range(sessions, options.since, options.until, None);
         let mut out_path = path.to_path_buf();
         if out_path.extension().map(|ext| ext != "csv").unwrap_or(true) {
             out_path.set_extension("csv");
         }
         let mut file = fs::File::create(&out_path)
             .with_context(|| format!("Failed to create {}", out_path.display()))?;
-        writeln!(file, "id,start,end,duration_minutes,note")?;
-        for session in sessions {
-            let note = session.note.replace('"', "'");
-            writeln!(
-                file,
-                "{},{},{},{:.2},\"{}\"",
+        let mut header = String::from("id,start,end,duration_minutes,note");
+        if options.local_date {
+            header.push_str(",date");
+        }
+        if options.weekday {
+            header.push_str(",weekday");
+        }
+        if options.hours {
+            header.push_str(",duration_hours");
+        }
+        writeln!(file, "{header}")?;
+        for session in &sessions {
+            let mut row = format!(
+                "{},{},{},{:.2},{}",
                 session.id,
                 session.start.to_rfc3339(),
                 session.end.to_rfc3339(),
                 session.duration_minutes,
-                note
-            )?;
+                quote_csv_field(&session.note)
+            );
+            if options.local_date {
+                row.push_str(&format!(",{}", session.start.format("%Y-%m-%d")));
+            }
+            if options.weekday {
+                row.push_str(&format!(",{}", session.start.format("%a")));
+            }
+            if options.hours {
+                row.push_str(&format!(",{:.2}", session.duration_minutes / 60.0));
+            }
+            writeln!(file, "{row}")?;
+        }
+        Ok((sessions.len(), out_path))
+    }
+
+    pub fn export_json(&self, path: &Path, sessions: &[Session]) -> Result<(usize, PathBuf)> {
+        let mut out_path = path.to_path_buf();
+        if out_path
+            .extension()
+            .map(|ext| ext != "json")
+            .unwrap_or(true)
+        {
+            out_path.set_extension("json");
         }
+        let payload = serde_json::to_string_pretty(sessions)?;
+        fs::write(&out_path, payload)
+            .with_context(|| format!("Failed to write {}", out_path.display()))?;
         Ok((sessions.len(), out_path))
     }
 
+    /// Imports sessions from a CSV file in the format written by `export_csv`.
+    pub fn import_csv(&self, path: &Path) -> Result<ImportSummary> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let mut candidates = Vec::new();
+        let mut errors = Vec::new();
+        for (idx, record) in parse_csv_records(&content).into_iter().enumerate() {
+            if idx == 0 && record.first().map(|field| field.trim()) == Some("id") {
+                continue;
+            }
+            match parse_csv_session(&record) {
+                Ok(session) => candidates.push(session),
+                Err(err) => errors.push(format!("Row {}: {err}", idx + 1)),
+            }
+        }
+        self.merge_imported(candidates, errors)
+    }
+
+    /// Imports sessions from a JSON array in the format written by `export_json`.
+    pub fn import_json(&self, path: &Path) -> Result<ImportSummary> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let values: Vec<serde_json::Value> = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        let mut candidates = Vec::new();
+        let mut errors = Vec::new();
+        for (idx, value) in values.into_iter().enumerate() {
+            match serde_json::from_value::<Session>(value) {
+                Ok(session) => candidates.push(session),
+                Err(err) => errors.push(format!("Entry {}: {err}", idx + 1)),
+            }
+        }
+        self.merge_imported(candidates, errors)
+    }
+
+    fn merge_imported(
+        &self,
+        candidates: Vec<Session>,
+        errors: Vec<String>,
+    ) -> Result<ImportSummary> {
+        let mut sessions = self.load_sessions()?;
+        let mut known: HashSet<Uuid> = sessions.iter().map(|s| s.id).collect();
+        let mut summary = ImportSummary {
+            errors,
+            ..ImportSummary::default()
+        };
+        for session in candidates {
+            if known.insert(session.id) {
+                sessions.push(session);
+                summary.added += 1;
+            } else {
+                summary.skipped += 1;
+            }
+        }
+        if summary.added > 0 {
+            self.save_sessions(&sessions)?;
+        }
+        Ok(summary)
+    }
+
+    /// Copies the data files into a new timestamped folder under `dest` (defaults to
+    /// `<data dir>/backups`). Returns the folder and the number of files copied.
+    pub fn backup_to(&self, dest: Option<&Path>) -> Result<(PathBuf, usize)> {
+        let root = match dest {
+            Some(dest) => dest.to_path_buf(),
+            None => self.data_dir.join("backups"),
+        };
+        let folder = root.join(format!("backup-{}", Local::now().format("%Y%m%d-%H%M%S")));
+        fs::create_dir_all(&folder)
+            .with_context(|| format!("Failed to create {}", folder.display()))?;
+        let mut copied = 0;
+        for name in BACKUP_FILES {
+            let source = self.data_dir.join(name);
+            if !source.exists() {
+                continue;
+            }
+            fs::copy(&source, folder.join(name))
+                .with_context(|| format!("Failed to back up {}", source.display()))?;
+            copied += 1;
+        }
+        Ok((folder, copied))
+    }
+
     pub fn data_dir(&self) -> &Path {
         &self.data_dir
     }
 }
 
+fn parse_csv_session(record: &[String]) -> std::result::Result<Session, String> {
+    if record.len() < 4 {
+        return Err(format!(
+            "expected at least 4 columns, found {}",
+            record.len()
+        ));
+    }
+    let id = Uuid::parse_str(record[0].trim()).map_err(|err| format!("invalid id: {err}"))?;
+    let start = DateTime::parse_from_rfc3339(record[1].trim())
+        .map_err(|err| format!("invalid start: {err}"))?
+        .with_timezone(&Local);
+    let end = DateTime::parse_from_rfc3339(record[2].trim())
+        .map_err(|err| format!("invalid end: {err}"))?
+        .with_timezone(&Local);
+    let duration_minutes: f64 = record[3]
+        .trim()
+        .parse()
+        .map_err(|err| format!("invalid duration_minutes: {err}"))?;
+    if end < start {
+        return Err("end is before start".to_string());
+    }
+    Ok(Session {
+        id,
+        start,
+        end,
+        duration_minutes,
+        note: record.get(4).cloned().unwrap_or_default(),
+        tags: Vec::new(),
+        game: None,
+    })
+}
+
+/// Recovers every top-level `{ ... }` object in `content` that deserializes as a `Session`.
+/// Works on truncated or partially mangled arrays because objects are located by brace
+/// matching rather than by parsing the whole document. Returns the sessions and the number
+/// of objects that could not be read.
+fn salvage_sessions(content: &str) -> (Vec<Session>, usize) {
+    let mut sessions = Vec::new();
+    let mut dropped = 0;
+    let mut seen = HashSet::new();
+    let mut depth = 0usize;
+    let mut start = None;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (idx, ch) in content.char_indices() {
+        if in_string {
+            match ch {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match ch {
+            '"' => in_string = true,
+            '{' => {
+                if depth == 0 {
+                    start = Some(idx);
+                }
+                depth += 1;
+            }
+            '}' if depth > 0 => {
+                depth -= 1;
+                if depth == 0
+                    && let Some(begin) = start.take()
+                {
+                    match serde_json::from_str::<Session>(&content[begin..=idx]) {
+                        Ok(session) if seen.insert(session.id) => sessions.push(session),
+                        Ok(_) => {}
+                        Err(_) => dropped += 1,
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    if start.is_some() {
+        dropped += 1;
+    }
+    (sessions, dropped)
+}
+
+/// Quotes a field per RFC 4180 (embedded quotes doubled) so commas, quotes and newlines
+/// survive a round trip through `parse_csv_records`.
+fn quote_csv_field(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+/// Splits CSV content into records, honouring quoted fields with doubled quotes and
+/// embedded newlines. Blank lines are skipped.
+fn parse_csv_records(content: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if in_quotes {
+            match ch {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                _ => field.push(ch),
+            }
+            continue;
+        }
+        match ch {
+            '"' => in_quotes = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                if record.len() > 1 || !record[0].is_empty() {
+                    records.push(std::mem::take(&mut record));
+                } else {
+                    record.clear();
+                }
+            }
+            _ => field.push(ch),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+/// Writes `contents` to a temporary file next to `path`, flushes it to disk, then renames it
+/// over `path`. A crash mid-write leaves the previous file intact instead of truncating it.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    write_atomic_with(path, |file| Ok(file.write_all(contents.as_ref())?))
+}
+
+/// `write_atomic` with the temporary file filled in by `write`; the temporary file is removed
+/// and `path` left untouched when `write` fails.
+fn write_atomic_with(path: &Path, write: impl FnOnce(&mut fs::File) -> Result<()>) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .with_context(|| format!("Invalid file path {}", path.display()))?;
+    let mut tmp_name = std::ffi::OsString::from(".");
+    tmp_name.push(file_name);
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let result = (|| -> Result<()> {
+        let mut file = fs::File::create(&tmp_path)
+            .with_context(|| format!("Failed to create {}", tmp_path.display()))?;
+        write(&mut file).with_context(|| format!("Failed to write {}", tmp_path.display()))?;
+        file.sync_all()
+            .with_context(|| format!("Failed to flush {}", tmp_path.display()))?;
+        drop(file);
+        fs::rename(&tmp_path, path).with_context(|| format!("Failed to replace {}", path.display()))
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+/// Size at which append-only logs are rotated by `rotate_log`.
+pub const LOG_ROTATE_BYTES: u64 = 5 * 1024 * 1024;
+/// Rotated generations kept next to each log; `<name>.1` is the most recent.
+pub const LOG_ROTATE_KEEP: usize = 3;
+
+/// Once `path` has grown to `max_bytes`, renames it to `<path>.1` (shifting older generations up
+/// and dropping anything beyond `keep`) so the next append starts a fresh file. Does nothing when
+/// the log is missing or still below the threshold.
+pub fn rotate_log(path: &Path, max_bytes: u64, keep: usize) -> Result<()> {
+    match fs::metadata(path) {
+        Ok(meta) if meta.len() >= max_bytes => {}
+        _ => return Ok(()),
+    }
+    if keep == 0 {
+        return fs::remove_file(path)
+            .with_context(|| format!("Failed to remove {}", path.display()));
+    }
+    let generation = |index: usize| {
+        let mut name = path.as_os_str().to_owned();
+        name.push(format!(".{index}"));
+        PathBuf::from(name)
+    };
+    let _ = fs::remove_file(generation(keep));
+    for index in (1..keep).rev() {
+        let from = generation(index);
+        if from.exists() {
+            let to = generation(index + 1);
+            fs::rename(&from, &to)
+                .with_context(|| format!("Failed to rotate {}", from.display()))?;
+        }
+    }
+    fs::rename(path, generation(1)).with_context(|| format!("Failed to rotate {}", path.display()))
+}
+
+/// Game label used in analytics for sessions recorded before the game name was stored.
+/// Matches the process name of the Star Citizen client, which was the only tracked game.
+pub const DEFAULT_GAME_LABEL: &str = "StarCitizen";
+
+/// Label used in analytics for sessions without any tags.
+pub const UNTAGGED_LABEL: &str = "untagged";
+
+/// Session length buckets for the duration histogram: label and exclusive upper bound in
+/// minutes. The last bucket is open-ended.
+pub const DURATION_BUCKETS: [(&str, f64); 5] = [
+    ("<30m", 30.0),
+    ("30–60m", 60.0),
+    ("1–2h", 120.0),
+    ("2–4h", 240.0),
+    ("4h+", f64::INFINITY),
+];
+
+/// Parses a comma-separated tag list into trimmed, lowercase, de-duplicated tags.
+pub fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for raw in input.split(',') {
+        let tag = raw.trim().to_lowercase();
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
 pub fn format_duration(minutes: f64) -> String {
     if minutes <= 0.0 {
         return "0m".to_string();
@@ -177,14 +959,355 @@ pub fn format_duration(minutes: f64) -> String {
     }
 }
 
+/// Like `format_duration`, but keeps seconds for sessions under an hour so very short
+/// sessions read as `45s` or `2m 30s` instead of `0m`.
+pub fn format_duration_precise(minutes: f64) -> String {
+    let total_seconds = (minutes * 60.0).round().max(0.0) as i64;
+    if total_seconds >= 3600 {
+        return format_duration(total_seconds as f64 / 60.0);
+    }
+    match (total_seconds / 60, total_seconds % 60) {
+        (0, s) => format!("{s}s"),
+        (m, 0) => format!("{m}m"),
+        (m, s) => format!("{m}m {s}s"),
+    }
+}
+
+/// The session the monitor is currently tracking, if any. Reads the persisted marker, so it
+/// works from any process without talking to a running monitor.
+pub fn read_active_status(store: &SessionStore) -> Result<Option<ActiveSession>> {
+    store.load_active()
+}
+
 pub fn active_session_minutes(active: &ActiveSession) -> f64 {
     let duration = active.last_seen - active.start;
     duration.num_seconds().max(0) as f64 / 60.0
 }
 
+/// Returns true when the marker was last refreshed more than `threshold_minutes` before `now`.
+pub fn active_session_is_stale(
+    active: &ActiveSession,
+    now: DateTime<Local>,
+    threshold_minutes: u64,
+) -> bool {
+    let idle = now - active.last_seen;
+    idle.num_seconds() > (threshold_minutes as i64).saturating_mul(60)
+}
+
+/// Parses a local timestamp given as RFC 3339 or `YYYY-MM-DD HH:MM`.
+pub fn parse_local_datetime(input: &str) -> Result<DateTime<Local>> {
+    let input = input.trim();
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(input) {
+        return Ok(parsed.with_timezone(&Local));
+    }
+    let naive = NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M")
+        .with_context(|| format!("Invalid time '{input}'; use YYYY-MM-DD HH:MM or RFC 3339"))?;
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .with_context(|| format!("'{input}' does not exist in the local time zone"))
+}
+
+/// True when the sessions with `ids` sit next to each other in start order, i.e. no other
+/// session falls between them.
+pub fn sessions_are_adjacent(sessions: &[Session], ids: &[Uuid]) -> bool {
+    let mut ordered = sessions.iter().collect::<Vec<_>>();
+    ordered.sort_by_key(|session| session.start);
+    let positions = ordered
+        .iter()
+        .enumerate()
+        .filter(|(_, session)| ids.contains(&session.id))
+        .map(|(idx, _)| idx)
+        .collect::<Vec<_>>();
+    positions.windows(2).all(|pair| pair[1] == pair[0] + 1)
+}
+
+/// The calendar date of `time` at `utc_offset`, or in the system time zone when `None`.
+pub fn date_at(time: DateTime<Local>, utc_offset: Option<FixedOffset>) -> NaiveDate {
+    match utc_offset {
+        Some(offset) => time.with_timezone(&offset).date_naive(),
+        None => time.date_naive(),
+    }
+}
+
+/// Keeps sessions whose start date (at `utc_offset`, see `date_at`) falls within the inclusive
+/// `since`..=`until` range. A missing bound leaves that side of the range open.
+pub fn sessions_in_range(
+    sessions: &[Session],
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+    utc_offset: Option<FixedOffset>,
+) -> Vec<Session> {
+    sessions
+        .iter()
+        .filter(|session| {
+            let day = date_at(session.start, utc_offset);
+            since.is_none_or(|since| day >= since) && until.is_none_or(|until| day <= until)
+        })
+        .cloned()
+        .collect()
+}
+
+/// Minutes played during each patch, bucketing sessions by the date they started at
+/// `utc_offset` (see `date_at`). `patches` may be in any order; the result is sorted by release
+/// date and starts at the patch that contains the first session. Sessions before the earliest
+/// patch are ignored.
+pub fn minutes_per_patch(
+    sessions: &[Session],
+    patches: &[PatchRelease],
+    utc_offset: Option<FixedOffset>,
+) -> Vec<(String, f64)> {
+    let mut patches: Vec<&PatchRelease> = patches.iter().collect();
+    patches.sort_by_key(|patch| patch.start);
+    let mut totals = vec![0.0; patches.len()];
+    let mut first_played = patches.len();
+    for session in sessions {
+        let day = date_at(session.start, utc_offset);
+        let Some(idx) = patches.iter().rposition(|patch| patch.start <= day) else {
+            continue;
+        };
+        totals[idx] += session.duration_minutes;
+        first_played = first_played.min(idx);
+    }
+    patches
+        .iter()
+        .zip(totals)
+        .skip(first_played)
+        .map(|(patch, minutes)| (patch.label.clone(), minutes))
+        .collect()
+}
+
+/// Consecutive days with a session, counting back from `today`; 0 when nothing was played today.
+/// Days are taken at `utc_offset` (see `date_at`).
+pub fn current_streak(
+    sessions: &[Session],
+    today: NaiveDate,
+    utc_offset: Option<FixedOffset>,
+) -> u32 {
+    let days: HashSet<NaiveDate> = sessions
+        .iter()
+        .map(|session| date_at(session.start, utc_offset))
+        .collect();
+    let mut streak = 0;
+    let mut cursor = Some(today);
+    while let Some(day) = cursor.filter(|day| days.contains(day)) {
+        streak += 1;
+        cursor = day.pred_opt();
+    }
+    streak
+}
+
+/// The longest run of consecutive days with a session and the day it ended on, with days taken
+/// at `utc_offset`. The earliest run wins a tie.
+pub fn longest_streak(
+    sessions: &[Session],
+    utc_offset: Option<FixedOffset>,
+) -> Option<(u32, NaiveDate)> {
+    let days: BTreeSet<NaiveDate> = sessions
+        .iter()
+        .map(|session| date_at(session.start, utc_offset))
+        .collect();
+    let mut best: Option<(u32, NaiveDate)> = None;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for day in days {
+        run = if previous.and_then(|previous| previous.succ_opt()) == Some(day) {
+            run + 1
+        } else {
+            1
+        };
+        if best.is_none_or(|(longest, _)| run > longest) {
+            best = Some((run, day));
+        }
+        previous = Some(day);
+    }
+    best
+}
+
+/// Minutes played on `day` (at `utc_offset`), counting only the part of each session that fell
+/// on it.
+pub fn minutes_on_day(
+    sessions: &[Session],
+    day: NaiveDate,
+    utc_offset: Option<FixedOffset>,
+) -> f64 {
+    let mut totals = BTreeMap::new();
+    for session in sessions.iter().filter(|session| {
+        date_at(session.start, utc_offset) <= day && date_at(session.end, utc_offset) >= day
+    }) {
+        match utc_offset {
+            Some(offset) => spread_over_days(
+                &mut totals,
+                &offset,
+                session.start,
+                session.end,
+                session.duration_minutes,
+            ),
+            None => spread_over_days(
+                &mut totals,
+                &Local,
+                session.start,
+                session.end,
+                session.duration_minutes,
+            ),
+        }
+    }
+    totals.get(&day).copied().unwrap_or(0.0)
+}
+
+/// Minutes of the session in progress that fell on `day` (at `utc_offset`), so a session that
+/// started before midnight only counts from midnight.
+pub fn active_minutes_on_day(
+    active: &ActiveSession,
+    day: NaiveDate,
+    utc_offset: Option<FixedOffset>,
+) -> f64 {
+    let session = Session::new(active.start, active.last_seen, String::new());
+    minutes_on_day(std::slice::from_ref(&session), day, utc_offset)
+}
+
+/// Splits `minutes` across the days (in `tz`) between `start` and `end`, in proportion to how
+/// much of the span fell on each day. Zero-length spans go to the start day.
+fn spread_over_days<Tz: TimeZone>(
+    totals: &mut BTreeMap<NaiveDate, f64>,
+    tz: &Tz,
+    start: DateTime<Local>,
+    end: DateTime<Local>,
+    minutes: f64,
+) where
+    Tz::Offset: Copy,
+{
+    let (start, end) = (start.with_timezone(tz), end.with_timezone(tz));
+    let span = (end - start).num_seconds();
+    if span <= 0 {
+        *totals.entry(start.date_naive()).or_default() += minutes;
+        return;
+    }
+    let mut cursor = start;
+    while cursor < end {
+        let day = cursor.date_naive();
+        // A midnight skipped by a DST change leaves the rest of the span on this day.
+        let next_midnight = day
+            .succ_opt()
+            .and_then(|next| {
+                tz.from_local_datetime(&next.and_time(NaiveTime::MIN))
+                    .earliest()
+            })
+            .filter(|midnight| *midnight > cursor)
+            .unwrap_or(end);
+        let segment_end = next_midnight.min(end);
+        let share = (segment_end - cursor).num_seconds() as f64 / span as f64;
+        *totals.entry(day).or_default() += minutes * share;
+        cursor = segment_end;
+    }
+}
+
+/// Adds the time between `start` and `end` to the hour-of-day buckets (in `tz`) it overlaps.
+fn spread_over_hours<Tz: TimeZone>(
+    buckets: &mut [f64; 24],
+    tz: &Tz,
+    start: DateTime<Local>,
+    end: DateTime<Local>,
+) where
+    Tz::Offset: Copy,
+{
+    let (mut cursor, end) = (start.with_timezone(tz), end.with_timezone(tz));
+    while cursor < end {
+        let into_hour = Duration::seconds(i64::from(cursor.minute() * 60 + cursor.second()))
+            + Duration::nanoseconds(i64::from(cursor.nanosecond().min(999_999_999)));
+        let next_hour = cursor - into_hour + Duration::hours(1);
+        let segment_end = next_hour.min(end);
+        buckets[cursor.hour() as usize] += (segment_end - cursor).num_seconds() as f64 / 60.0;
+        cursor = segment_end;
+    }
+}
+
+/// Minutes of `spans` inside the `days` × 24 hours before `now`.
+fn minutes_in_window(
+    spans: &[(DateTime<Local>, DateTime<Local>, f64)],
+    now: DateTime<Local>,
+    days: u32,
+) -> f64 {
+    if days == 0 {
+        return 0.0;
+    }
+    let since = now - Duration::days(i64::from(days));
+    minutes_between(spans, since, now)
+}
+
+/// Minutes of `spans` in the `days` × 24 hours that precede the `minutes_in_window` window.
+fn minutes_in_previous_window(
+    spans: &[(DateTime<Local>, DateTime<Local>, f64)],
+    now: DateTime<Local>,
+    days: u32,
+) -> f64 {
+    if days == 0 {
+        return 0.0;
+    }
+    let until = now - Duration::days(i64::from(days));
+    minutes_between(spans, until - Duration::days(i64::from(days)), until)
+}
+
+/// Midnight UTC on the first day of the month containing `now`.
+fn utc_month_start(now: DateTime<Utc>) -> DateTime<Utc> {
+    let first = now.date_naive().with_day(1).unwrap_or(now.date_naive());
+    first.and_time(NaiveTime::MIN).and_utc()
+}
+
+fn minutes_between(
+    spans: &[(DateTime<Local>, DateTime<Local>, f64)],
+    since: DateTime<Local>,
+    until: DateTime<Local>,
+) -> f64 {
+    spans
+        .iter()
+        .map(|(start, end, minutes)| overlap_minutes(*start, *end, *minutes, since, until))
+        .sum()
+}
+
+/// The share of `minutes`, spread evenly over `start..end`, that falls within `since..until`.
+fn overlap_minutes(
+    start: DateTime<Local>,
+    end: DateTime<Local>,
+    minutes: f64,
+    since: DateTime<Local>,
+    until: DateTime<Local>,
+) -> f64 {
+    let span = (end - start).num_seconds();
+    if span <= 0 {
+        return if (since..=until).contains(&start) {
+            minutes
+        } else {
+            0.0
+        };
+    }
+    let overlap = (end.min(until) - start.max(since)).num_seconds();
+    if overlap <= 0 {
+        0.0
+    } else {
+        minutes * overlap as f64 / span as f64
+    }
+}
+
 pub fn compute_analytics(sessions: &[Session]) -> Analytics {
-    use std::collections::BTreeMap;
+    compute_analytics_at(sessions, None)
+}
 
+/// `compute_analytics` with days, weekdays and hours taken at a fixed UTC offset instead of the
+/// system time zone, so travelling does not move the day boundaries. `None` uses local time.
+pub fn compute_analytics_at(sessions: &[Session], utc_offset: Option<FixedOffset>) -> Analytics {
+    let mut analytics = match utc_offset {
+        Some(offset) => analytics_in_zone(sessions, &offset),
+        None => analytics_in_zone(sessions, &Local),
+    };
+    analytics.utc_offset = utc_offset;
+    analytics
+}
+
+fn analytics_in_zone<Tz: TimeZone>(sessions: &[Session], tz: &Tz) -> Analytics
+where
+    Tz::Offset: Copy,
+{
     let total_sessions = sessions.len();
     let total_minutes: f64 = sessions.iter().map(|s| s.duration_minutes).sum();
     let average_session_minutes = if total_sessions == 0 {
@@ -208,8 +1331,13 @@ pub fn compute_analytics(sessions: &[Session]) -> Analytics {
 
     let mut daily_totals: BTreeMap<NaiveDate, f64> = BTreeMap::new();
     for session in sessions {
-        let day = session.start.date_naive();
-        *daily_totals.entry(day).or_default() += session.duration_minutes;
+        spread_over_days(
+            &mut daily_totals,
+            tz,
+            session.start,
+            session.end,
+            session.duration_minutes,
+        );
     }
 
     let mut weekly_totals: BTreeMap<(i32, u32), f64> = BTreeMap::new();
@@ -220,12 +1348,34 @@ pub fn compute_analytics(sessions: &[Session]) -> Analytics {
             .or_default() += minutes;
     }
 
+    let mut weekly_counts: BTreeMap<(i32, u32), (usize, f64)> = BTreeMap::new();
+    for session in sessions {
+        let iso_week = session.start.with_timezone(tz).date_naive().iso_week();
+        let (count, minutes) = weekly_counts
+            .entry((iso_week.year(), iso_week.week()))
+            .or_default();
+        *count += 1;
+        *minutes += session.duration_minutes;
+    }
+
     let recent_sessions = {
         let mut list = sessions.to_vec();
         list.sort_by_key(|s| s.start);
         list.into_iter().rev().take(20).collect::<Vec<_>>()
     };
 
+    let longest_sessions = {
+        let mut list = sessions.to_vec();
+        list.sort_by(|a, b| {
+            b.duration_minutes
+                .partial_cmp(&a.duration_minutes)
+                .unwrap()
+                .then_with(|| b.start.cmp(&a.start))
+        });
+        list.truncate(5);
+        list
+    };
+
     let mut top_days = daily_totals
         .iter()
         .map(|(day, minutes)| (*day, *minutes))
@@ -233,31 +1383,76 @@ pub fn compute_analytics(sessions: &[Session]) -> Analytics {
     top_days.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
     top_days.truncate(5);
 
-    let recent_daily = daily_totals
+    let recent_daily = recent_days(&daily_totals);
+
+    let recent_weekly = weekly_totals
         .iter()
         .rev()
-        .map(|(day, minutes)| (*day, *minutes))
-        .take(14)
+        .map(|(week, minutes)| (*week, *minutes))
+        .take(8)
         .collect();
 
-    let recent_weekly = weekly_totals
+    // Counts and minutes are both keyed by start week, so a week only reached by a session
+    // running past midnight on Sunday does not show up as a zero average.
+    let avg_session_by_week = weekly_counts
         .iter()
         .rev()
-        .map(|(week, minutes)| (*week, *minutes))
         .take(8)
+        .map(|(week, (count, minutes))| (*week, minutes / (*count).max(1) as f64))
         .collect();
 
-    let today = Local::now().date_naive();
-    let minutes_last_7: f64 = daily_totals
+    let mut tag_map: BTreeMap<String, f64> = BTreeMap::new();
+    for session in sessions {
+        if session.tags.is_empty() {
+            *tag_map.entry(UNTAGGED_LABEL.to_string()).or_default() += session.duration_minutes;
+        }
+        for tag in &session.tags {
+            *tag_map.entry(tag.clone()).or_default() += session.duration_minutes;
+        }
+    }
+    let mut tag_totals = tag_map.into_iter().collect::<Vec<_>>();
+    tag_totals.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+
+    let mut play_by_hour = [0.0; 24];
+    let mut weekday_totals = [0.0; 7];
+    for session in sessions {
+        spread_over_hours(&mut play_by_hour, tz, session.start, session.end);
+    }
+    for (day, minutes) in &daily_totals {
+        weekday_totals[day.weekday().num_days_from_monday() as usize] += minutes;
+    }
+
+    let mut bucket_counts = [0usize; DURATION_BUCKETS.len()];
+    for session in sessions {
+        let bucket = DURATION_BUCKETS
+            .iter()
+            .position(|(_, upper)| session.duration_minutes < *upper)
+            .unwrap_or(DURATION_BUCKETS.len() - 1);
+        bucket_counts[bucket] += 1;
+    }
+    let duration_histogram = DURATION_BUCKETS
         .iter()
-        .filter(|(day, _)| **day >= today - Duration::days(6))
-        .map(|(_, minutes)| *minutes)
-        .sum();
-    let minutes_last_30: f64 = daily_totals
+        .zip(bucket_counts)
+        .map(|((label, _), count)| (label.to_string(), count))
+        .collect();
+
+    let mut game_map: BTreeMap<String, f64> = BTreeMap::new();
+    for session in sessions {
+        let game = session.game.as_deref().unwrap_or(DEFAULT_GAME_LABEL);
+        *game_map.entry(game.to_string()).or_default() += session.duration_minutes;
+    }
+    let mut game_totals = game_map.into_iter().collect::<Vec<_>>();
+    game_totals.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+
+    let spans = sessions
         .iter()
-        .filter(|(day, _)| **day >= today - Duration::days(29))
-        .map(|(_, minutes)| *minutes)
-        .sum();
+        .map(|session| (session.start, session.end, session.duration_minutes))
+        .collect::<Vec<_>>();
+    let now = Local::now();
+    let minutes_last_7 = minutes_in_window(&spans, now, 7);
+    let minutes_last_30 = minutes_in_window(&spans, now, 30);
+    let minutes_prev_7 = minutes_in_previous_window(&spans, now, 7);
+    let minutes_prev_30 = minutes_in_previous_window(&spans, now, 30);
 
     let first_day = daily_totals.keys().next().copied();
     let last_day = daily_totals.keys().next_back().copied();
@@ -269,11 +1464,237 @@ pub fn compute_analytics(sessions: &[Session]) -> Analytics {
         median_session_minutes,
         minutes_last_7,
         minutes_last_30,
+        minutes_prev_7,
+        minutes_prev_30,
         top_days,
         recent_sessions,
+        longest_sessions,
         recent_daily,
         recent_weekly,
+        avg_session_by_week,
+        tag_totals,
+        game_totals,
+        play_by_hour,
+        weekday_totals,
+        duration_histogram,
         first_day,
         last_day,
+        daily_totals,
+        spans,
+        active_minutes: None,
+        utc_offset: None,
+    }
+}
+
+/// The last 14 days with playtime, most recent first.
+fn recent_days(daily_totals: &BTreeMap<NaiveDate, f64>) -> Vec<(NaiveDate, f64)> {
+    daily_totals
+        .iter()
+        .rev()
+        .map(|(day, minutes)| (*day, *minutes))
+        .take(14)
+        .collect()
+}
+
+/// Helpers shared by the test modules of this crate.
+#[cfg(test)]
+pub(crate) mod test_support {
+    use std::ops::Deref;
+
+    use super::*;
+
+    /// Parses `YYYY-MM-DD HH:MM` (or RFC 3339) as local time.
+    pub(crate) fn local(input: &str) -> DateTime<Local> {
+        parse_local_datetime(input).unwrap()
+    }
+
+    /// A store in a fresh directory under the system temp dir, removed again on drop so a
+    /// failing assertion does not leave it behind.
+    pub(crate) struct TempStore(SessionStore);
+
+    impl TempStore {
+        pub(crate) fn new() -> Self {
+            let dir = std::env::temp_dir().join(format!("sc-playtime-test-{}", Uuid::new_v4()));
+            Self(SessionStore::with_dir(dir).unwrap())
+        }
+    }
+
+    impl Deref for TempStore {
+        type Target = SessionStore;
+
+        fn deref(&self) -> &SessionStore {
+            &self.0
+        }
+    }
+
+    impl Drop for TempStore {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(self.0.data_dir());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_support::{TempStore, local};
+    use super::*;
+
+    fn session(start: &str, end: &str) -> Session {
+        Session::new(local(start), local(end), String::new())
+    }
+
+    /// `hh:mm` on the given March 2024 day in UTC.
+    fn utc(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        let utc = FixedOffset::east_opt(0).unwrap();
+        utc.with_ymd_and_hms(2024, 3, day, hour, minute, 0)
+            .unwrap()
+            .with_timezone(&Local)
+    }
+
+    #[test]
+    fn session_across_midnight_is_split_between_days() {
+        let tz = FixedOffset::east_opt(0).unwrap();
+        let mut totals = BTreeMap::new();
+        spread_over_days(&mut totals, &tz, utc(10, 23, 30), utc(11, 1, 0), 90.0);
+        let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        assert_eq!(totals.get(&day(10)), Some(&30.0));
+        assert_eq!(totals.get(&day(11)), Some(&60.0));
+        assert_eq!(totals.values().sum::<f64>(), 90.0);
+    }
+
+    #[test]
+    fn active_session_counts_only_from_midnight() {
+        let mut active = ActiveSession::new(utc(10, 23, 0));
+        active.last_seen = utc(11, 1, 0);
+        let utc_offset = FixedOffset::east_opt(0);
+        let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        assert_eq!(active_minutes_on_day(&active, day(11), utc_offset), 60.0);
+        assert_eq!(active_minutes_on_day(&active, day(10), utc_offset), 60.0);
+        assert_eq!(active_minutes_on_day(&active, day(12), utc_offset), 0.0);
+    }
+
+    #[test]
+    fn session_across_midnight_is_split_between_hours() {
+        let tz = FixedOffset::east_opt(0).unwrap();
+        let mut buckets = [0.0; 24];
+        spread_over_hours(&mut buckets, &tz, utc(10, 23, 30), utc(11, 1, 0));
+        assert_eq!(buckets[23], 30.0);
+        assert_eq!(buckets[0], 60.0);
+        assert_eq!(buckets.iter().sum::<f64>(), 90.0);
+    }
+
+    #[test]
+    fn minutes_in_window_counts_only_the_overlap() {
+        let now = utc(10, 12, 0);
+        // Two hours ending one hour into the 24 hour window.
+        let partly_inside = [(utc(9, 11, 0), utc(9, 13, 0), 120.0)];
+        assert_eq!(minutes_in_window(&partly_inside, now, 1), 60.0);
+        let outside = [(utc(8, 10, 0), utc(8, 12, 0), 120.0)];
+        assert_eq!(minutes_in_window(&outside, now, 1), 0.0);
+        let spanning = [(utc(9, 0, 0), utc(10, 18, 0), 2520.0)];
+        assert_eq!(minutes_in_window(&spanning, now, 1), 1440.0);
+    }
+
+    #[test]
+    fn overlap_minutes_scales_by_the_share_inside() {
+        let (since, until) = (utc(10, 10, 0), utc(10, 11, 0));
+        assert_eq!(
+            overlap_minutes(utc(10, 10, 30), utc(10, 11, 30), 60.0, since, until),
+            30.0
+        );
+        assert_eq!(
+            overlap_minutes(utc(10, 12, 0), utc(10, 13, 0), 60.0, since, until),
+            0.0
+        );
+        assert_eq!(
+            overlap_minutes(utc(10, 9, 0), utc(10, 13, 0), 240.0, since, until),
+            60.0
+        );
+    }
+
+    #[test]
+    fn weekly_average_lists_only_weeks_with_session_starts() {
+        // Sunday 23:00 to Monday 01:00 puts minutes in two ISO weeks.
+        let late = Session::new(utc(10, 23, 0), utc(11, 1, 0), String::new());
+        let analytics = compute_analytics_at(&[late], FixedOffset::east_opt(0));
+        assert_eq!(analytics.recent_weekly.len(), 2);
+        assert_eq!(analytics.avg_session_by_week, [((2024, 10), 120.0)]);
+    }
+
+    #[test]
+    fn csv_note_with_comma_quote_and_newline_round_trips() {
+        let note = "boarded, then \"fun\"\nsecond line";
+        let line = format!("{},{}\n", quote_csv_field("id"), quote_csv_field(note));
+        assert_eq!(
+            parse_csv_records(&line),
+            vec![vec!["id".to_string(), note.to_string()]]
+        );
+    }
+
+    #[test]
+    fn failed_atomic_write_keeps_the_previous_file() {
+        let store = TempStore::new();
+        let path = store.data_dir().join("sessions.json");
+        write_atomic(&path, "previous").unwrap();
+        let result = write_atomic_with(&path, |file| {
+            file.write_all(b"part")?;
+            Err(anyhow::anyhow!("disk full"))
+        });
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "previous");
+        let entries = fs::read_dir(store.data_dir())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect::<Vec<_>>();
+        assert_eq!(entries, vec![std::ffi::OsString::from("sessions.json")]);
+    }
+
+    #[test]
+    fn append_or_merge_session_merges_within_the_gap() {
+        let store = TempStore::new();
+        store
+            .append_session(session("2024-03-10 10:00", "2024-03-10 11:00"))
+            .unwrap();
+        let (merged, was_merged) = store
+            .append_or_merge_session(
+                session("2024-03-10 11:10", "2024-03-10 11:40"),
+                Duration::minutes(15),
+            )
+            .unwrap();
+        assert!(was_merged);
+        assert_eq!(merged.start, local("2024-03-10 10:00"));
+        assert_eq!(merged.end, local("2024-03-10 11:40"));
+        // The 10 minute gap is not playtime.
+        assert_eq!(merged.duration_minutes, 90.0);
+        assert_eq!(store.load_sessions().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn append_or_merge_session_appends_outside_the_gap() {
+        let store = TempStore::new();
+        store
+            .append_session(session("2024-03-10 10:00", "2024-03-10 11:00"))
+            .unwrap();
+        let (stored, was_merged) = store
+            .append_or_merge_session(
+                session("2024-03-10 11:10", "2024-03-10 11:40"),
+                Duration::minutes(5),
+            )
+            .unwrap();
+        assert!(!was_merged);
+        assert_eq!(stored.duration_minutes, 30.0);
+        assert_eq!(store.load_sessions().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn active_session_is_stale_only_past_the_threshold() {
+        let session = ActiveSession::new(local("2024-03-10 12:00"));
+        let at_threshold = session.last_seen + Duration::minutes(30);
+        assert!(!active_session_is_stale(&session, at_threshold, 30));
+        assert!(active_session_is_stale(
+            &session,
+            at_threshold + Duration::seconds(1),
+            30
+        ));
     }
 }
@@ -0,0 +1,79 @@
+use std::{
+    ffi::OsStr,
+    path::Path,
+    sync::mpsc::{self, Receiver},
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+/// Files whose changes should make a viewer reload its sessions.
+const WATCHED_FILES: [&str; 2] = ["sessions.json", "active_session.json"];
+
+/// Quiet period after the last change before a reload is reported; the monitor writes the
+/// session list and clears the active marker back to back.
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Watches the data directory for writes to `sessions.json` and `active_session.json`, for
+/// example by a CLI monitor running in another process. The directory is watched rather than
+/// the files because atomic writes replace them. Watching stops when the value is dropped.
+pub struct DataWatcher {
+    _watcher: RecommendedWatcher,
+    rx: Receiver<()>,
+    last_change: Option<Instant>,
+}
+
+impl DataWatcher {
+    /// Starts watching `data_dir`. `on_change` runs on the watcher thread for every relevant
+    /// event, which lets a GUI request a repaint.
+    pub fn start(data_dir: &Path, on_change: impl Fn() + Send + 'static) -> Result<Self> {
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |result: notify::Result<Event>| {
+            let Ok(event) = result else {
+                return;
+            };
+            // Reads show up as access events; ignoring them keeps our own reloads quiet.
+            if matches!(event.kind, EventKind::Access(_)) {
+                return;
+            }
+            let relevant = event.paths.iter().any(|path| {
+                path.file_name()
+                    .and_then(OsStr::to_str)
+                    .is_some_and(|name| WATCHED_FILES.contains(&name))
+            });
+            if relevant && tx.send(()).is_ok() {
+                on_change();
+            }
+        })
+        .context("Failed to create file watcher")?;
+        watcher
+            .watch(data_dir, RecursiveMode::NonRecursive)
+            .with_context(|| format!("Failed to watch {}", data_dir.display()))?;
+        Ok(Self {
+            _watcher: watcher,
+            rx,
+            last_change: None,
+        })
+    }
+
+    /// Drains pending change notifications. Returns true once changes were seen and none has
+    /// arrived for `debounce`, so a burst of writes results in a single reload.
+    pub fn poll(&mut self, debounce: Duration) -> bool {
+        while self.rx.try_recv().is_ok() {
+            self.last_change = Some(Instant::now());
+        }
+        match self.last_change {
+            Some(at) if at.elapsed() >= debounce => {
+                self.last_change = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether a reload is waiting for the debounce period to pass.
+    pub fn is_pending(&self) -> bool {
+        self.last_change.is_some()
+    }
+}